@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 use sha1_smol::Digest;
@@ -25,5 +25,5 @@ pub struct ChunkedDifResponse {
     pub dif: Option<DebugInfoFile>,
 }
 
-pub type AssembleDifsRequest<'a> = HashMap<Digest, ChunkedDifRequest<'a>>;
+pub type AssembleDifsRequest<'a> = BTreeMap<Digest, ChunkedDifRequest<'a>>;
 pub type AssembleDifsResponse = HashMap<Digest, ChunkedDifResponse>;
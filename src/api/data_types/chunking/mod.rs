@@ -10,7 +10,9 @@ mod upload;
 
 pub use self::artifact::{AssembleArtifactsResponse, ChunkedArtifactRequest};
 pub use self::compression::ChunkCompression;
-pub use self::dif::{AssembleDifsRequest, AssembleDifsResponse, ChunkedDifRequest};
+pub use self::dif::{
+    AssembleDifsRequest, AssembleDifsResponse, ChunkedDifRequest, ChunkedDifResponse,
+};
 pub use self::file_state::ChunkedFileState;
 pub use self::hash_algorithm::ChunkHashAlgorithm;
 pub use self::upload::{ChunkUploadCapability, ChunkUploadOptions};
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use super::SentryError;
+
 #[derive(Debug, thiserror::Error)]
 pub struct ApiError {
     inner: ApiErrorKind,
@@ -18,6 +20,10 @@ pub(in crate::api) enum ApiErrorKind {
     BadJson,
     #[error("not a JSON response")]
     NotJson,
+    #[error(
+        "received an HTML page instead of API JSON — your auth proxy session may have expired"
+    )]
+    HtmlResponse,
     #[error("request failed because API URL was incorrectly formatted")]
     BadApiUrl,
     #[error("organization not found")]
@@ -32,6 +38,13 @@ pub(in crate::api) enum ApiErrorKind {
     ChunkUploadNotSupported,
     #[error("API request failed")]
     RequestFailed,
+    #[error(
+        "could not connect to the Sentry server. Check the configured URL, or use \
+         --connect-timeout to wait longer."
+    )]
+    ServerUnreachable,
+    #[error("too many redirects. Use --max-redirects to follow more redirects.")]
+    TooManyRedirects,
     #[error("could not compress data")]
     CompressionFailed,
     #[error("region overrides cannot be applied to absolute urls")]
@@ -46,6 +59,8 @@ pub(in crate::api) enum ApiErrorKind {
     DsnMissing,
     #[error("Error preparing request")]
     ErrorPreparingRequest,
+    #[error("The server does not support the requested API version. Use --api-version to target a different version.")]
+    UnsupportedApiVersion,
 }
 
 impl fmt::Display for ApiError {
@@ -69,6 +84,40 @@ impl ApiError {
         self.inner
     }
 
+    /// Returns the HTTP status code if the server responded with an error.
+    pub fn http_status(&self) -> Option<u32> {
+        self.sentry_error().map(|err| err.status)
+    }
+
+    /// Returns the id the server assigned to the failed request.
+    pub fn request_id(&self) -> Option<&str> {
+        self.sentry_error()?.request_id.as_deref()
+    }
+
+    /// Returns the JSON body of the server's error response.
+    pub fn server_response(&self) -> Option<&serde_json::Value> {
+        self.sentry_error()?.response.as_ref()
+    }
+
+    fn sentry_error(&self) -> Option<&SentryError> {
+        self.source.as_ref()?.downcast_ref()
+    }
+
+    /// Returns the curl error if the server could not be connected to.
+    pub(in crate::api) fn connect_error(&self) -> Option<&curl::Error> {
+        if self.inner != ApiErrorKind::RequestFailed {
+            return None;
+        }
+        self.source
+            .as_ref()?
+            .downcast_ref::<curl::Error>()
+            .filter(|err| {
+                err.is_couldnt_connect()
+                    || err.is_couldnt_resolve_host()
+                    || err.is_couldnt_resolve_proxy()
+            })
+    }
+
     fn set_source<E: Into<anyhow::Error>>(mut self, source: E) -> ApiError {
         self.source = Some(source.into());
         self
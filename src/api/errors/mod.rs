@@ -1,12 +1,25 @@
 mod api_error;
 mod sentry_error;
 
-pub(super) use api_error::{ApiError, ApiErrorKind};
+pub(crate) use api_error::ApiError;
+pub(super) use api_error::ApiErrorKind;
 pub(super) use sentry_error::SentryError;
 
 #[derive(Clone, Debug, thiserror::Error)]
 #[error("project was renamed to '{0}'\nPlease use this slug in your .sentryclirc file, sentry.properties file or in the CLI --project parameter")]
 pub(super) struct ProjectRenamedError(pub(super) String);
 
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("redirect chain: {}", .0.join(" -> "))]
+pub(super) struct RedirectChainError(pub(super) Vec<String>);
+
+#[derive(Debug, thiserror::Error)]
+#[error("could not reach {url}")]
+pub(super) struct UnreachableError {
+    pub(super) url: String,
+    #[source]
+    pub(super) source: curl::Error,
+}
+
 /// Shortcut alias for results of this module.
 pub(super) type ApiResult<T> = Result<T, ApiError>;
@@ -5,6 +5,10 @@ pub(in crate::api) struct SentryError {
     pub(in crate::api) status: u32,
     pub(in crate::api) detail: Option<String>,
     pub(in crate::api) extra: Option<serde_json::Value>,
+    /// The id the server assigned to the request, for support inquiries.
+    pub(in crate::api) request_id: Option<String>,
+    /// The JSON body of the error response.
+    pub(in crate::api) response: Option<serde_json::Value>,
 }
 
 impl fmt::Display for SentryError {
@@ -9,10 +9,12 @@ mod connection_manager;
 mod data_types;
 mod encoding;
 mod errors;
+mod multipart;
 mod pagination;
+mod request_dump;
 
 use std::borrow::Cow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fmt;
@@ -20,19 +22,19 @@ use std::fs::File;
 use std::io::{self, Read, Write};
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use backoff::backoff::Backoff;
 use brotli2::write::BrotliEncoder;
-#[cfg(target_os = "macos")]
-use chrono::Duration;
 use chrono::{DateTime, FixedOffset, Utc};
 use clap::ArgMatches;
 use flate2::write::GzEncoder;
 use if_chain::if_chain;
 use lazy_static::lazy_static;
-use log::{debug, info, warn};
+use log::{debug, info, trace, warn};
 use parking_lot::Mutex;
 use regex::{Captures, Regex};
 use secrecy::ExposeSecret;
@@ -44,22 +46,44 @@ use symbolic::common::DebugId;
 use symbolic::debuginfo::ObjectKind;
 use uuid::Uuid;
 
-use crate::api::errors::ProjectRenamedError;
+use crate::api::errors::{ProjectRenamedError, RedirectChainError, UnreachableError};
 use crate::config::{Auth, Config};
 use crate::constants::{ARCH, DEFAULT_URL, EXT, PLATFORM, RELEASE_REGISTRY_LATEST_URL, VERSION};
+use crate::utils::chunks::UploadProtocol;
 use crate::utils::file_upload::UploadContext;
 use crate::utils::http::{self, is_absolute_url};
+use crate::utils::metrics_summary;
 use crate::utils::progress::{ProgressBar, ProgressBarMode};
+use crate::utils::rate_limit::RateLimiter;
 use crate::utils::retry::{get_default_backoff, DurationAsMilliseconds};
 use crate::utils::sourcemaps::get_sourcemap_reference_from_headers;
 use crate::utils::ui::{capitalize_string, make_byte_progress_bar};
 
 use self::pagination::Pagination;
+use self::request_dump::RequestDump;
 use connection_manager::CurlConnectionManager;
 use encoding::{PathArg, QueryArg};
-use errors::{ApiError, ApiErrorKind, ApiResult, SentryError};
+use errors::{ApiErrorKind, ApiResult, SentryError};
+
+pub(crate) use self::errors::ApiError;
 
 pub use self::data_types::*;
+pub use self::multipart::{MultipartBoundaryCharset, MultipartForm};
+
+/// The largest page size the server accepts for paginated listings.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// The longest a rate limited request waits before it is retried, regardless
+/// of the `Retry-After` header.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Response headers that carry the id the server assigned to a request.
+const REQUEST_ID_HEADERS: &[&str] = &["x-sentry-request-id", "x-request-id"];
+
+/// Number of connections opened over the lifetime of the process.
+static NEW_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+/// Number of times an existing connection was reused for a request.
+static REUSED_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
 
 lazy_static! {
     static ref API: Mutex<Option<Arc<Api>>> = Mutex::new(None);
@@ -71,6 +95,7 @@ lazy_static! {
 pub struct Api {
     config: Arc<Config>,
     pool: r2d2::Pool<CurlConnectionManager>,
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 /// Wrapper for Api that ensures Auth is provided. AuthenticatedApi provides implementations of high-level
@@ -86,7 +111,7 @@ pub struct RegionSpecificApi<'a> {
 }
 
 /// Represents an HTTP method that is used by the API.
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum Method {
     Get,
     Post,
@@ -109,12 +134,20 @@ impl fmt::Display for Method {
 /// sending but only sent once.
 pub struct ApiRequest {
     handle: r2d2::PooledConnection<CurlConnectionManager>,
+    method: Method,
+    url: String,
     headers: curl::easy::List,
     is_authenticated: bool,
     body: Option<Vec<u8>>,
     progress_bar_mode: ProgressBarMode,
     max_retries: u32,
     retry_on_statuses: &'static [u32],
+    retry_idempotent: bool,
+    max_redirects: u32,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// How often a request rejected with status 429 is retried.
+    max_rate_limited_retries: u32,
+    dump: Option<RequestDump>,
 }
 
 /// Represents an API response.
@@ -123,6 +156,7 @@ pub struct ApiResponse {
     status: u32,
     headers: Vec<String>,
     body: Option<Vec<u8>>,
+    url: Option<String>,
 }
 
 impl<'a> TryFrom<&'a Api> for AuthenticatedApi<'a> {
@@ -151,12 +185,17 @@ impl Api {
 
     /// Similar to `new` but uses a specific config.
     pub fn with_config(config: Arc<Config>) -> Api {
+        let rate_limiter = config.get_rate_limit().map(|per_second| {
+            info!("limiting requests to {per_second} per second");
+            Arc::new(RateLimiter::new(per_second))
+        });
         Api {
             config,
             pool: r2d2::Pool::builder()
                 .max_size(16)
                 .build(CurlConnectionManager)
                 .unwrap(),
+            rate_limiter,
         }
     }
 
@@ -182,8 +221,15 @@ impl Api {
         url: &str,
         region_url: Option<&str>,
     ) -> ApiResult<ApiRequest> {
-        let (url, auth) = self.resolve_base_url_and_auth(url, region_url)?;
-        self.construct_api_request(method, &url, auth)
+        let (resolved_url, auth) = self.resolve_base_url_and_auth(url, region_url)?;
+        let request = self.construct_api_request(method, &resolved_url, auth)?;
+
+        // Only requests against the Sentry API negotiate the API version.
+        if is_absolute_url(url) {
+            Ok(request)
+        } else {
+            request.with_header("Accept", &self.config.get_api_accept_header())
+        }
     }
 
     fn resolve_base_url_and_auth(
@@ -220,6 +266,13 @@ impl Api {
         handle.reset();
         if !self.config.allow_keepalive() {
             handle.forbid_reuse(true).ok();
+        } else if let Some(timeout) = self.config.get_keepalive_timeout() {
+            handle.maxage_conn(timeout)?;
+            handle.tcp_keepalive(true)?;
+            handle.tcp_keepidle(timeout)?;
+            if let Some(interval) = self.config.get_keepalive_interval() {
+                handle.tcp_keepintvl(interval)?;
+            }
         }
         let mut ssl_opts = curl::easy::SslOpt::new();
         if self.config.disable_ssl_revocation_check() {
@@ -231,10 +284,10 @@ impl Api {
             handle.proxy(&proxy_url)?;
         }
         if let Some(proxy_username) = self.config.get_proxy_username() {
-            handle.proxy_username(proxy_username)?;
+            handle.proxy_username(&proxy_username)?;
         }
         if let Some(proxy_password) = self.config.get_proxy_password() {
-            handle.proxy_password(proxy_password)?;
+            handle.proxy_password(&proxy_password)?;
         }
         handle.ssl_verify_host(self.config.should_verify_ssl())?;
         handle.ssl_verify_peer(self.config.should_verify_ssl())?;
@@ -245,7 +298,22 @@ impl Api {
         let env = self.config.get_pipeline_env();
         let headers = self.config.get_headers();
 
-        ApiRequest::create(handle, &method, url, auth, env, headers)
+        let mut request = ApiRequest::create(handle, &method, url, auth, env, headers)?;
+        request.max_redirects = self.config.get_max_redirects();
+        if let Some(ref rate_limiter) = self.rate_limiter {
+            request.rate_limiter = Some(rate_limiter.clone());
+            request.max_rate_limited_retries =
+                self.config.get_max_retry_count().unwrap_or_default();
+        }
+        if let Some(dir) = self.config.get_dump_request_bodies_dir() {
+            request.dump = Some(RequestDump::new(
+                &dir,
+                self.config.dump_full_request_bodies(),
+                &method,
+                url,
+            ));
+        }
+        Ok(request)
     }
 
     /// Convenience method that performs a `GET` request.
@@ -274,9 +342,7 @@ impl Api {
 
     /// Convenience method that downloads a file into the given file object.
     pub fn download(&self, url: &str, dst: &mut File) -> ApiResult<ApiResponse> {
-        self.request(Method::Get, url, None)?
-            .follow_location(true)?
-            .send_into(dst)
+        self.request(Method::Get, url, None)?.send_into(dst)
     }
 
     /// Convenience method that downloads a file into the given file object
@@ -284,7 +350,6 @@ impl Api {
     #[cfg(not(feature = "managed"))]
     pub fn download_with_progress(&self, url: &str, dst: &mut File) -> ApiResult<ApiResponse> {
         self.request(Method::Get, url, None)?
-            .follow_location(true)?
             .progress_bar_mode(ProgressBarMode::Response)?
             .send_into(dst)
     }
@@ -292,7 +357,7 @@ impl Api {
     /// Convenience method that waits for a few seconds until a resource
     /// becomes available. We only use this in the macOS binary.
     #[cfg(target_os = "macos")]
-    pub fn wait_until_available(&self, url: &str, duration: Duration) -> ApiResult<bool> {
+    pub fn wait_until_available(&self, url: &str, duration: chrono::Duration) -> ApiResult<bool> {
         let started = Utc::now();
         loop {
             match self.request(Method::Get, url, None)?.send() {
@@ -303,7 +368,7 @@ impl Api {
                     }
                 }
             }
-            std::thread::sleep(Duration::milliseconds(500).to_std().unwrap());
+            std::thread::sleep(Duration::from_millis(500));
             if Utc::now() - duration > started {
                 return Ok(false);
             }
@@ -376,28 +441,19 @@ impl Api {
         I: IntoIterator<Item = &'data T>,
         T: AsRef<(Digest, &'data [u8])> + 'data,
     {
-        // Curl stores a raw pointer to the stringified checksum internally. We first
-        // transform all checksums to string and keep them in scope until the request
-        // has completed. The original iterator is not needed anymore after this.
-        let stringified_chunks: Vec<_> = chunks
-            .into_iter()
-            .map(T::as_ref)
-            .map(|&(checksum, data)| (checksum.to_string(), data))
-            .collect();
-
-        let mut form = curl::easy::Form::new();
-        for (ref checksum, data) in stringified_chunks {
+        let mut form = MultipartForm::new();
+        for &(checksum, data) in chunks.into_iter().map(T::as_ref) {
             let name = compression.field_name();
             let buffer = Api::compress(data, compression)
                 .map_err(|err| ApiError::with_source(ApiErrorKind::CompressionFailed, err))?;
-            form.part(name).buffer(&checksum, buffer).add()?
+            form.buffer(name, &checksum.to_string(), buffer);
         }
 
         let request = self
             .request(Method::Post, url, None)?
-            .with_form_data(form)?
+            .with_form_data(form, &self.config)?
             .with_retry(
-                self.config.get_max_retry_count().unwrap(),
+                self.config.get_upload_max_retries().unwrap(),
                 &[
                     http::HTTP_STATUS_502_BAD_GATEWAY,
                     http::HTTP_STATUS_503_SERVICE_UNAVAILABLE,
@@ -795,16 +851,68 @@ impl<'a> AuthenticatedApi<'a> {
 
     /// Returns a list of releases for a given project.  This is currently a
     /// capped list by what the server deems an acceptable default limit.
-    pub fn list_releases(&self, org: &str, project: Option<&str>) -> ApiResult<Vec<ReleaseInfo>> {
-        if let Some(project) = project {
-            let path = format!("/projects/{}/{}/releases/", PathArg(org), PathArg(project));
-            self.get(&path)?
-                .convert_rnf::<Vec<ReleaseInfo>>(ApiErrorKind::ProjectNotFound)
-        } else {
-            let path = format!("/organizations/{}/releases/", PathArg(org));
-            self.get(&path)?
-                .convert_rnf::<Vec<ReleaseInfo>>(ApiErrorKind::OrganizationNotFound)
+    ///
+    /// If `sort` is given, it is passed to the server as sort order, and
+    /// `per_page` overrides the server's default limit.  After every page,
+    /// `more` decides whether to fetch the next page as well.
+    pub fn list_releases(
+        &self,
+        org: &str,
+        project: Option<&str>,
+        sort: Option<&str>,
+        per_page: Option<usize>,
+        mut more: impl FnMut(&[ReleaseInfo]) -> bool,
+    ) -> ApiResult<Vec<ReleaseInfo>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let mut query = vec![];
+            if let Some(sort) = sort {
+                query.push(format!("sort={}", QueryArg(sort)));
+            }
+            if let Some(per_page) = per_page {
+                query.push(format!("per_page={per_page}"));
+            }
+            if !cursor.is_empty() {
+                query.push(format!("cursor={}", QueryArg(&cursor)));
+            }
+            let query = if query.is_empty() {
+                String::new()
+            } else {
+                format!("?{}", query.join("&"))
+            };
+            let (path, not_found) = if let Some(project) = project {
+                (
+                    format!(
+                        "/projects/{}/{}/releases/{}",
+                        PathArg(org),
+                        PathArg(project),
+                        query
+                    ),
+                    ApiErrorKind::ProjectNotFound,
+                )
+            } else {
+                (
+                    format!("/organizations/{}/releases/{}", PathArg(org), query),
+                    ApiErrorKind::OrganizationNotFound,
+                )
+            };
+
+            let resp = self.get(&path)?;
+            if resp.status() == 400 && !cursor.is_empty() {
+                break;
+            }
+
+            let pagination = resp.pagination();
+            let page = resp.convert_rnf::<Vec<ReleaseInfo>>(not_found)?;
+            let fetch_next = more(&page);
+            rv.extend(page);
+            match pagination.into_next_cursor() {
+                Some(next) if fetch_next => cursor = next,
+                _ => break,
+            }
         }
+        Ok(rv)
     }
 
     /// Looks up a release commits and returns it.  If it does not exist `None`
@@ -935,11 +1043,33 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(state.missing)
     }
 
+    /// Returns the debug information files of a project with the given debug id.
+    pub fn list_difs_by_debug_id(
+        &self,
+        org: &str,
+        project: &str,
+        debug_id: DebugId,
+    ) -> ApiResult<Vec<DebugInfoFile>> {
+        let path = format!(
+            "/projects/{}/{}/files/dsyms/?debug_id={}",
+            PathArg(org),
+            PathArg(project),
+            QueryArg(&debug_id.to_string())
+        );
+        self.get(&path)?.convert_rnf(ApiErrorKind::ProjectNotFound)
+    }
+
     /// Get the server configuration for chunked file uploads.
     pub fn get_chunk_upload_options(&self, org: &str) -> ApiResult<Option<ChunkUploadOptions>> {
+        if self.api.config.get_upload_protocol() == UploadProtocol::Legacy {
+            debug!("ignoring chunk upload options, legacy upload is forced");
+            return Ok(None);
+        }
+
         let url = format!("/organizations/{}/chunk-upload/", PathArg(org));
         match self
-            .get(&url)?
+            .request(Method::Get, &url)?
+            .send_preflight(self.api.config.get_preflight_connect_timeout())?
             .convert_rnf::<ChunkUploadOptions>(ApiErrorKind::ChunkUploadNotSupported)
         {
             Ok(options) => Ok(Some(options)),
@@ -976,6 +1106,7 @@ impl<'a> AuthenticatedApi<'a> {
                     http::HTTP_STATUS_504_GATEWAY_TIMEOUT,
                 ],
             )?
+            .idempotent(&self.api.config)
             .send()?
             .convert_rnf(ApiErrorKind::ProjectNotFound)
     }
@@ -1009,6 +1140,7 @@ impl<'a> AuthenticatedApi<'a> {
                     http::HTTP_STATUS_504_GATEWAY_TIMEOUT,
                 ],
             )?
+            .idempotent(&self.api.config)
             .send()?
             .convert_rnf(ApiErrorKind::ReleaseNotFound)
     }
@@ -1040,6 +1172,7 @@ impl<'a> AuthenticatedApi<'a> {
                     http::HTTP_STATUS_504_GATEWAY_TIMEOUT,
                 ],
             )?
+            .idempotent(&self.api.config)
             .send()?
             .convert_rnf(ApiErrorKind::ReleaseNotFound)
     }
@@ -1153,14 +1286,19 @@ impl<'a> AuthenticatedApi<'a> {
     }
 
     /// List all projects associated with an organization
-    pub fn list_organization_projects(&self, org: &str) -> ApiResult<Vec<Project>> {
+    pub fn list_organization_projects(
+        &self,
+        org: &str,
+        per_page: Option<usize>,
+    ) -> ApiResult<Vec<Project>> {
         let mut rv = vec![];
         let mut cursor = "".to_string();
         loop {
             let resp = self.get(&format!(
-                "/organizations/{}/projects/?cursor={}",
+                "/organizations/{}/projects/?cursor={}{}",
                 PathArg(org),
-                QueryArg(&cursor)
+                QueryArg(&cursor),
+                per_page_query(per_page)
             ))?;
             if resp.status() == 404 || (resp.status() == 400 && !cursor.is_empty()) {
                 if rv.is_empty() {
@@ -1186,6 +1324,7 @@ impl<'a> AuthenticatedApi<'a> {
         org: &str,
         project: &str,
         max_pages: usize,
+        per_page: Option<usize>,
     ) -> ApiResult<Vec<ProcessedEvent>> {
         let mut rv = vec![];
         let mut cursor = "".to_string();
@@ -1195,10 +1334,11 @@ impl<'a> AuthenticatedApi<'a> {
             requests_no += 1;
 
             let resp = self.get(&format!(
-                "/projects/{}/{}/events/?cursor={}",
+                "/projects/{}/{}/events/?cursor={}{}",
                 PathArg(org),
                 PathArg(project),
-                QueryArg(&cursor)
+                QueryArg(&cursor),
+                per_page_query(per_page)
             ))?;
 
             if resp.status() == 404 || (resp.status() == 400 && !cursor.is_empty()) {
@@ -1232,27 +1372,33 @@ impl<'a> AuthenticatedApi<'a> {
         org: &str,
         project: &str,
         max_pages: usize,
-        query: Option<String>,
+        issue_query: &IssueListQuery,
+        per_page: Option<usize>,
     ) -> ApiResult<Vec<Issue>> {
         let mut rv = vec![];
         let mut cursor = "".to_string();
         let mut requests_no = 0;
 
-        let url = if let Some(query) = query {
-            format!(
-                "/projects/{}/{}/issues/?query={}&",
-                PathArg(org),
-                PathArg(project),
-                QueryArg(&query),
-            )
-        } else {
-            format!("/projects/{}/{}/issues/?", PathArg(org), PathArg(project),)
-        };
+        let mut url = format!("/projects/{}/{}/issues/?", PathArg(org), PathArg(project));
+        for (name, value) in [
+            ("query", &issue_query.query),
+            ("sort", &issue_query.sort),
+            ("statsPeriod", &issue_query.stats_period),
+        ] {
+            if let Some(value) = value {
+                url.push_str(&format!("{name}={}&", QueryArg(value)));
+            }
+        }
 
         loop {
             requests_no += 1;
 
-            let resp = self.get(&format!("{}cursor={}", url, QueryArg(&cursor)))?;
+            let resp = self.get(&format!(
+                "{}cursor={}{}",
+                url,
+                QueryArg(&cursor),
+                per_page_query(per_page)
+            ))?;
 
             if resp.status() == 404 || (resp.status() == 400 && !cursor.is_empty()) {
                 if rv.is_empty() {
@@ -1279,6 +1425,142 @@ impl<'a> AuthenticatedApi<'a> {
         Ok(rv)
     }
 
+    /// Looks up a single issue by its numeric ID or its short ID.
+    /// If it does not exist `None` will be returned.
+    pub fn get_issue(&self, org: &str, issue_id: &str) -> ApiResult<Option<IssueDetails>> {
+        if issue_id.bytes().all(|b| b.is_ascii_digit()) {
+            let resp = self.get(&format!(
+                "/organizations/{}/issues/{}/",
+                PathArg(org),
+                PathArg(issue_id)
+            ))?;
+            if resp.status() == 404 {
+                Ok(None)
+            } else {
+                resp.convert().map(Some)
+            }
+        } else {
+            let resp = self.get(&format!(
+                "/organizations/{}/shortids/{}/",
+                PathArg(org),
+                PathArg(issue_id)
+            ))?;
+            if resp.status() == 404 {
+                Ok(None)
+            } else {
+                resp.convert::<ShortIdLookup>()
+                    .map(|lookup| Some(lookup.group))
+            }
+        }
+    }
+
+    /// Returns the latest event of the issue with the given numeric ID.
+    pub fn get_issue_latest_event(
+        &self,
+        org: &str,
+        issue_id: &str,
+    ) -> ApiResult<Option<serde_json::Value>> {
+        let resp = self.get(&format!(
+            "/organizations/{}/issues/{}/events/latest/",
+            PathArg(org),
+            PathArg(issue_id)
+        ))?;
+        if resp.status() == 404 {
+            Ok(None)
+        } else {
+            resp.convert().map(Some)
+        }
+    }
+
+    /// Updates the issue with the given numeric ID.
+    /// If it does not exist `None` will be returned.
+    pub fn update_issue(
+        &self,
+        org: &str,
+        issue_id: &str,
+        update: &IssueUpdate,
+    ) -> ApiResult<Option<IssueDetails>> {
+        let resp = self.put(
+            &format!(
+                "/organizations/{}/issues/{}/",
+                PathArg(org),
+                PathArg(issue_id)
+            ),
+            update,
+        )?;
+        if resp.status() == 404 {
+            Ok(None)
+        } else {
+            resp.convert().map(Some)
+        }
+    }
+
+    /// Adds a comment to the issue with the given numeric ID.
+    /// Returns `false` if the issue does not exist.
+    pub fn create_issue_comment(&self, org: &str, issue_id: &str, text: &str) -> ApiResult<bool> {
+        let resp = self.post(
+            &format!(
+                "/organizations/{}/issues/{}/comments/",
+                PathArg(org),
+                PathArg(issue_id)
+            ),
+            &NewIssueComment { text },
+        )?;
+        if resp.status() == 404 {
+            Ok(false)
+        } else {
+            resp.into_result().map(|_| true)
+        }
+    }
+
+    /// List all members of an organization
+    pub fn list_organization_members(&self, org: &str) -> ApiResult<Vec<OrganizationMember>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let resp = self.get(&format!(
+                "/organizations/{}/members/?cursor={}",
+                PathArg(org),
+                QueryArg(&cursor)
+            ))?;
+            if resp.status() == 404 {
+                return Err(ApiErrorKind::OrganizationNotFound.into());
+            }
+            let pagination = resp.pagination();
+            rv.extend(resp.convert::<Vec<OrganizationMember>>()?);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
+    /// List all teams of an organization
+    pub fn list_organization_teams(&self, org: &str) -> ApiResult<Vec<Team>> {
+        let mut rv = vec![];
+        let mut cursor = "".to_string();
+        loop {
+            let resp = self.get(&format!(
+                "/organizations/{}/teams/?cursor={}",
+                PathArg(org),
+                QueryArg(&cursor)
+            ))?;
+            if resp.status() == 404 {
+                return Err(ApiErrorKind::OrganizationNotFound.into());
+            }
+            let pagination = resp.pagination();
+            rv.extend(resp.convert::<Vec<Team>>()?);
+            if let Some(next) = pagination.into_next_cursor() {
+                cursor = next;
+            } else {
+                break;
+            }
+        }
+        Ok(rv)
+    }
+
     /// List all repos associated with an organization
     pub fn list_organization_repos(&self, org: &str) -> ApiResult<Vec<Repo>> {
         let mut rv = vec![];
@@ -1404,12 +1686,12 @@ impl<'a> RegionSpecificApi<'a> {
             PathArg(self.org),
             PathArg(project)
         );
-        let mut form = curl::easy::Form::new();
-        form.part("file").file(file).add()?;
+        let mut form = MultipartForm::new();
+        form.file("file", file);
         self.request(Method::Post, &path)?
-            .with_form_data(form)?
+            .with_form_data(form, &self.api.api.config)?
             .with_retry(
-                self.api.api.config.get_max_retry_count().map_err(|e| {
+                self.api.api.config.get_upload_max_retries().map_err(|e| {
                     ApiError::with_source(
                         ApiErrorKind::ErrorPreparingRequest,
                         e.context("Could not parse retry count"),
@@ -1450,33 +1732,29 @@ impl<'a> RegionSpecificApi<'a> {
                 PathArg(release)
             )
         };
-        let mut form = curl::easy::Form::new();
+        let mut form = MultipartForm::new();
 
         let filename = Path::new(name)
             .file_name()
             .and_then(OsStr::to_str)
             .unwrap_or("unknown.bin");
-        form.part("file")
-            .buffer(filename, contents.to_vec())
-            .add()?;
-        form.part("name").contents(name.as_bytes()).add()?;
+        form.buffer("file", filename, contents.to_vec());
+        form.contents("name", name.as_bytes());
         if let Some(dist) = context.dist {
-            form.part("dist").contents(dist.as_bytes()).add()?;
+            form.contents("dist", dist.as_bytes());
         }
 
         if let Some(headers) = headers {
             for (key, value) in headers {
-                form.part("header")
-                    .contents(format!("{key}:{value}").as_bytes())
-                    .add()?;
+                form.contents("header", format!("{key}:{value}").as_bytes());
             }
         }
 
         let resp = self
             .request(Method::Post, &path)?
-            .with_form_data(form)?
+            .with_form_data(form, &self.api.api.config)?
             .with_retry(
-                self.api.api.config.get_max_retry_count().unwrap(),
+                self.api.api.config.get_upload_max_retries().unwrap(),
                 &[
                     http::HTTP_STATUS_502_BAD_GATEWAY,
                     http::HTTP_STATUS_503_SERVICE_UNAVAILABLE,
@@ -1496,6 +1774,7 @@ impl<'a> RegionSpecificApi<'a> {
 fn send_req<W: Write>(
     handle: &mut curl::easy::Easy,
     out: &mut W,
+    redirect_out: &mut Vec<u8>,
     body: Option<&[u8]>,
     progress_bar_mode: ProgressBarMode,
 ) -> ApiResult<(u32, Vec<String>)> {
@@ -1503,17 +1782,21 @@ fn send_req<W: Write>(
         Some(mut body) => {
             handle.upload(true)?;
             handle.in_filesize(body.len() as u64)?;
-            handle_req(handle, out, progress_bar_mode, &mut |buf| {
+            handle_req(handle, out, redirect_out, progress_bar_mode, &mut |buf| {
                 body.read(buf).unwrap_or(0)
             })
         }
-        None => handle_req(handle, out, progress_bar_mode, &mut |_| 0),
+        None => handle_req(handle, out, redirect_out, progress_bar_mode, &mut |_| 0),
     }
 }
 
+/// Performs the request.  The bodies of redirect responses are written to
+/// `redirect_out` instead of `out`, since they are discarded when the
+/// redirect is followed.
 fn handle_req<W: Write>(
     handle: &mut curl::easy::Easy,
     out: &mut W,
+    redirect_out: &mut Vec<u8>,
     progress_bar_mode: ProgressBarMode,
     read: &mut dyn FnMut(&mut [u8]) -> usize,
 ) -> ApiResult<(u32, Vec<String>)> {
@@ -1526,8 +1809,10 @@ fn handle_req<W: Write>(
 
     let mut headers = Vec::new();
     let pb: Rc<RefCell<Option<ProgressBar>>> = Rc::new(RefCell::new(None));
+    let is_redirect = Cell::new(false);
     {
         let headers = &mut headers;
+        let is_redirect = &is_redirect;
         let mut handle = handle.transfer();
 
         if let ProgressBarMode::Shared((pb_progress, len, idx, counts)) = progress_bar_mode {
@@ -1570,6 +1855,7 @@ fn handle_req<W: Write>(
         handle.read_function(move |buf| Ok(read(buf)))?;
 
         handle.write_function(move |data| {
+            let out: &mut dyn Write = if is_redirect.get() { redirect_out } else { out };
             Ok(match out.write_all(data) {
                 Ok(_) => data.len(),
                 Err(_) => 0,
@@ -1583,11 +1869,19 @@ fn handle_req<W: Write>(
             curl::easy::InfoType::HeaderOut => {
                 log_headers(true, data);
             }
+            curl::easy::InfoType::Text => {
+                count_connection(data);
+            }
             _ => {}
         })?;
 
         handle.header_function(move |data| {
-            headers.push(String::from_utf8_lossy(data).into_owned());
+            let line = String::from_utf8_lossy(data).into_owned();
+            if let Some(status) = line.strip_prefix("HTTP/") {
+                let status = status.split_whitespace().nth(1).unwrap_or("");
+                is_redirect.set(status.len() == 3 && status.starts_with('3'));
+            }
+            headers.push(line);
             true
         })?;
         handle.perform()?;
@@ -1600,6 +1894,40 @@ fn handle_req<W: Write>(
     Ok((handle.response_code()?, headers))
 }
 
+/// Returns whether following a redirect from `from` to `to` changes the
+/// scheme, host or port of the request.
+fn is_cross_origin(from: &str, to: &str) -> bool {
+    match (url::Url::parse(from), url::Url::parse(to)) {
+        (Ok(from), Ok(to)) => from.origin() != to.origin(),
+        _ => true,
+    }
+}
+
+/// Formats the `per_page` query parameter appended to paginated requests.
+fn per_page_query(per_page: Option<usize>) -> String {
+    per_page
+        .map(|per_page| format!("&per_page={per_page}"))
+        .unwrap_or_default()
+}
+
+/// Tracks whether curl opened a new connection or reused an existing one.
+fn count_connection(data: &[u8]) {
+    let counter = if data.starts_with(b"Re-using existing") || data.starts_with(b"Reusing existing")
+    {
+        &REUSED_CONNECTIONS
+    } else if data.starts_with(b"Connected to ") {
+        &NEW_CONNECTIONS
+    } else {
+        return;
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+    trace!(
+        "connections: {} new, {} reused",
+        NEW_CONNECTIONS.load(Ordering::Relaxed),
+        REUSED_CONNECTIONS.load(Ordering::Relaxed)
+    );
+}
+
 /// Iterator over response headers
 #[allow(dead_code)]
 pub struct Headers<'a> {
@@ -1666,12 +1994,19 @@ impl ApiRequest {
 
         let request = ApiRequest {
             handle,
+            method: *method,
+            url: url.to_owned(),
             headers,
             is_authenticated: false,
             body: None,
             progress_bar_mode: ProgressBarMode::Disabled,
             max_retries: 0,
             retry_on_statuses: &[],
+            retry_idempotent: false,
+            max_redirects: 0,
+            rate_limiter: None,
+            max_rate_limited_retries: 0,
+            dump: None,
         };
 
         let request = match auth {
@@ -1725,18 +2060,22 @@ impl ApiRequest {
     }
 
     /// attaches some form data to the request.
-    pub fn with_form_data(mut self, form: curl::easy::Form) -> ApiResult<Self> {
+    pub fn with_form_data(mut self, form: MultipartForm, config: &Config) -> ApiResult<Self> {
         debug!("sending form data");
-        self.handle.httppost(form)?;
-        self.body = None;
-        Ok(self)
-    }
-
-    /// enables or disables redirects.  The default is off.
-    pub fn follow_location(mut self, val: bool) -> ApiResult<Self> {
-        debug!("follow redirects: {}", val);
-        self.handle.follow_location(val)?;
-        Ok(self)
+        // When dumping requests, the form is encoded here so that the dumped
+        // body is exactly what is sent.
+        match config.get_multipart_boundary_charset() {
+            MultipartBoundaryCharset::Default if self.dump.is_none() => {
+                self.handle.httppost(form.into_curl_form()?)?;
+                self.body = None;
+                Ok(self)
+            }
+            _ => {
+                let (content_type, body) = form.encode()?;
+                self.body = Some(body);
+                self.with_header("Content-Type", &content_type)
+            }
+        }
     }
 
     /// enables a progress bar.
@@ -1755,6 +2094,14 @@ impl ApiRequest {
         Ok(self)
     }
 
+    /// Marks the request as safe to send more than once.  If retrying
+    /// idempotent requests is enabled in the config, connection errors and
+    /// internal server errors are retried as well.
+    pub fn idempotent(mut self, config: &Config) -> Self {
+        self.retry_idempotent = config.retry_idempotent_post();
+        self
+    }
+
     /// Get a copy of the header list
     fn get_headers(&self) -> curl::easy::List {
         let mut result = curl::easy::List::new();
@@ -1767,24 +2114,99 @@ impl ApiRequest {
 
     /// Sends the request and writes response data into the given file
     /// instead of the response object's in memory buffer.
+    ///
+    /// Redirects are followed up to the configured limit.  Their bodies are
+    /// never written to `out`.
     pub fn send_into<W: Write>(&mut self, out: &mut W) -> ApiResult<ApiResponse> {
-        let headers = self.get_headers();
-        self.handle.http_headers(headers)?;
-        let body = self.body.as_deref();
-        let (status, headers) =
-            send_req(&mut self.handle, out, body, self.progress_bar_mode.clone())?;
-        debug!("response status: {}", status);
-        Ok(ApiResponse {
-            status,
-            headers,
-            body: None,
-        })
+        let mut chain = vec![self.url.clone()];
+        loop {
+            let headers = self.get_headers();
+            self.handle.http_headers(headers)?;
+            let body = self.body.as_deref();
+            if let Some(ref dump) = self.dump {
+                dump.write(&self.headers, body);
+            }
+            if let Some(ref rate_limiter) = self.rate_limiter {
+                rate_limiter.wait();
+            }
+            let mut redirect_body = vec![];
+            let result = send_req(
+                &mut self.handle,
+                out,
+                &mut redirect_body,
+                body,
+                self.progress_bar_mode.clone(),
+            );
+            metrics_summary::record_request(self.handle.upload_size().unwrap_or(0.0) as u64);
+            let (status, headers) = result?;
+            debug!("response status: {}", status);
+            let url = self.handle.effective_url()?.map(str::to_owned);
+            let response = ApiResponse {
+                status,
+                headers,
+                body: None,
+                url,
+            };
+
+            let Some(target) = response.redirect_target(&self.url, &redirect_body) else {
+                out.write_all(&redirect_body)
+                    .map_err(|err| ApiError::with_source(ApiErrorKind::RequestFailed, err))?;
+                return Ok(response);
+            };
+            chain.push(target.clone());
+            if chain.len() > self.max_redirects as usize + 1 {
+                return Err(ApiError::with_source(
+                    ApiErrorKind::TooManyRedirects,
+                    RedirectChainError(chain),
+                ));
+            }
+            self.redirect(status, target)?;
+        }
+    }
+
+    /// Prepares the request for following a redirect to `target`.
+    fn redirect(&mut self, status: u32, target: String) -> ApiResult<()> {
+        debug!(
+            "following redirect ({status}) from {} to {target}",
+            self.url
+        );
+
+        // Credentials are only sent to the origin the request was made to.
+        if is_cross_origin(&self.url, &target) && self.is_authenticated {
+            debug!("dropping credentials for cross-origin redirect");
+            let mut headers = curl::easy::List::new();
+            for header in self.headers.iter() {
+                if !header.to_ascii_lowercase().starts_with(b"authorization:") {
+                    headers.append(&String::from_utf8_lossy(header))?;
+                }
+            }
+            self.headers = headers;
+            self.handle.http_auth(&curl::easy::Auth::new())?;
+            self.is_authenticated = false;
+        }
+
+        // Like browsers, switch to GET for "See Other" and for POST requests
+        // that were moved.
+        if self.method != Method::Get
+            && (status == 303 || (self.method == Method::Post && matches!(status, 301 | 302)))
+        {
+            debug!("switching to GET for redirect");
+            self.handle.get(true)?;
+            self.handle.custom_request("GET")?;
+            self.method = Method::Get;
+            self.body = None;
+        }
+
+        self.handle.url(&target)?;
+        self.url = target;
+        Ok(())
     }
 
     /// Sends the request and reads the response body into the response object.
     pub fn send(mut self) -> ApiResult<ApiResponse> {
         let mut backoff = get_default_backoff();
         let mut retry_number = 0;
+        let mut rate_limited_retries = 0;
 
         loop {
             let mut out = vec![];
@@ -1793,24 +2215,82 @@ impl ApiRequest {
                 retry_number, self.max_retries,
             );
 
-            let mut rv = self.send_into(&mut out)?;
-            if retry_number >= self.max_retries || !self.retry_on_statuses.contains(&rv.status) {
+            let can_retry = retry_number < self.max_retries;
+            let mut rv = match self.send_into(&mut out) {
+                Ok(rv) => rv,
+                Err(err) if can_retry && self.retry_idempotent => {
+                    info!("retrying idempotent request after error: {err}");
+                    self.backoff(&mut backoff, retry_number);
+                    retry_number += 1;
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            if rv.status == http::HTTP_STATUS_429_TOO_MANY_REQUESTS
+                && rate_limited_retries < self.max_rate_limited_retries
+            {
+                match rv.retry_after() {
+                    Some(delay) => {
+                        let delay = delay.min(MAX_RETRY_AFTER);
+                        info!(
+                            "rate limited by the server, retrying in {}s",
+                            delay.as_secs()
+                        );
+                        std::thread::sleep(delay);
+                        metrics_summary::record_retry();
+                    }
+                    None => {
+                        info!("rate limited by the server, retrying");
+                        self.backoff(&mut backoff, rate_limited_retries);
+                    }
+                }
+                rate_limited_retries += 1;
+                continue;
+            }
+            let retry_status = self.retry_on_statuses.contains(&rv.status)
+                || (self.retry_idempotent
+                    && rv.status == http::HTTP_STATUS_500_INTERNAL_SERVER_ERROR);
+            if !can_retry || !retry_status {
                 rv.body = Some(out);
                 return Ok(rv);
             }
+            if self.retry_idempotent {
+                info!("retrying idempotent request after status {}", rv.status);
+            }
 
-            // Exponential backoff
-            let backoff_timeout = backoff.next_backoff().unwrap();
-            debug!(
-                "retry number {}, retrying again in {} ms",
-                retry_number,
-                backoff_timeout.as_milliseconds()
-            );
-            std::thread::sleep(backoff_timeout);
-
+            self.backoff(&mut backoff, retry_number);
             retry_number += 1;
         }
     }
+
+    /// Sends the first request of a command with a short connect timeout, so
+    /// that a wrong URL is reported quickly.  Later requests wait longer.
+    pub fn send_preflight(mut self, connect_timeout: Duration) -> ApiResult<ApiResponse> {
+        self.handle.connect_timeout(connect_timeout)?;
+        let url = self.url.clone();
+        self.send().map_err(|err| match err.connect_error() {
+            Some(source) => ApiError::with_source(
+                ApiErrorKind::ServerUnreachable,
+                UnreachableError {
+                    url,
+                    source: source.clone(),
+                },
+            ),
+            None => err,
+        })
+    }
+
+    /// Sleeps for the next exponential backoff interval.
+    fn backoff(&self, backoff: &mut impl Backoff, retry_number: u32) {
+        let backoff_timeout = backoff.next_backoff().unwrap();
+        debug!(
+            "retry number {}, retrying again in {} ms",
+            retry_number,
+            backoff_timeout.as_milliseconds()
+        );
+        std::thread::sleep(backoff_timeout);
+        metrics_summary::record_retry();
+    }
 }
 
 impl ApiResponse {
@@ -1839,6 +2319,11 @@ impl ApiResponse {
         if self.ok() {
             return Ok(self);
         }
+        if self.is_login_page() {
+            return Err(self.html_error());
+        }
+        let request_id = self.request_id().map(str::to_owned);
+        let response = self.deserialize::<serde_json::Value>().ok();
         if let Ok(err) = self.deserialize::<ErrorInfo>() {
             Err(ApiError::with_source(
                 ApiErrorKind::RequestFailed,
@@ -1849,15 +2334,22 @@ impl ApiResponse {
                         ErrorInfo::Error(val) => val,
                     }),
                     extra: None,
+                    request_id,
+                    response,
                 },
             ))
-        } else if let Ok(value) = self.deserialize::<serde_json::Value>() {
+        } else if self.status() == 406 {
+            // Without details from the server, the version is the likely cause.
+            Err(ApiErrorKind::UnsupportedApiVersion.into())
+        } else if let Some(value) = response {
             Err(ApiError::with_source(
                 ApiErrorKind::RequestFailed,
                 SentryError {
                     status: self.status(),
                     detail: Some("request failure".into()),
-                    extra: Some(value),
+                    extra: Some(value.clone()),
+                    request_id,
+                    response: Some(value),
                 },
             ))
         } else {
@@ -1867,13 +2359,25 @@ impl ApiResponse {
                     status: self.status(),
                     detail: None,
                     extra: None,
+                    request_id,
+                    response: None,
                 },
             ))
         }
     }
 
+    /// Returns the id the server assigned to the request, if it sent one.
+    fn request_id(&self) -> Option<&str> {
+        REQUEST_ID_HEADERS
+            .iter()
+            .find_map(|header| self.get_header(header))
+    }
+
     /// Deserializes the response body into the given type
     pub fn deserialize<T: DeserializeOwned>(&self) -> ApiResult<T> {
+        if self.is_login_page() {
+            return Err(self.html_error());
+        }
         if !self.is_json() {
             return Err(ApiErrorKind::NotJson.into());
         }
@@ -1937,6 +2441,33 @@ impl ApiResponse {
         None
     }
 
+    /// Returns the absolute URL this response redirects to, if it should be
+    /// followed.  Sentry answers requests for renamed projects with a redirect
+    /// carrying a JSON body, which is returned to the caller instead.
+    fn redirect_target(&self, request_url: &str, body: &[u8]) -> Option<String> {
+        if !matches!(self.status, 301 | 302 | 303 | 307 | 308)
+            || (self.is_json() && !body.is_empty())
+        {
+            return None;
+        }
+        let location = self.get_header("location")?;
+        match url::Url::parse(request_url).and_then(|url| url.join(location)) {
+            Ok(target) => Some(target.into()),
+            Err(err) => {
+                warn!("Not following redirect to invalid location {location}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Returns how long the server asks to wait before retrying, if the
+    /// `Retry-After` header gives a number of seconds.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.get_header("retry-after")
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs)
+    }
+
     /// Returns the pagination info
     pub fn pagination(&self) -> Pagination {
         self.get_header("link")
@@ -1951,6 +2482,40 @@ impl ApiResponse {
             .unwrap_or("")
             == "application/json"
     }
+
+    /// Indicates that the response is an HTML page.  Auth proxies in front of
+    /// Sentry answer with a login page once their session expired, sometimes
+    /// even claiming it is JSON, so the body is checked as well.
+    fn is_html(&self) -> bool {
+        let content_type = self
+            .get_header("content-type")
+            .and_then(|x| x.split(';').next())
+            .unwrap_or("");
+        if content_type.trim().eq_ignore_ascii_case("text/html") {
+            return true;
+        }
+        let body = self.body.as_deref().unwrap_or_default();
+        let start = &body[..body.len().min(64)];
+        let start = String::from_utf8_lossy(start)
+            .trim_start()
+            .to_ascii_lowercase();
+        start.starts_with("<!doctype html") || start.starts_with("<html")
+    }
+
+    /// Indicates whether the response is an HTML page where API JSON was
+    /// expected, such as the login page of an auth proxy whose session
+    /// expired.  Other error responses with HTML bodies are reported by
+    /// their status instead.
+    fn is_login_page(&self) -> bool {
+        (self.ok() || self.status == 401) && self.is_html()
+    }
+
+    fn html_error(&self) -> ApiError {
+        ApiError::with_source(
+            ApiErrorKind::HtmlResponse,
+            anyhow::anyhow!("response URL: {}", self.url.as_deref().unwrap_or("unknown")),
+        )
+    }
 }
 
 fn log_headers(is_response: bool, data: &[u8]) {
@@ -2028,6 +2593,8 @@ pub struct NewRelease {
     pub projects: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    #[serde(rename = "dateCreated", skip_serializing_if = "Option::is_none")]
+    pub date_created: Option<DateTime<Utc>>,
     #[serde(rename = "dateStarted", skip_serializing_if = "Option::is_none")]
     pub date_started: Option<DateTime<Utc>>,
     #[serde(rename = "dateReleased", skip_serializing_if = "Option::is_none")]
@@ -2074,10 +2641,9 @@ pub struct UpdatedRelease {
 }
 
 /// Provides all release information from already existing releases
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReleaseInfo {
     pub version: String,
-    #[expect(dead_code)]
     pub url: Option<String>,
     #[serde(rename = "dateCreated")]
     pub date_created: DateTime<Utc>,
@@ -2108,7 +2674,7 @@ pub enum OptionalReleaseInfo {
 #[serde(deny_unknown_fields)]
 pub struct NoneReleaseInfo {}
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ReleaseCommit {
     pub id: String,
 }
@@ -2160,7 +2726,6 @@ pub struct DebugInfoFile {
     #[serde(rename = "cpuName")]
     pub cpu_name: String,
     #[serde(rename = "sha1")]
-    #[expect(dead_code)]
     pub checksum: String,
     #[serde(default)]
     pub data: DebugInfoData,
@@ -2183,7 +2748,17 @@ struct MissingChecksumsResponse {
     missing: HashSet<Digest>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// Narrows down and orders the issues returned by `list_organization_project_issues`.
+#[derive(Clone, Debug, Default)]
+pub struct IssueListQuery {
+    pub query: Option<String>,
+    /// One of `date`, `new`, `freq`, `priority` or `user`.
+    pub sort: Option<String>,
+    /// The period of the event counts in `Issue::stats`, such as `24h` or `14d`.
+    pub stats_period: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Issue {
     pub id: String,
@@ -2192,6 +2767,84 @@ pub struct Issue {
     pub last_seen: String,
     pub status: String,
     pub level: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_count: Option<u64>,
+    /// Event counts per time bucket as `(timestamp, count)`, keyed by the
+    /// stats period.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub stats: HashMap<String, Vec<(i64, u64)>>,
+}
+
+impl Issue {
+    /// The number of events within the given stats period.
+    pub fn events_in_period(&self, stats_period: &str) -> Option<u64> {
+        self.stats
+            .get(stats_period)
+            .map(|buckets| buckets.iter().map(|(_, count)| count).sum())
+    }
+}
+
+/// Full details of a single issue.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueDetails {
+    pub id: String,
+    pub short_id: String,
+    pub title: String,
+    pub culprit: Option<String>,
+    pub permalink: Option<String>,
+    pub level: String,
+    pub status: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub count: String,
+    pub user_count: u64,
+    pub assigned_to: Option<IssueAssignee>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+/// The user or team an issue is assigned to.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct IssueAssignee {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Deserialize)]
+struct ShortIdLookup {
+    group: IssueDetails,
+}
+
+/// Changes to a single issue.
+#[derive(Serialize, Default)]
+pub struct IssueUpdate {
+    /// The actor to assign the issue to, e.g. `user:42` or `team:7`.
+    #[serde(rename = "assignedTo", skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NewIssueComment<'a> {
+    text: &'a str,
+}
+
+/// A member of an organization.  Members with pending invites have no user.
+#[derive(Debug, Deserialize)]
+pub struct OrganizationMember {
+    pub email: String,
+    pub user: Option<MemberUser>,
+}
+
+/// The user account of an organization member.
+#[derive(Debug, Deserialize)]
+pub struct MemberUser {
+    pub id: String,
+    pub username: String,
 }
 
 /// Change information for issue bulk updates.
@@ -2280,17 +2933,14 @@ pub struct Organization {
 
 #[derive(Deserialize, Debug)]
 pub struct Team {
-    #[expect(dead_code)]
     pub id: String,
-    #[expect(dead_code)]
     pub slug: String,
     pub name: String,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ProjectSlugAndName {
     pub slug: String,
-    #[expect(dead_code)]
     pub name: String,
 }
 
@@ -2430,7 +3080,6 @@ impl fmt::Display for ProcessedEventTag {
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Region {
-    #[expect(dead_code)]
     pub name: String,
     pub url: String,
 }
@@ -0,0 +1,186 @@
+//! Building of `multipart/form-data` request bodies.
+//!
+//! By default forms are handed to curl, which picks its own boundary.  Some
+//! strict proxies reject the punctuation in curl's boundaries, so forms can
+//! alternatively be encoded here with a boundary made of ASCII alphanumerics
+//! only.
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use super::errors::{ApiError, ApiErrorKind, ApiResult};
+
+/// The set of characters used for multipart boundaries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MultipartBoundaryCharset {
+    /// Let curl generate the boundary.
+    #[default]
+    Default,
+    /// Only use ASCII letters and digits in the boundary.
+    Simple,
+}
+
+impl MultipartBoundaryCharset {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(MultipartBoundaryCharset::Default),
+            "simple" => Some(MultipartBoundaryCharset::Simple),
+            _ => None,
+        }
+    }
+}
+
+enum PartData {
+    Contents(Vec<u8>),
+    Buffer(String, Vec<u8>),
+    File(PathBuf),
+}
+
+struct Part {
+    name: String,
+    data: PartData,
+}
+
+/// A `multipart/form-data` form that can be sent with an `ApiRequest`.
+#[derive(Default)]
+pub struct MultipartForm {
+    parts: Vec<Part>,
+}
+
+impl MultipartForm {
+    pub fn new() -> MultipartForm {
+        Default::default()
+    }
+
+    /// Adds a plain field with the given contents.
+    pub fn contents(&mut self, name: &str, contents: &[u8]) {
+        self.push(name, PartData::Contents(contents.to_vec()));
+    }
+
+    /// Adds a file field with the given file name and in-memory contents.
+    pub fn buffer(&mut self, name: &str, filename: &str, data: Vec<u8>) {
+        self.push(name, PartData::Buffer(filename.to_owned(), data));
+    }
+
+    /// Adds a file field which is read from the given path.
+    pub fn file(&mut self, name: &str, path: &Path) {
+        self.push(name, PartData::File(path.to_owned()));
+    }
+
+    fn push(&mut self, name: &str, data: PartData) {
+        self.parts.push(Part {
+            name: name.to_owned(),
+            data,
+        });
+    }
+
+    /// Converts the form into a curl form.
+    pub(super) fn into_curl_form(self) -> ApiResult<curl::easy::Form> {
+        let mut form = curl::easy::Form::new();
+        for part in self.parts {
+            match part.data {
+                PartData::Contents(contents) => {
+                    form.part(&part.name).contents(&contents).add()?;
+                }
+                PartData::Buffer(filename, data) => {
+                    form.part(&part.name).buffer(&filename, data).add()?;
+                }
+                PartData::File(path) => {
+                    form.part(&part.name).file(&path).add()?;
+                }
+            }
+        }
+        Ok(form)
+    }
+
+    /// Encodes the form into a request body.  Returns the value of the
+    /// `Content-Type` header together with the body.
+    pub(super) fn encode(self) -> ApiResult<(String, Vec<u8>)> {
+        let boundary = generate_boundary();
+        let mut body = vec![];
+        for part in self.parts {
+            write!(
+                body,
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"",
+                escape_quoted(&part.name)
+            )
+            .ok();
+            let data = match part.data {
+                PartData::Contents(contents) => {
+                    body.extend_from_slice(b"\r\n\r\n");
+                    contents
+                }
+                PartData::Buffer(filename, data) => {
+                    write_file_headers(&mut body, &filename);
+                    data
+                }
+                PartData::File(path) => {
+                    let filename = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    write_file_headers(&mut body, &filename);
+                    fs::read(&path).map_err(|err| {
+                        ApiError::with_source(ApiErrorKind::ErrorPreparingRequest, err)
+                    })?
+                }
+            };
+            body.extend_from_slice(&data);
+            body.extend_from_slice(b"\r\n");
+        }
+        write!(body, "--{boundary}--\r\n").ok();
+
+        Ok((format!("multipart/form-data; boundary={boundary}"), body))
+    }
+}
+
+fn write_file_headers(body: &mut Vec<u8>, filename: &str) {
+    write!(
+        body,
+        "; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+        escape_quoted(filename)
+    )
+    .ok();
+}
+
+/// Escapes a value for use in a quoted header parameter, the same way curl does.
+fn escape_quoted(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Generates a boundary consisting only of ASCII letters and digits.
+fn generate_boundary() -> String {
+    format!("sentrycli{}", Uuid::new_v4().simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_simple_boundary() {
+        let mut form = MultipartForm::new();
+        form.buffer("file", "app.js", b"code".to_vec());
+        form.contents("name", b"~/app.js");
+        let (content_type, body) = form.encode().unwrap();
+
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        assert!(boundary.chars().all(|c| c.is_ascii_alphanumeric()));
+
+        let expected = format!(
+            "--{boundary}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"app.js\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n\
+             code\r\n\
+             --{boundary}\r\n\
+             Content-Disposition: form-data; name=\"name\"\r\n\r\n\
+             ~/app.js\r\n\
+             --{boundary}--\r\n"
+        );
+        assert_eq!(String::from_utf8(body).unwrap(), expected);
+    }
+}
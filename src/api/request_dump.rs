@@ -0,0 +1,140 @@
+//! Dumping of request bodies for debugging uploads.
+//!
+//! With `--dump-request-bodies <DIR>` every request body is written into the
+//! given directory, together with a metadata file per request that records
+//! the method, URL and headers.  Credentials in headers are redacted.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::{debug, warn};
+use serde::Serialize;
+
+use super::Method;
+
+/// Bodies larger than this are truncated unless `--dump-full` is passed.
+const MAX_DUMPED_BODY_SIZE: usize = 1024 * 1024;
+
+/// Headers whose values are never written to a dump.
+const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-sentry-auth",
+];
+
+static DUMPED_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Serialize)]
+struct DumpMetadata<'a> {
+    method: String,
+    url: &'a str,
+    headers: Vec<String>,
+    body_file: Option<String>,
+    body_size: usize,
+    truncated: bool,
+}
+
+/// Writes the bodies of requests sent to a single URL into a directory.
+pub struct RequestDump {
+    dir: PathBuf,
+    full: bool,
+    method: String,
+    url: String,
+}
+
+impl RequestDump {
+    pub fn new(dir: &Path, full: bool, method: &Method, url: &str) -> RequestDump {
+        RequestDump {
+            dir: dir.to_owned(),
+            full,
+            method: method.to_string(),
+            url: url.to_owned(),
+        }
+    }
+
+    /// Dumps a request.  Failures are only logged, as dumping must never
+    /// affect the request itself.
+    pub fn write(&self, headers: &curl::easy::List, body: Option<&[u8]>) {
+        if let Err(err) = self.try_write(headers, body) {
+            warn!("Could not dump request body: {err}");
+        }
+    }
+
+    fn try_write(&self, headers: &curl::easy::List, body: Option<&[u8]>) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let name = format!("{:04}", DUMPED_REQUESTS.fetch_add(1, Ordering::Relaxed) + 1);
+
+        let headers: Vec<_> = headers
+            .iter()
+            .map(|header| redact_header(&String::from_utf8_lossy(header)))
+            .collect();
+        let is_json = headers.iter().any(|header| {
+            header
+                .to_ascii_lowercase()
+                .starts_with("content-type: application/json")
+        });
+
+        let body = body.unwrap_or_default();
+        let truncated = !self.full && body.len() > MAX_DUMPED_BODY_SIZE;
+        let body_file = if body.is_empty() {
+            None
+        } else {
+            let body_file = format!("{name}.{}", if is_json { "json" } else { "bin" });
+            let dumped = if truncated {
+                &body[..MAX_DUMPED_BODY_SIZE]
+            } else {
+                body
+            };
+            fs::write(self.dir.join(&body_file), dumped)?;
+            Some(body_file)
+        };
+
+        let metadata = DumpMetadata {
+            method: self.method.clone(),
+            url: &self.url,
+            headers,
+            body_file,
+            body_size: body.len(),
+            truncated,
+        };
+        let metadata_path = self.dir.join(format!("{name}.meta.json"));
+        fs::write(&metadata_path, serde_json::to_vec_pretty(&metadata)?)?;
+        debug!("dumped request to {}", metadata_path.display());
+        if truncated {
+            warn!(
+                "Dumped request body of {} bytes was truncated, pass --dump-full to dump it completely",
+                body.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+fn redact_header(header: &str) -> String {
+    match header.split_once(':') {
+        Some((name, _))
+            if REDACTED_HEADERS.contains(&name.trim().to_ascii_lowercase().as_str()) =>
+        {
+            format!("{name}: [redacted]")
+        }
+        _ => header.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_header() {
+        assert_eq!(
+            redact_header("Authorization: Bearer secret"),
+            "Authorization: [redacted]"
+        );
+        assert_eq!(
+            redact_header("Content-Type: application/json"),
+            "Content-Type: application/json"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use ini::Ini;
+
+use super::get::{value_sources, KNOWN_KEYS, SECRET_KEYS};
+use crate::config::{load_config_layers, Config};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Print the effective configuration as a config file.")
+        .long_about(
+            "Print the effective configuration as a config file.{n}{n}\
+             All config files, environment variables and command line options are \
+             merged and printed as `.sentryclirc` content, with a comment naming the \
+             source of every value.  Secrets such as the auth token are left out \
+             unless `--include-secrets` is passed.",
+        )
+        .arg(
+            Arg::new("include_secrets")
+                .long("include-secrets")
+                .action(ArgAction::SetTrue)
+                .help("Include secrets such as the auth token in the output."),
+        )
+}
+
+/// Formats a single `key=value` line, escaped the same way config files are written.
+fn format_entry(key: &str, value: &str) -> Result<String> {
+    let mut ini = Ini::new();
+    ini.with_general_section().set(key, value);
+    let mut buf = vec![];
+    ini.write_to(&mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let include_secrets = matches.get_flag("include_secrets");
+    let config = Config::current();
+    let (_, layers) = load_config_layers()?;
+
+    let mut keys: BTreeSet<_> = config.get_ini_keys().into_iter().collect();
+    for layer in &layers {
+        for (section, props) in layer.ini.iter() {
+            if let Some(section) = section {
+                keys.extend(
+                    props
+                        .iter()
+                        .map(|(key, _)| (section.to_owned(), key.to_owned())),
+                );
+            }
+        }
+    }
+    keys.extend(
+        KNOWN_KEYS
+            .iter()
+            .map(|(section, key, _)| ((*section).to_owned(), (*key).to_owned())),
+    );
+
+    println!("; Effective sentry-cli configuration, as printed by `sentry-cli config dump`.");
+    let mut current_section = None;
+    for (section, key) in &keys {
+        let sources = value_sources(&config, &layers, section, key);
+        let Some((source, Some(value))) = sources.into_iter().find(|(_, value)| value.is_some())
+        else {
+            continue;
+        };
+
+        if current_section != Some(section) {
+            println!();
+            println!("[{section}]");
+            current_section = Some(section);
+        }
+        println!("; source: {source}");
+        if !include_secrets && SECRET_KEYS.contains(&format!("{section}.{key}").as_str()) {
+            println!("; {key}=[REDACTED] (pass --include-secrets to include it)");
+        } else {
+            print!("{}", format_entry(key, &value)?);
+        }
+    }
+
+    Ok(())
+}
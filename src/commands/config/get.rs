@@ -0,0 +1,177 @@
+use std::env;
+
+use anyhow::{format_err, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
+
+use crate::config::{load_config_layers, Config, ConfigLayer};
+use crate::constants::DEFAULT_URL;
+use crate::utils::formatting::print_value;
+
+/// Config keys with special handling, as `(section, key, environment variable)`.
+pub(super) const KNOWN_KEYS: &[(&str, &str, Option<&str>)] = &[
+    ("auth", "token", Some("SENTRY_AUTH_TOKEN")),
+    ("auth", "api_key", Some("SENTRY_API_KEY")),
+    ("auth", "dsn", Some("SENTRY_DSN")),
+    ("defaults", "url", Some("SENTRY_URL")),
+    ("defaults", "org", Some("SENTRY_ORG")),
+    ("defaults", "project", Some("SENTRY_PROJECT")),
+    ("defaults", "pipeline", Some("SENTRY_PIPELINE")),
+    ("defaults", "vcs_remote", Some("SENTRY_VCS_REMOTE")),
+    ("defaults", "custom_header", Some("CUSTOM_HEADER")),
+    ("http", "max_retries", Some("SENTRY_HTTP_MAX_RETRIES")),
+    ("http", "keepalive_timeout", Some("SENTRY_HTTP_KEEPALIVE")),
+    (
+        "http",
+        "keepalive_interval",
+        Some("SENTRY_HTTP_KEEPALIVE_INTERVAL"),
+    ),
+    ("http", "proxy_username", Some("SENTRY_HTTP_PROXY_USERNAME")),
+    ("http", "proxy_password", Some("SENTRY_HTTP_PROXY_PASSWORD")),
+    ("log", "level", Some("SENTRY_LOG_LEVEL")),
+];
+
+/// Keys whose values are never printed.
+pub(super) const SECRET_KEYS: &[&str] = &["auth.token", "auth.api_key", "http.proxy_password"];
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Print the effective value of a config key.")
+        .long_about(
+            "Print the effective value of a config key.{n}{n}\
+             Keys are given as `section.key` (for example `defaults.org`).  \
+             The shorthands `org`, `project`, `url` and `token` are also accepted.",
+        )
+        .arg(
+            Arg::new("key")
+                .value_name("KEY")
+                .required(true)
+                .help("The config key to look up."),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .action(ArgAction::SetTrue)
+                .help("Show every source of the value and which one takes precedence."),
+        )
+}
+
+/// Resolves shorthands and splits a key into section and key.
+fn parse_key(key: &str) -> Result<(&str, &str)> {
+    match key {
+        "org" => Ok(("defaults", "org")),
+        "project" => Ok(("defaults", "project")),
+        "url" => Ok(("defaults", "url")),
+        "token" => Ok(("auth", "token")),
+        _ => key
+            .split_once('.')
+            .filter(|(section, key)| !section.is_empty() && !key.is_empty())
+            .ok_or_else(|| format_err!("Invalid config key `{key}`, expected `section.key`")),
+    }
+}
+
+/// Returns every source of a config value, highest precedence first.
+pub(super) fn value_sources(
+    config: &Config,
+    layers: &[ConfigLayer],
+    section: &str,
+    key: &str,
+) -> Vec<(String, Option<String>)> {
+    let name = format!("{section}.{key}");
+
+    // Values from the config files, highest precedence first.
+    let mut file_sources: Vec<_> = layers
+        .iter()
+        .map(|layer| {
+            (
+                layer.description.clone(),
+                layer.ini.get_from(Some(section), key).map(str::to_owned),
+            )
+        })
+        .collect();
+    file_sources.reverse();
+    let file_value = file_sources.iter().find_map(|(_, value)| value.clone());
+
+    // Every command line override is written back to the in-memory config,
+    // so it shows as a value that differs from the merged files.
+    let cli_value = config
+        .get_ini_value(section, key)
+        .filter(|value| Some(*value) != file_value.as_deref())
+        .map(str::to_owned);
+
+    let mut sources = vec![];
+    if cli_value.is_some() {
+        sources.push(("command line".to_owned(), cli_value));
+    }
+    let token_value = config
+        .get_token_data()
+        .and_then(|token_data| match name.as_str() {
+            "defaults.org" => Some(token_data.org.clone()),
+            "defaults.url" => Some(token_data.url.clone()),
+            _ => None,
+        })
+        .filter(|value| !value.is_empty());
+    if token_value.is_some() {
+        sources.push(("auth token".to_owned(), token_value));
+    }
+    if let Some(var) = KNOWN_KEYS
+        .iter()
+        .find(|(s, k, _)| *s == section && *k == key)
+        .and_then(|(_, _, var)| *var)
+    {
+        sources.push((format!("environment variable {var}"), env::var(var).ok()));
+    }
+    sources.extend(file_sources);
+    match name.as_str() {
+        "defaults.url" => sources.push(("default".to_owned(), Some(DEFAULT_URL.to_owned()))),
+        "defaults.vcs_remote" => sources.push(("default".to_owned(), Some("origin".to_owned()))),
+        _ => {}
+    }
+    sources
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let (section, key) = parse_key(matches.get_one::<String>("key").unwrap())?;
+    let name = format!("{section}.{key}");
+    let config = Config::current();
+    let (_, layers) = load_config_layers()?;
+    let sources = value_sources(&config, &layers, section, key);
+
+    let is_secret = SECRET_KEYS.contains(&name.as_str());
+    let display = |value: &str| {
+        if is_secret {
+            "[REDACTED]".to_owned()
+        } else {
+            value.to_owned()
+        }
+    };
+    let winner = sources.iter().position(|(_, value)| value.is_some());
+
+    if !matches.get_flag("explain") {
+        let index = winner.ok_or_else(|| format_err!("{name} is not set"))?;
+        return print_value(display(sources[index].1.as_deref().unwrap_or_default()));
+    }
+
+    match winner {
+        Some(index) => println!(
+            "{name} = {}",
+            display(sources[index].1.as_deref().unwrap_or_default())
+        ),
+        None => println!("{name} is not set"),
+    }
+    println!();
+    println!("Sources (highest precedence first):");
+    for (index, (source, value)) in sources.iter().enumerate() {
+        let value = match value {
+            Some(value) => display(value),
+            None => style("not set").dim().to_string(),
+        };
+        if Some(index) == winner {
+            println!("  {} {source}: {value}", style("*").green());
+        } else {
+            println!("    {source}: {value}");
+        }
+    }
+
+    Ok(())
+}
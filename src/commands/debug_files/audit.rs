@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
+use log::debug;
+use serde::Serialize;
+use symbolic::common::{ByteView, DebugId};
+use symbolic::debuginfo::{Archive, FileFormat};
+use walkdir::WalkDir;
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::args::ArgExt;
+use crate::utils::formatting::print_json;
+use crate::utils::system::QuietExit;
+
+#[derive(Serialize)]
+struct AuditedId {
+    debug_id: DebugId,
+    path: PathBuf,
+    arch: &'static str,
+    present: bool,
+}
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Check that the server has the debug info files at the given paths.")
+        .long_about(
+            "Check that the server has the debug info files at the given paths.{n}{n}\
+            This computes the debug ids of all debug info files in the given paths and \
+            looks them up in the project.  Missing debug ids fail the command, which \
+            catches uploads that failed in earlier steps of a build.",
+        )
+        .org_arg()
+        .project_arg(false)
+        .arg(
+            Arg::new("paths")
+                .value_name("PATH")
+                .required(true)
+                .num_args(1..)
+                .action(ArgAction::Append)
+                .help("A path to search recursively for debug info files."),
+        )
+        .arg(
+            Arg::new("report_only")
+                .long("report-only")
+                .action(ArgAction::SetTrue)
+                .help("Only report missing debug ids instead of failing."),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Format outputs as JSON."),
+        )
+}
+
+/// Returns the debug ids of all objects in the given paths, together with
+/// the first file and architecture they were found in.
+fn find_debug_ids<'a, I>(paths: I) -> Result<BTreeMap<DebugId, (PathBuf, &'static str)>>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    let mut ids = BTreeMap::new();
+    let files = paths
+        .into_iter()
+        .flat_map(WalkDir::new)
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file());
+
+    for entry in files {
+        let data = ByteView::open(entry.path())?;
+        if Archive::peek(&data) == FileFormat::Unknown {
+            continue;
+        }
+        let archive = match Archive::parse(&data) {
+            Ok(archive) => archive,
+            Err(err) => {
+                debug!("Skipping {}: {err}", entry.path().display());
+                continue;
+            }
+        };
+        for object in archive.objects().filter_map(Result::ok) {
+            let debug_id = object.debug_id();
+            if !debug_id.is_nil() {
+                ids.entry(debug_id)
+                    .or_insert_with(|| (entry.path().to_owned(), object.arch().name()));
+            }
+        }
+    }
+
+    Ok(ids)
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (org, project) = config.get_org_and_project(matches)?;
+    let ids = find_debug_ids(matches.get_many::<String>("paths").unwrap())?;
+
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let mut audited = vec![];
+    for (debug_id, (path, arch)) in ids {
+        let present = !authenticated_api
+            .list_difs_by_debug_id(&org, &project, debug_id)?
+            .is_empty();
+        audited.push(AuditedId {
+            debug_id,
+            path,
+            arch,
+            present,
+        });
+    }
+    let missing = audited.iter().filter(|id| !id.present).count();
+
+    if matches.get_flag("json") {
+        print_json(&audited)?;
+    } else {
+        for id in &audited {
+            let state = if id.present {
+                style("PRESENT").green()
+            } else {
+                style("MISSING").red()
+            };
+            println!(
+                "  {state:>7} {} ({}; {})",
+                style(id.debug_id).dim(),
+                id.path.display(),
+                id.arch
+            );
+        }
+        println!();
+        println!(
+            "{} {} of {} debug ids are missing on the server",
+            style(">").dim(),
+            missing,
+            audited.len()
+        );
+    }
+
+    if missing > 0 && !matches.get_flag("report_only") {
+        return Err(QuietExit(1).into());
+    }
+    Ok(())
+}
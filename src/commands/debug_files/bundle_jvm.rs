@@ -5,8 +5,10 @@ use crate::utils::args::ArgExt;
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::{FileUpload, SourceFile, UploadContext};
 use crate::utils::fs::path_as_url;
+use crate::utils::text_encoding::transcode_to_utf8;
 use anyhow::{bail, Context, Result};
 use clap::{Arg, ArgMatches, Command};
+use encoding::EncodingRef;
 use sentry::types::DebugId;
 use std::collections::BTreeMap;
 use std::fs;
@@ -45,6 +47,7 @@ pub fn make_command(command: Command) -> Command {
                 .value_parser(DebugId::from_str)
                 .help("Debug ID (UUID) to use for the source bundle."),
         )
+        .input_encoding_arg()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -62,7 +65,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         note: None,
         wait: true,
         max_wait: DEFAULT_MAX_WAIT,
-        dedupe: false,
+        dedupe: true,
+        prefetch_missing_chunks: false,
+        chunked_threshold: None,
         chunk_upload_options: chunk_upload_options.as_ref(),
     };
     let path = matches.get_one::<PathBuf>("path").unwrap();
@@ -85,6 +90,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         ))?;
     }
 
+    let input_encoding = matches.get_one::<EncodingRef>("input_encoding").copied();
     let sources = ReleaseFileSearch::new(path.to_path_buf()).collect_files()?;
     let files = sources
         .iter()
@@ -97,7 +103,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 SourceFile {
                     url,
                     path: source.path.clone(),
-                    contents: source.contents.clone(),
+                    contents: transcode_to_utf8(
+                        &source.contents,
+                        input_encoding,
+                        &source.path.display().to_string(),
+                    )
+                    .into_owned(),
                     ty: SourceFileType::Source,
                     headers: BTreeMap::new(),
                     messages: vec![],
@@ -8,6 +8,7 @@ use symbolic::debuginfo::sourcebundle::SourceBundleWriter;
 
 use crate::utils::dif::DifFile;
 use crate::utils::dif_upload::filter_bad_sources;
+use crate::utils::fs::{set_keep_temp, PartialFile};
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -29,6 +30,15 @@ pub fn make_command(command: Command) -> Command {
                      file is placed next to the input file.",
                 ),
         )
+        .arg(
+            Arg::new("keep_temp")
+                .long("keep-temp")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Do not delete temporary and partially written files. \
+                     Their locations are printed instead.  Useful for debugging.",
+                ),
+        )
 }
 
 fn is_dsym(path: &Path) -> bool {
@@ -69,6 +79,7 @@ fn get_canonical_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let output_path = matches.get_one::<String>("output").map(Path::new);
+    set_keep_temp(matches.get_flag("keep_temp"));
 
     for orig_path in matches.get_many::<String>("paths").unwrap() {
         let canonical_path = get_canonical_path(orig_path)?;
@@ -95,7 +106,8 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             };
 
             fs::create_dir_all(out.parent().unwrap())?;
-            let writer = SourceBundleWriter::create(&out)?;
+            let out = PartialFile::new(out);
+            let writer = SourceBundleWriter::create(out.path())?;
 
             // Resolve source files from the object and write their contents into the archive. Skip to
             // upload this bundle if no source could be written. This can happen if there is no file or
@@ -110,10 +122,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
             if !written {
                 eprintln!("skipped {orig_path} (no files found)");
-                fs::remove_file(&out)?;
                 continue;
             } else {
-                println!("{}", out.display());
+                println!("{}", out.persist().display());
             }
         }
     }
@@ -1,4 +1,3 @@
-use std::io;
 use std::path::Path;
 
 use anyhow::Result;
@@ -6,6 +5,7 @@ use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
 use console::style;
 
 use crate::utils::dif::{DifFile, DifType};
+use crate::utils::formatting::print_json;
 use crate::utils::logging::is_quiet_mode;
 use crate::utils::system::QuietExit;
 
@@ -38,6 +38,15 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Format outputs as JSON."),
         )
+        .arg(
+            Arg::new("require_inlines")
+                .long("require-inlines")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Fail if the debug info file does not contain inline function \
+                     information.",
+                ),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -56,14 +65,15 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .get_one::<String>("type")
         .map(|t| t.parse().unwrap());
     let dif = DifFile::open_path(path, ty)?;
+    let has_inlines = dif.supports_inline_info() && dif.has_inline_info();
+    let missing_inlines = matches.get_flag("require_inlines") && !has_inlines;
 
     if matches.get_flag("json") {
-        serde_json::to_writer_pretty(&mut io::stdout(), &dif)?;
-        println!();
+        print_json(&dif)?;
     }
 
     if matches.get_flag("json") || is_quiet_mode() {
-        return if dif.is_usable() {
+        return if dif.is_usable() && !missing_inlines {
             Ok(())
         } else {
             Err(QuietExit(1).into())
@@ -93,6 +103,14 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     println!("  Contained debug information:");
     println!("    > {}", dif.features());
+    if dif.supports_inline_info() {
+        let has_inlines = if has_inlines {
+            style("yes").green()
+        } else {
+            style("no").yellow()
+        };
+        println!("  Inline function information: {has_inlines}");
+    }
 
     if let Some(msg) = dif.get_note() {
         println!("  Note: {msg}");
@@ -101,6 +119,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     if let Some(prob) = dif.get_problem() {
         println!("  Usable: {} ({})", style("no").red(), prob);
         Err(QuietExit(1).into())
+    } else if missing_inlines {
+        println!(
+            "  Usable: {} (missing inline function information)",
+            style("no").red()
+        );
+        Err(QuietExit(1).into())
     } else {
         println!("  Usable: {}", style("yes").green());
         Ok(())
@@ -2,7 +2,6 @@ use std::collections::HashSet;
 use std::env;
 use std::ffi::OsStr;
 use std::fmt::Debug;
-use std::io;
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -12,11 +11,12 @@ use console::style;
 use if_chain::if_chain;
 use proguard::ProguardMapping;
 use serde::Serialize;
-use symbolic::common::{ByteView, DebugId};
+use symbolic::common::{ByteView, CodeId, DebugId};
 use uuid::{Uuid, Version as UuidVersion};
 use walkdir::{DirEntry, WalkDir};
 
 use crate::utils::dif::{DifFile, DifType};
+use crate::utils::formatting::print_json;
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 use crate::utils::system::QuietExit;
 
@@ -24,12 +24,75 @@ use crate::utils::system::QuietExit;
 // valid mapping files when scanning
 const MAX_MAPPING_FILE: u64 = 32 * 1024 * 1024;
 
+/// Where a debug information file was found.
+#[derive(Clone, Copy, Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+enum SearchSource {
+    /// Xcode's DerivedData folder.
+    WellKnown,
+    /// The current working directory.
+    Cwd,
+    /// A path given with `--path`.
+    Path,
+}
+
 #[derive(Serialize, Debug)]
 struct DifMatch {
-    #[serde(rename = "type")]
-    pub ty: DifType,
+    /// The debug id under its original key, kept for existing consumers.
     pub id: DebugId,
+    pub debug_id: DebugId,
+    pub code_id: Option<CodeId>,
     pub path: PathBuf,
+    #[serde(rename = "type")]
+    pub ty: DifType,
+    /// The kind of object, such as `exe`, `lib` or `dbg`.
+    pub kind: Option<&'static str>,
+    pub features: Vec<&'static str>,
+    pub source: SearchSource,
+    /// Whether this is the most complete of all files found for the debug id.
+    pub best_match: bool,
+}
+
+impl DifMatch {
+    fn new(id: DebugId, ty: DifType, path: PathBuf, source: SearchSource) -> Self {
+        let dif = DifFile::open_path(&path, Some(ty)).ok();
+        let code_id = dif.as_ref().and_then(|dif| {
+            dif.variants()
+                .into_iter()
+                .find(|variant| variant.debug_id == id)
+                .and_then(|variant| variant.code_id)
+        });
+        DifMatch {
+            id,
+            debug_id: id,
+            code_id,
+            path,
+            ty,
+            kind: dif.as_ref().and_then(DifFile::kind).map(|kind| kind.name()),
+            features: dif.map(|dif| dif.features().names()).unwrap_or_default(),
+            source,
+            best_match: false,
+        }
+    }
+
+    /// Ranks files by the information they contain, debug information first.
+    fn rank(&self) -> [bool; 4] {
+        ["debug", "unwind", "symtab", "sources"].map(|feature| self.features.contains(&feature))
+    }
+}
+
+/// Sorts matches and marks the best match for every debug id.  Of several
+/// equally complete files, the first one by path is picked.
+fn mark_best_matches(matches: &mut [DifMatch]) {
+    matches.sort_by(|a, b| (a.debug_id, &a.path).cmp(&(b.debug_id, &b.path)));
+    for group in matches.chunk_by_mut(|a, b| a.debug_id == b.debug_id) {
+        let best = group
+            .iter_mut()
+            .rev()
+            .max_by_key(|m| m.rank())
+            .expect("groups are not empty");
+        best.best_match = true;
+    }
 }
 
 pub fn make_command(command: Command) -> Command {
@@ -79,7 +142,11 @@ pub fn make_command(command: Command) -> Command {
             Arg::new("json")
                 .long("json")
                 .action(ArgAction::SetTrue)
-                .help("Format outputs as JSON."),
+                .help(
+                    "Format outputs as JSON.  This reports every location in which a \
+                    file was found instead of only the first one, and marks the most \
+                    complete file for every debug identifier as `best_match`.",
+                ),
         )
 }
 
@@ -97,12 +164,16 @@ fn id_hint(id: &DebugId) -> &'static str {
 }
 
 fn find_ids(
-    paths: &HashSet<PathBuf>,
+    paths: &[(PathBuf, SearchSource)],
     types: &HashSet<DifType>,
     ids: &HashSet<DebugId>,
     as_json: bool,
 ) -> Result<bool> {
+    // The identifiers to search for.  With JSON output, the search continues
+    // after the first match to report all locations.
     let mut remaining = ids.clone();
+    let mut missing = ids.clone();
+    let mut visited = HashSet::new();
     let mut breakpad_found = HashSet::new();
     let mut proguard_uuids: HashSet<_> = ids
         .iter()
@@ -112,9 +183,13 @@ fn find_ids(
 
     let iter = paths
         .iter()
-        .flat_map(WalkDir::new)
-        .filter_map(Result::ok)
-        .filter(|e| e.file_type().is_file());
+        .flat_map(|(path, source)| {
+            WalkDir::new(path)
+                .into_iter()
+                .filter_map(Result::ok)
+                .map(move |dirent| (dirent, *source))
+        })
+        .filter(|(e, _)| e.file_type().is_file());
 
     let mut found_files = vec![];
     let pb = ProgressBar::new_spinner();
@@ -127,10 +202,13 @@ fn find_ids(
             ),
     );
 
-    for dirent in iter {
+    for (dirent, source) in iter {
         if remaining.is_empty() {
             break;
         }
+        if !visited.insert(dirent.path().to_path_buf()) {
+            continue;
+        }
 
         if let Some(p) = dirent.file_name().to_str() {
             pb.set_message(p);
@@ -157,41 +235,46 @@ fn find_ids(
 
         for (id, ty) in found {
             let path = dirent.path().to_path_buf();
-            found_files.push(DifMatch { ty, id, path });
+            found_files.push(DifMatch::new(id, ty, path, source));
             if ty == DifType::Breakpad {
                 breakpad_found.insert(id);
             } else {
-                remaining.remove(&id);
+                missing.remove(&id);
+                if !as_json {
+                    remaining.remove(&id);
+                }
+            }
+            if !as_json {
+                proguard_uuids.remove(&id.uuid());
             }
-            proguard_uuids.remove(&id.uuid());
         }
     }
 
     pb.finish_and_clear();
 
     if as_json {
-        serde_json::to_writer_pretty(&mut io::stdout(), &found_files)?;
-        println!();
+        mark_best_matches(&mut found_files);
+        print_json(&found_files)?;
     } else {
         for m in found_files {
             println!(
                 "{} {} [{}]",
-                style(m.id).dim(),
+                style(m.debug_id).dim(),
                 m.path.display(),
                 style(m.ty).yellow()
             );
         }
-        remaining.extend(breakpad_found);
-        if !remaining.is_empty() {
+        missing.extend(breakpad_found);
+        if !missing.is_empty() {
             eprintln!();
             eprintln!("missing debug information files:");
-            for id in &remaining {
+            for id in &missing {
                 eprintln!("  {} ({})", id, id_hint(id),);
             }
         }
     }
 
-    Ok(remaining.is_empty())
+    Ok(missing.is_empty())
 }
 
 fn find_ids_for_proguard(
@@ -329,8 +412,15 @@ fn extract_remaining_ids(
         .collect()
 }
 
+/// Adds a path to search, unless it is searched already.
+fn add_path(paths: &mut Vec<(PathBuf, SearchSource)>, path: PathBuf, source: SearchSource) {
+    if !paths.iter().any(|(p, _)| *p == path) {
+        paths.push((path, source));
+    }
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
-    let mut paths = HashSet::new();
+    let mut paths = Vec::new();
     let mut types = HashSet::new();
     let mut ids = HashSet::new();
 
@@ -353,7 +443,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         if let Some(path) = dirs::home_dir().map(|x| x.join("Library/Developer/Xcode/DerivedData"));
         if path.is_dir();
         then {
-            paths.insert(path);
+            add_path(&mut paths, path, SearchSource::WellKnown);
         }
     }
 
@@ -362,14 +452,14 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         if with_cwd;
         if let Ok(path) = env::current_dir();
         then {
-            paths.insert(path);
+            add_path(&mut paths, path, SearchSource::Cwd);
         }
     }
 
     // extra paths
     if let Some(p) = matches.get_many::<String>("paths") {
         for path in p {
-            paths.insert(PathBuf::from(path));
+            add_path(&mut paths, PathBuf::from(path), SearchSource::Path);
         }
     }
 
@@ -0,0 +1,111 @@
+use std::path::Path;
+
+use anyhow::Result;
+use clap::{builder::ValueParser, Arg, ArgAction, ArgMatches, Command};
+use serde::Serialize;
+use symbolic::common::ByteView;
+use symbolic::debuginfo::Archive;
+
+use crate::utils::formatting::print_json;
+use crate::utils::fs::get_sha1_checksums;
+
+#[derive(Serialize)]
+struct ObjectHashes<'a> {
+    path: &'a Path,
+    debug_id: String,
+    arch: &'static str,
+    checksum: String,
+    chunks: Vec<String>,
+}
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Print the chunk hashes of debug info files without uploading them.")
+        .long_about(
+            "Print the chunk hashes of debug info files without uploading them.{n}{n}\
+            For every object in the given files, this prints the debug id, the \
+            SHA1 checksum of the object and the ordered SHA1 checksums of its \
+            chunks, as computed for chunked uploads.  This works fully offline.",
+        )
+        .arg(
+            Arg::new("paths")
+                .value_name("PATH")
+                .required(true)
+                .num_args(1..)
+                .action(ArgAction::Append)
+                .help("The paths to the debug info files."),
+        )
+        .arg(
+            Arg::new("chunk_size")
+                .long("chunk-size")
+                .value_name("BYTES")
+                .value_parser(ValueParser::new(parse_chunk_size))
+                // This matches the chunk size Sentry advertises for chunk uploads.
+                .default_value("8388608")
+                .help("The size of the chunks in bytes.  Must be a power of two."),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Format outputs as JSON."),
+        )
+}
+
+fn parse_chunk_size(value: &str) -> Result<u64, String> {
+    match value.parse::<u64>() {
+        Ok(size) if size.is_power_of_two() => Ok(size),
+        Ok(_) => Err("chunk size must be a power of two".to_owned()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let chunk_size = *matches.get_one::<u64>("chunk_size").unwrap();
+
+    let paths: Vec<_> = matches
+        .get_many::<String>("paths")
+        .unwrap()
+        .map(Path::new)
+        .collect();
+    let data = paths
+        .iter()
+        .map(ByteView::open)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut hashes = vec![];
+    for (path, data) in paths.iter().zip(&data) {
+        let archive = Archive::parse(data)?;
+        for object in archive.objects() {
+            let object = object?;
+            let (checksum, chunks) = get_sha1_checksums(object.data(), chunk_size)?;
+            hashes.push(ObjectHashes {
+                path,
+                debug_id: object.debug_id().to_string(),
+                arch: object.arch().name(),
+                checksum: checksum.to_string(),
+                chunks: chunks.iter().map(ToString::to_string).collect(),
+            });
+        }
+    }
+
+    if matches.get_flag("json") {
+        print_json(&hashes)?;
+        return Ok(());
+    }
+
+    for object in hashes {
+        println!(
+            "{} {} ({})",
+            object.debug_id,
+            object.path.display(),
+            object.arch
+        );
+        println!("  checksum: {}", object.checksum);
+        for chunk in object.chunks {
+            println!("  chunk: {chunk}");
+        }
+    }
+
+    Ok(())
+}
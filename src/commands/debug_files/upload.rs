@@ -3,21 +3,30 @@ use std::str::{self, FromStr};
 use std::time::Duration;
 
 use anyhow::{bail, format_err, Result};
-use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
+use clap::{builder::PossibleValuesParser, value_parser, Arg, ArgAction, ArgMatches, Command};
 use console::style;
 use itertools::Itertools;
-use log::info;
+use log::{info, warn};
 use symbolic::common::DebugId;
 use symbolic::debuginfo::FileFormat;
 
+use crate::api::{Api, DebugInfoFile};
 use crate::config::Config;
 use crate::constants::DEFAULT_MAX_WAIT;
 use crate::utils::args::ArgExt;
 use crate::utils::dif::{DifType, ObjectDifFeatures};
-use crate::utils::dif_upload::{DifFormat, DifUpload};
+use crate::utils::dif_upload::{ConflictPolicy, DifFormat, DifUpload};
+use crate::utils::formatting::print_json;
+use crate::utils::fs::set_keep_temp;
+use crate::utils::logging::{quiet_println, set_quiet_mode};
+use crate::utils::path_list::{expand_glob_paths, get_path_list};
 use crate::utils::system::QuietExit;
 use crate::utils::xcode::InfoPlist;
 
+/// Directories that are skipped by default, as they are large and practically
+/// never contain debug information files.
+const DEFAULT_EXCLUDED_DIRS: [&str; 4] = [".git", ".hg", ".svn", "node_modules"];
+
 static DERIVED_DATA_FOLDER: &str = "Library/Developer/Xcode/DerivedData";
 
 pub fn make_command(command: Command) -> Command {
@@ -31,11 +40,15 @@ pub fn make_command(command: Command) -> Command {
     command
         .about("Upload debugging information files.")
         .org_arg()
-        .project_arg(false)
+        .project_arg(true)
         .arg(
             Arg::new("paths")
                 .value_name("PATH")
-                .help("A path to search recursively for symbol files.")
+                .help(
+                    "A path to search recursively for symbol files.  Glob patterns such as \
+                    `build/**/*.{dSYM,so}` are expanded, even if the shell does not expand them.",
+                )
+                .conflicts_with_all(["stdin_paths", "paths_from"])
                 .num_args(1..)
                 .action(ArgAction::Append),
         )
@@ -105,11 +118,13 @@ pub fn make_command(command: Command) -> Command {
         .arg(
             Arg::new("symbol_maps")
                 .long("symbol-maps")
+                .alias("symbol-map-dir")
                 .value_name("PATH")
                 .help(
                     "Optional path to BCSymbolMap files which are used to \
                     resolve hidden symbols in dSYM files downloaded from \
-                    iTunes Connect.  This requires the dsymutil tool to be \
+                    iTunes Connect.  Directories are searched recursively. \
+                    This requires the dsymutil tool to be \
                     available.  This should not be used when using the App \
                     Store Connect integration, the .bcsymbolmap files needed \
                     for the integration are uploaded without this option if \
@@ -168,6 +183,19 @@ pub fn make_command(command: Command) -> Command {
                     "Include sources from the local file system and upload them as source bundles.",
                 ),
         )
+        .arg(
+            Arg::new("source_root")
+                .long("source-root")
+                .value_name("PATH")
+                .requires("include_sources")
+                .help(
+                    "Directory from which to read the sources referenced by DWARF debug \
+                    information in ELF and Mach-O files with --include-sources.  Source \
+                    paths are resolved relative to the compilation directory, so this \
+                    can be used when the build ran in a different directory.  Sources \
+                    not found there are read from their original path.",
+                ),
+        )
         .arg(
             Arg::new("wait")
                 .long("wait")
@@ -192,6 +220,60 @@ pub fn make_command(command: Command) -> Command {
                     significantly slow down the upload process.",
                 ),
         )
+        .arg(
+            Arg::new("verify_upload")
+                .long("verify-upload")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "After the upload, look up the debug id of every uploaded file on the \
+                    server and fail if any of them cannot be found.  This takes one more \
+                    request per file.  Implies --wait, since files are only listed once \
+                    they are processed.",
+                ),
+        )
+        .arg(
+            Arg::new("no_dedupe")
+                .long("no-dedupe")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Upload all chunks, even those the server reports to already \
+                    have.  This is meant for debugging and increases bandwidth usage.",
+                ),
+        )
+        .arg(
+            Arg::new("no_pipeline")
+                .long("no-pipeline")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Hash all debug information files before uploading any of them, \
+                    instead of uploading while the remaining files are hashed.",
+                ),
+        )
+        .arg(
+            Arg::new("require_inlines")
+                .long("require-inlines")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Fail if a debug information file has debug information but no inline \
+                    function information, instead of only warning about it.",
+                ),
+        )
+        .arg(
+            Arg::new("abort_on_partial_assemble")
+                .long("abort-on-partial-assemble")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Fail if some debug information files did not process correctly, even if \
+                    others did.  By default, such partial failures are reported, but only fail \
+                    the upload if no file processed correctly.",
+                ),
+        )
+        .arg(
+            Arg::new("allow_empty")
+                .long("allow-empty")
+                .action(ArgAction::SetTrue)
+                .help("Skip glob patterns that match no files instead of failing."),
+        )
         // Legacy flag that has no effect, left hidden for backward compatibility
         .arg(
             Arg::new("upload_symbol_maps")
@@ -205,11 +287,91 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Compute il2cpp line mappings and upload them along with sources."),
         )
+        .arg(
+            Arg::new("keep_temp")
+                .long("keep-temp")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Do not delete temporary files created while bundling and \
+                    extracting debug files.  Their locations are printed instead.",
+                ),
+        )
+        .path_list_args()
+        .arg(
+            Arg::new("exclude_dirs")
+                .long("exclude-dir")
+                .value_name("NAME-OR-GLOB")
+                .action(ArgAction::Append)
+                .value_parser(glob::Pattern::new)
+                .default_values(DEFAULT_EXCLUDED_DIRS)
+                .help(
+                    "Do not descend into directories with this name or matching \
+                    this glob pattern while searching for debug files.  This \
+                    argument can be specified multiple times.  Passing it \
+                    replaces the default list.",
+                ),
+        )
+        .arg(
+            Arg::new("prune_after")
+                .long("prune-after")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Delete the local debug information files once the server has \
+                    processed them successfully, to free disk space.  Files in archives \
+                    and files outside of the given paths are never deleted.",
+                ),
+        )
+        .arg(
+            Arg::new("on_conflict")
+                .long("on-conflict")
+                .value_name("POLICY")
+                .value_parser(["prefer-debug", "prefer-first", "upload-both", "error"])
+                .default_value("upload-both")
+                .help(
+                    "How to handle several files with the same debug id, such as a \
+                    stripped and an unstripped copy.  `prefer-debug` uploads only the \
+                    file with debug information, `prefer-first` only the file found \
+                    first, and `error` fails the upload.  By default, all of them are \
+                    uploaded, since companion files share debug ids.",
+                ),
+        )
+        .arg(
+            Arg::new("max_concurrent_assembles")
+                .value_name("N")
+                .long("max-concurrent-assembles")
+                .value_parser(value_parser!(u32).range(1..))
+                .help(
+                    "Check at most N batches of uploaded debug information files at once \
+                     while waiting for the server to process them.  The remaining batches \
+                     are queued.  Defaults to the upload concurrency of the server.  Can \
+                     also be set with SENTRY_HTTP_MAX_CONCURRENT_ASSEMBLES.",
+                ),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("no_upload")
+                .help(
+                    "Print a single JSON document instead of the upload report, with \
+                    the project, path, debug id and status (uploaded, skipped or error) \
+                    of the files from all given paths and a summary of the counts.",
+                ),
+        )
+        .chunk_upload_args()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
-    let (org, project) = config.get_org_and_project(matches)?;
+    let org = config.get_org(matches)?;
+    let projects = config.get_projects(matches)?;
+    set_keep_temp(matches.get_flag("keep_temp"));
+    let json = matches.get_flag("json");
+
+    // Keep stdout free of anything but the JSON report.
+    if json {
+        set_quiet_mode(true);
+    }
 
     let ids = matches
         .get_many::<DebugId>("ids")
@@ -218,21 +380,51 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     info!(
         "Issuing a command for Organization: {} Project: {}",
-        org, project
+        org,
+        projects.join(", ")
     );
 
     let wait_for_secs = matches.get_one::<u64>("wait_for").copied();
-    let wait = matches.get_flag("wait") || wait_for_secs.is_some();
+    let verify_upload = matches.get_flag("verify_upload");
+    let wait = matches.get_flag("wait") || wait_for_secs.is_some() || verify_upload;
     let max_wait = wait_for_secs.map_or(DEFAULT_MAX_WAIT, Duration::from_secs);
 
+    let dedupe = !matches.get_flag("no_dedupe");
+    if !dedupe {
+        warn!("--no-dedupe uploads all chunks, which increases bandwidth usage.");
+    }
+
     // Build generic upload parameters
-    let mut upload = DifUpload::new(org.clone(), project.clone());
+    let mut upload = DifUpload::new(org.clone(), projects.clone());
+    match get_path_list(matches)? {
+        Some(paths) => upload.search_paths(paths),
+        None => upload.search_paths(expand_glob_paths(
+            matches.get_many::<String>("paths").unwrap_or_default(),
+            matches.get_flag("allow_empty"),
+        )?),
+    };
     upload
         .wait(wait)
         .max_wait(max_wait)
-        .search_paths(matches.get_many::<String>("paths").unwrap_or_default())
+        .dedupe(dedupe)
+        .pipeline(!matches.get_flag("no_pipeline"))
+        .require_inlines(matches.get_flag("require_inlines"))
+        .abort_on_partial_assemble(matches.get_flag("abort_on_partial_assemble"))
+        .prune_after(matches.get_flag("prune_after"))
         .allow_zips(!matches.get_flag("no_zips"))
+        .exclude_dirs(
+            matches
+                .get_many::<glob::Pattern>("exclude_dirs")
+                .unwrap_or_default()
+                .cloned(),
+        )
         .filter_ids(ids);
+    if let Some(on_conflict) = matches
+        .get_one::<String>("on_conflict")
+        .and_then(|name| ConflictPolicy::from_name(name))
+    {
+        upload.on_conflict(on_conflict);
+    }
 
     // Restrict symbol types, if specified by the user
     for ty in matches
@@ -272,6 +464,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     });
 
     upload.include_sources(matches.get_flag("include_sources"));
+    if let Some(source_root) = matches.get_one::<String>("source_root") {
+        upload.source_root(source_root);
+    }
     upload.il2cpp_mapping(matches.get_flag("il2cpp_mapping"));
 
     // Configure BCSymbolMap resolution, if possible
@@ -305,6 +500,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     // Execute the upload
     let (uploaded, has_processing_errors) = upload.upload()?;
+    if json {
+        print_json(upload.report())?;
+    }
 
     // Did we miss explicitly requested symbols?
     if matches.get_flag("require_all") {
@@ -314,7 +512,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             .cloned()
             .collect();
 
-        let found_ids = uploaded.into_iter().map(|dif| dif.id()).collect();
+        let found_ids = uploaded.iter().map(|dif| dif.id()).collect();
         let missing_ids: Vec<_> = required_ids.difference(&found_ids).collect();
 
         if !missing_ids.is_empty() {
@@ -336,5 +534,53 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         return Err(QuietExit(1).into());
     }
 
+    if verify_upload {
+        let rejected = upload.rejected_projects();
+        for project in projects.iter().filter(|p| !rejected.contains(p)) {
+            verify_uploaded_difs(&org, project, &uploaded)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the server lists every uploaded file under its debug id.
+fn verify_uploaded_difs(org: &str, project: &str, uploaded: &[DebugInfoFile]) -> Result<()> {
+    if uploaded.is_empty() {
+        return Ok(());
+    }
+
+    quiet_println!();
+    quiet_println!(
+        "{} Verifying uploaded debug information files",
+        style(">").dim()
+    );
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let mut missing = vec![];
+    for dif in uploaded {
+        let present = !authenticated_api
+            .list_difs_by_debug_id(org, project, dif.id())?
+            .is_empty();
+        let state = if present {
+            style("FOUND").green()
+        } else {
+            missing.push(dif.id());
+            style("MISSING").red()
+        };
+        quiet_println!(
+            "  {state:>7} {} ({}; {})",
+            style(dif.id()).dim(),
+            dif.object_name,
+            dif.cpu_name
+        );
+    }
+
+    if !missing.is_empty() {
+        bail!(
+            "Uploaded debug information files are missing on the server: {}",
+            missing.iter().join(", ")
+        );
+    }
     Ok(())
 }
@@ -3,6 +3,7 @@ use clap::{Arg, ArgAction, ArgMatches, Command};
 
 use crate::api::Api;
 use crate::config::Config;
+use crate::utils::args::{get_batch_size, ArgExt};
 use crate::utils::formatting::Table;
 
 pub fn make_command(command: Command) -> Command {
@@ -37,6 +38,7 @@ pub fn make_command(command: Command) -> Command {
                 .value_parser(clap::value_parser!(usize))
                 .help("Maximum number of pages to fetch (100 events/page)."),
         )
+        .batch_size_arg()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -46,9 +48,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let pages = *matches.get_one("pages").unwrap();
     let api = Api::current();
 
-    let events = api
-        .authenticated()?
-        .list_organization_project_events(&org, &project, pages)?;
+    let events = api.authenticated()?.list_organization_project_events(
+        &org,
+        &project,
+        pages,
+        get_batch_size(matches),
+    )?;
 
     let mut table = Table::new();
     let title_row = table.title_row().add("Event ID").add("Date").add("Title");
@@ -5,6 +5,7 @@ use clap::{Arg, ArgAction, ArgMatches, Command};
 
 use crate::api::Api;
 use crate::config::Config;
+use crate::utils::ui::confirm_destructive;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -36,6 +37,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let authenticated_api = api.authenticated()?;
 
     if matches.get_flag("all") {
+        if !confirm_destructive(&format!(
+            "Do you really want to delete all files of release {release}?"
+        ))? {
+            println!("Aborted!");
+            return Ok(());
+        }
         authenticated_api.delete_release_files(&org, project.as_deref(), &release)?;
         println!("All files deleted.");
         return Ok(());
@@ -45,6 +52,17 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         Some(paths) => paths.map(|x| x.into()).collect(),
         None => HashSet::new(),
     };
+    if files.is_empty() {
+        return Ok(());
+    }
+    if !confirm_destructive(&format!(
+        "Do you really want to delete {} files of release {release}?",
+        files.len()
+    ))? {
+        println!("Aborted!");
+        return Ok(());
+    }
+
     for file in authenticated_api.list_release_files(&org, project.as_deref(), &release)? {
         if !files.contains(&file.name) {
             continue;
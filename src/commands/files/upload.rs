@@ -2,21 +2,26 @@ use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fs;
 use std::io::Read;
+use std::iter;
 use std::path::Path;
 use std::time::Duration;
 
 use anyhow::{bail, format_err, Result};
+use clap::builder::PossibleValuesParser;
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
 use log::warn;
 use symbolic::debuginfo::sourcebundle::SourceFileType;
 
-use crate::api::Api;
+use crate::api::{Api, ChunkUploadCapability};
 use crate::config::Config;
 use crate::constants::DEFAULT_MAX_WAIT;
-use crate::utils::args::validate_distribution;
+use crate::utils::args::{get_url_prefix, validate_distribution, ArgExt};
+use crate::utils::chunks::use_chunked_upload;
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::{
-    initialize_legacy_release_upload, FileUpload, SourceFile, UploadContext,
+    initialize_legacy_release_upload, uses_artifact_bundles, FileUpload, IfExists, SourceFile,
+    UploadContext,
 };
 use crate::utils::fs::{decompress_gzip_content, is_gzip_compressed, path_as_url};
 use crate::utils::progress::ProgressBarMode;
@@ -69,6 +74,18 @@ pub fn make_command(command: Command) -> Command {
                      but at most for the given number of seconds.",
                 ),
         )
+        .arg(
+            Arg::new("if_exists")
+                .long("if-exists")
+                .value_name("ACTION")
+                .value_parser(PossibleValuesParser::new(IfExists::all_names()))
+                .help(
+                    "What to do if a file with the same name and dist already exists \
+                     in the release: skip it, overwrite it or fail the upload. \
+                     Defaults to overwriting for directories and failing for single files.  \
+                     Not supported if the server stores uploads in artifact bundles.",
+                ),
+        )
         .arg(
             Arg::new("file-headers")
                 .long("file-header")
@@ -82,7 +99,20 @@ pub fn make_command(command: Command) -> Command {
                 .short('u')
                 .long("url-prefix")
                 .value_name("PREFIX")
-                .help("The URL prefix to prepend to all filenames."),
+                .help(
+                    "The URL prefix to prepend to all filenames.  Relative URLs start with \
+                     `~/`, which matches any scheme and host.  Trailing slashes are removed, \
+                     except for those of an empty host: `~/static/` becomes `~/static` and \
+                     `app:///` becomes `app://`.  Prefixes without `~/` or a scheme and other \
+                     suspicious forms cause a warning with a suggested correction.",
+                ),
+        )
+        .arg(
+            Arg::new("strict_url_prefix")
+                .long("strict-url-prefix")
+                .action(ArgAction::SetTrue)
+                .requires("url_prefix")
+                .help("Fail instead of warning if the --url-prefix looks suspicious."),
         )
         .arg(
             Arg::new("url_suffix")
@@ -120,6 +150,7 @@ pub fn make_command(command: Command) -> Command {
                     extensions must be repeated. Specify once per extension.",
                 ),
         )
+        .chunk_upload_args()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -143,6 +174,11 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     };
 
+    let if_exists = matches
+        .get_one::<String>("if_exists")
+        .map(|value| value.parse::<IfExists>())
+        .transpose()?;
+
     let wait_for_secs = matches.get_one::<u64>("wait_for").copied();
     let wait = matches.get_flag("wait") || wait_for_secs.is_some();
     let max_wait = wait_for_secs.map_or(DEFAULT_MAX_WAIT, Duration::from_secs);
@@ -155,7 +191,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         note: None,
         wait,
         max_wait,
-        dedupe: false,
+        dedupe: true,
+        prefetch_missing_chunks: false,
+        chunked_threshold: None,
         chunk_upload_options: chunk_upload_options.as_ref(),
     };
 
@@ -175,25 +213,18 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             .map(|extensions| extensions.map(|ext| ext.trim_start_matches('.')).collect())
             .unwrap_or_default();
 
+        let url_suffix = matches
+            .get_one::<String>("url_suffix")
+            .map(String::as_str)
+            .unwrap_or_default();
+        let url_prefix = get_url_prefix(matches)?.unwrap_or_else(|| "~".into());
+
         let sources = ReleaseFileSearch::new(path.to_path_buf())
             .ignore_file(ignore_file)
             .ignores(ignores)
             .extensions(extensions)
             .decompress(matches.get_flag("decompress"))
             .collect_files()?;
-
-        let url_suffix = matches
-            .get_one::<String>("url_suffix")
-            .map(String::as_str)
-            .unwrap_or_default();
-        let mut url_prefix = matches
-            .get_one::<String>("url_prefix")
-            .map(String::as_str)
-            .unwrap_or("~");
-        // remove a single slash from the end.  so ~/ becomes ~ and app:/// becomes app://
-        if url_prefix.ends_with('/') {
-            url_prefix = &url_prefix[..url_prefix.len() - 1];
-        }
         let files = sources
             .iter()
             .map(|source| {
@@ -215,12 +246,13 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             })
             .collect();
 
-        FileUpload::new(context).files(&files).upload()
+        FileUpload::new(context)
+            .files(&files)
+            .if_exists(if_exists)
+            .upload()
     }
     // Single file upload
     else {
-        initialize_legacy_release_upload(context)?;
-
         let name = match matches.get_one::<String>("name") {
             Some(name) => name,
             None => Path::new(path)
@@ -240,6 +272,65 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             });
         }
 
+        // Files that span multiple chunks are uploaded through the chunk upload
+        // endpoint, so that a failed request only has to resend its own chunks.
+        // Bundles only hold text files, so binary files are still sent in a single
+        // request.  Servers that support artifact bundles would store the file in
+        // one instead of the release, so the file is sent in a single request to
+        // them as well.
+        let spans_chunks = chunk_upload_options
+            .as_ref()
+            .is_some_and(|options| contents.len() as u64 > options.chunk_size)
+            && std::str::from_utf8(&contents).is_ok();
+        if spans_chunks
+            && !uses_artifact_bundles(context)
+            && use_chunked_upload(
+                chunk_upload_options.as_ref(),
+                ChunkUploadCapability::ReleaseFiles,
+            )?
+        {
+            let files = iter::once((
+                name.to_owned(),
+                SourceFile {
+                    url: name.to_owned(),
+                    path: path.to_path_buf(),
+                    contents,
+                    ty: SourceFileType::Source,
+                    headers,
+                    messages: vec![],
+                    already_uploaded: false,
+                },
+            ))
+            .collect();
+            return FileUpload::new(context)
+                .files(&files)
+                .if_exists(Some(if_exists.unwrap_or(IfExists::Fail)))
+                .upload();
+        }
+
+        initialize_legacy_release_upload(context)?;
+
+        if let Some(if_exists @ (IfExists::Skip | IfExists::Overwrite)) = if_exists {
+            let existing = authenticated_api
+                .list_release_files(context.org, context.project, &release)?
+                .into_iter()
+                .find(|artifact| artifact.name == name && artifact.dist.as_deref() == dist);
+
+            if let Some(existing) = existing {
+                if if_exists == IfExists::Skip {
+                    println!("  {:<9} {}", style("skip").dim(), name);
+                    return Ok(());
+                }
+                authenticated_api.delete_release_file(
+                    context.org,
+                    context.project,
+                    &release,
+                    &existing.id,
+                )?;
+                println!("  {:<9} {}", style("overwrite").dim(), name);
+            }
+        }
+
         if let Some(artifact) = authenticated_api
             .region_specific(context.org)
             .upload_release_file(
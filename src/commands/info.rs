@@ -1,11 +1,10 @@
-use std::io;
-
 use anyhow::Result;
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use serde::Serialize;
 
 use crate::api::Api;
 use crate::config::{Auth, Config};
+use crate::utils::formatting::{print_json, Table};
 use crate::utils::logging::is_quiet_mode;
 use crate::utils::system::QuietExit;
 
@@ -30,6 +29,12 @@ pub struct Status {
     have_dsn: bool,
 }
 
+#[derive(Serialize)]
+struct RegionInfo {
+    name: String,
+    url: String,
+}
+
 pub fn make_command(command: Command) -> Command {
     command
         .about("Print information about the configuration and verify authentication.")
@@ -53,6 +58,22 @@ pub fn make_command(command: Command) -> Command {
                     without the need for setting other defaults.",
                 ),
         )
+        .arg(
+            Arg::new("list_regions")
+                .long("list-regions")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "List the regions available to the authenticated token together \
+                     with their URLs.  Self-hosted servers report the configured URL.",
+                ),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .requires("list_regions")
+                .help("Format the list of regions as JSON."),
+        )
 }
 
 fn describe_auth(auth: Option<&Auth>) -> &str {
@@ -80,8 +101,42 @@ fn get_config_status_json() -> Result<()> {
         config.get_auth().is_some() && Api::current().authenticated()?.get_auth_info().is_ok();
     rv.have_dsn = config.get_dsn().is_ok();
 
-    serde_json::to_writer_pretty(&mut io::stdout(), &rv)?;
-    println!();
+    print_json(&rv)?;
+    Ok(())
+}
+
+fn list_regions(json: bool) -> Result<()> {
+    let config = Config::current();
+    let mut regions: Vec<_> = Api::current()
+        .authenticated()?
+        .list_available_regions()?
+        .into_iter()
+        .map(|region| RegionInfo {
+            name: region.name,
+            url: region.url,
+        })
+        .collect();
+
+    // Self-hosted servers do not know about regions.
+    if regions.is_empty() {
+        regions.push(RegionInfo {
+            name: "default".into(),
+            url: config.get_base_url()?.into(),
+        });
+    }
+
+    if json {
+        print_json(&regions)?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.title_row().add("Name").add("URL");
+    for region in &regions {
+        table.add_row().add(&region.name).add(&region.url);
+    }
+    table.print();
+
     Ok(())
 }
 
@@ -90,6 +145,10 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         return get_config_status_json();
     }
 
+    if matches.get_flag("list_regions") {
+        return list_regions(matches.get_flag("json"));
+    }
+
     let config = Config::current();
     let (org, project) = config.get_org_and_project_defaults();
     let org = org.filter(|s| !s.is_empty());
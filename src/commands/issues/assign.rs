@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, AuthenticatedApi, IssueUpdate};
+use crate::config::Config;
+
+use super::{with_exit_codes, NotFound};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Assign a single issue to a user or team.")
+        .arg(
+            Arg::new("issue")
+                .value_name("ISSUE")
+                .required(true)
+                .help("The numeric ID or the short ID of the issue."),
+        )
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .value_name("ASSIGNEE")
+                .required(true)
+                .help(
+                    "The user or team to assign the issue to.  Users are matched by \
+                     email, username or ID; teams are given as `#slug` or `team:slug`.",
+                ),
+        )
+}
+
+/// Resolves an assignee to the actor the API expects, along with a label
+/// used for output.
+fn resolve_assignee(api: &AuthenticatedApi, org: &str, assignee: &str) -> Result<(String, String)> {
+    if let Some(slug) = assignee
+        .strip_prefix('#')
+        .or_else(|| assignee.strip_prefix("team:"))
+    {
+        return api
+            .list_organization_teams(org)?
+            .into_iter()
+            .find(|team| team.slug == slug)
+            .map(|team| (format!("team:{}", team.id), format!("#{}", team.slug)))
+            .ok_or_else(|| NotFound(format!("Team {slug} not found in {org}")).into());
+    }
+
+    let assignee = assignee.strip_prefix("user:").unwrap_or(assignee);
+    api.list_organization_members(org)?
+        .into_iter()
+        .find_map(|member| {
+            let user = member.user?;
+            (member.email.eq_ignore_ascii_case(assignee)
+                || user.username == assignee
+                || user.id == assignee)
+                .then(|| (format!("user:{}", user.id), member.email))
+        })
+        .ok_or_else(|| NotFound(format!("User {assignee} not found in {org}")).into())
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let issue_id = matches.get_one::<String>("issue").unwrap();
+    let assignee = matches.get_one::<String>("to").unwrap();
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+
+    with_exit_codes(assign_issue(&authenticated_api, &org, issue_id, assignee))
+}
+
+fn assign_issue(api: &AuthenticatedApi, org: &str, issue_id: &str, assignee: &str) -> Result<()> {
+    let issue = api
+        .get_issue(org, issue_id)?
+        .ok_or_else(|| NotFound(format!("Issue {issue_id} not found")))?;
+    let (actor, label) = resolve_assignee(api, org, assignee)?;
+
+    let update = IssueUpdate {
+        assigned_to: Some(actor),
+    };
+    api.update_issue(org, &issue.id, &update)?
+        .ok_or_else(|| NotFound(format!("Issue {issue_id} not found")))?;
+
+    println!("Assigned {} to {}.", issue.short_id, label);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::api::{Api, AuthenticatedApi};
+use crate::config::Config;
+
+use super::{with_exit_codes, NotFound};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Add a comment to a single issue.")
+        .arg(
+            Arg::new("issue")
+                .value_name("ISSUE")
+                .required(true)
+                .help("The numeric ID or the short ID of the issue."),
+        )
+        .arg(
+            Arg::new("text")
+                .long("text")
+                .value_name("TEXT")
+                .required(true)
+                .help("The text of the comment."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let issue_id = matches.get_one::<String>("issue").unwrap();
+    let text = matches.get_one::<String>("text").unwrap();
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+
+    with_exit_codes(comment_on_issue(&authenticated_api, &org, issue_id, text))
+}
+
+fn comment_on_issue(api: &AuthenticatedApi, org: &str, issue_id: &str, text: &str) -> Result<()> {
+    let issue = api
+        .get_issue(org, issue_id)?
+        .ok_or_else(|| NotFound(format!("Issue {issue_id} not found")))?;
+    if !api.create_issue_comment(org, &issue.id, text)? {
+        return Err(NotFound(format!("Issue {issue_id} not found")).into());
+    }
+
+    println!("Added comment to {}.", issue.short_id);
+    Ok(())
+}
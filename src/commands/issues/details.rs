@@ -0,0 +1,89 @@
+use anyhow::{format_err, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+
+use crate::api::Api;
+use crate::config::Config;
+use crate::utils::formatting::{print_json, Table};
+
+pub fn make_command(command: Command) -> Command {
+    command
+        .about("Show the details of a single issue.")
+        .arg(
+            Arg::new("issue")
+                .value_name("ISSUE")
+                .required(true)
+                .help("The numeric ID or the short ID of the issue."),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Format outputs as JSON."),
+        )
+        .arg(
+            Arg::new("with_latest_event")
+                .long("with-latest-event")
+                .action(ArgAction::SetTrue)
+                .help("Also fetch the latest event of the issue."),
+        )
+}
+
+pub fn execute(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let org = config.get_org(matches)?;
+    let issue_id = matches.get_one::<String>("issue").unwrap();
+    let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+
+    let issue = authenticated_api
+        .get_issue(&org, issue_id)?
+        .ok_or_else(|| format_err!("Issue {} not found", issue_id))?;
+
+    let latest_event = if matches.get_flag("with_latest_event") {
+        authenticated_api.get_issue_latest_event(&org, &issue.id)?
+    } else {
+        None
+    };
+
+    if matches.get_flag("json") {
+        let mut output = serde_json::to_value(&issue)?;
+        if let Some(event) = latest_event {
+            output["latestEvent"] = event;
+        }
+        print_json(&output)?;
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .title_row()
+        .add("Issue ID")
+        .add("Short ID")
+        .add("Title")
+        .add("First seen")
+        .add("Last seen")
+        .add("Events")
+        .add("Users")
+        .add("Assignee")
+        .add("Status");
+    table
+        .add_row()
+        .add(&issue.id)
+        .add(&issue.short_id)
+        .add(&issue.title)
+        .add(&issue.first_seen)
+        .add(&issue.last_seen)
+        .add(&issue.count)
+        .add(issue.user_count)
+        .add(issue.assigned_to.as_ref().map_or("-", |a| a.name.as_str()))
+        .add(&issue.status);
+    table.print();
+
+    if let Some(event) = latest_event {
+        if let Some(event_id) = event.get("eventID").and_then(|id| id.as_str()) {
+            println!("Latest event: {event_id}");
+        }
+    }
+
+    Ok(())
+}
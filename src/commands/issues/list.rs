@@ -1,9 +1,10 @@
 use anyhow::Result;
-use clap::{Arg, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 
-use crate::api::Api;
+use crate::api::{Api, IssueListQuery};
 use crate::config::Config;
-use crate::utils::formatting::Table;
+use crate::utils::args::{get_batch_size, ArgExt};
+use crate::utils::formatting::{print_json, Table};
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -30,6 +31,33 @@ pub fn make_command(command: Command) -> Command {
                 .default_value("")
                 .help("Query to pass at the request. An example is \"is:unresolved\""),
         )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("SORT")
+                .value_parser(["date", "new", "freq", "priority", "user"])
+                .help(
+                    "Sort issues by when they were last seen (`date`), first seen (`new`), \
+                     by number of events (`freq`), priority or number of users.",
+                ),
+        )
+        .arg(
+            Arg::new("stats_period")
+                .long("stats-period")
+                .value_name("PERIOD")
+                .value_parser(["24h", "14d"])
+                .help(
+                    "Include the number of events within the period, in addition to the \
+                     total number of events and users.",
+                ),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Format outputs as JSON."),
+        )
+        .batch_size_arg()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -37,37 +65,64 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let org = config.get_org(matches)?;
     let project = config.get_project(matches)?;
     let pages = *matches.get_one("pages").unwrap();
-    let query = matches.get_one::<String>("query").cloned();
+    let stats_period = matches.get_one::<String>("stats_period");
+    let issue_query = IssueListQuery {
+        query: matches.get_one::<String>("query").cloned(),
+        sort: matches.get_one::<String>("sort").cloned(),
+        stats_period: stats_period.cloned(),
+    };
     let api = Api::current();
 
-    let issues = api
-        .authenticated()?
-        .list_organization_project_issues(&org, &project, pages, query)?;
+    let mut issues = api.authenticated()?.list_organization_project_issues(
+        &org,
+        &project,
+        pages,
+        &issue_query,
+        get_batch_size(matches),
+    )?;
+
+    issues.truncate(*matches.get_one("max_rows").unwrap_or(&usize::MAX));
+
+    if matches.get_flag("json") {
+        return print_json(&issues);
+    }
 
     let mut table = Table::new();
-    table
-        .title_row()
+    let title_row = table.title_row();
+    title_row
         .add("Issue ID")
         .add("Short ID")
         .add("Title")
         .add("Last seen")
         .add("Status")
         .add("Level");
+    if let Some(stats_period) = stats_period {
+        title_row
+            .add("Events")
+            .add("Users")
+            .add(format!("Events ({stats_period})"));
+    }
 
-    let max_rows = std::cmp::min(
-        issues.len(),
-        *matches.get_one("max_rows").unwrap_or(&usize::MAX),
-    );
-
-    if let Some(issues) = issues.get(..max_rows) {
-        for issue in issues {
-            let row = table.add_row();
-            row.add(&issue.id)
-                .add(&issue.short_id)
-                .add(&issue.title)
-                .add(&issue.last_seen)
-                .add(&issue.status)
-                .add(&issue.level);
+    for issue in &issues {
+        let row = table.add_row();
+        row.add(&issue.id)
+            .add(&issue.short_id)
+            .add(&issue.title)
+            .add(&issue.last_seen)
+            .add(&issue.status)
+            .add(&issue.level);
+        if let Some(stats_period) = stats_period {
+            row.add(issue.count.as_deref().unwrap_or("-"))
+                .add(
+                    issue
+                        .user_count
+                        .map_or("-".to_owned(), |count| count.to_string()),
+                )
+                .add(
+                    issue
+                        .events_in_period(stats_period)
+                        .map_or("-".to_owned(), |count| count.to_string()),
+                );
         }
     }
 
@@ -1,15 +1,55 @@
-use anyhow::Result;
+use anyhow::{Error, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 
+use crate::api::ApiError;
 use crate::utils::args::ArgExt;
+use crate::utils::system::{print_error, QuietExit};
 
+pub mod assign;
+pub mod comment;
+pub mod details;
 pub mod list;
 pub mod mute;
 pub mod resolve;
 pub mod unresolve;
 
+/// Exit code used when the issue, user or team does not exist.
+const EXIT_NOT_FOUND: i32 = 2;
+/// Exit code used when the request was rejected for lack of permissions.
+const EXIT_PERMISSION_DENIED: i32 = 3;
+
+/// Something an issue mutation refers to could not be found.
+#[derive(thiserror::Error, Debug)]
+#[error("{0}")]
+struct NotFound(String);
+
+/// Reports not-found and permission errors with distinct exit codes, so that
+/// automation can tell them apart from other failures.
+fn with_exit_codes(result: Result<()>) -> Result<()> {
+    let Err(err) = result else {
+        return Ok(());
+    };
+    let code = if err.is::<NotFound>() {
+        EXIT_NOT_FOUND
+    } else {
+        match err
+            .downcast_ref::<ApiError>()
+            .and_then(ApiError::http_status)
+        {
+            Some(404) => EXIT_NOT_FOUND,
+            Some(401 | 403) => EXIT_PERMISSION_DENIED,
+            _ => return Err(err),
+        }
+    };
+    print_error(&err);
+    Err(Error::from(QuietExit(code)))
+}
+
 macro_rules! each_subcommand {
     ($mac:ident) => {
+        $mac!(assign);
+        $mac!(comment);
+        $mac!(details);
         $mac!(list);
         $mac!(mute);
         $mac!(resolve);
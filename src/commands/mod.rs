@@ -5,25 +5,41 @@ use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
 use clap_complete::{generate, Generator, Shell};
 use log::{debug, info, set_logger, set_max_level, LevelFilter};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::io;
+use std::path::PathBuf;
 use std::process;
+use std::time::{Duration, Instant};
 use std::{env, iter};
 
-use crate::api::Api;
-use crate::config::{Auth, Config};
-use crate::constants::{ARCH, PLATFORM, VERSION};
+use crate::api::{Api, MultipartBoundaryCharset};
+use crate::config::{is_project_id, Auth, Config};
+use crate::constants::{ARCH, DEFAULT_API_VERSION, PLATFORM, VERSION};
+use crate::utils::args::validate_proxy_url;
 use crate::utils::auth_token::{redact_token_from_string, AuthToken};
-use crate::utils::logging::set_quiet_mode;
+use crate::utils::chunks::UploadProtocol;
+use crate::utils::formatting::{set_no_newline, set_yaml_output};
+use crate::utils::fs::{
+    install_temp_cleanup_handler, set_cache_locking, DEFAULT_LOCK_TIMEOUT_SECS,
+};
 use crate::utils::logging::Logger;
-use crate::utils::system::{init_backtrace, load_dotenv, print_error, QuietExit};
+use crate::utils::logging::{is_quiet_mode, set_quiet_mode};
+use crate::utils::metrics_summary::format_metrics_summary;
+use crate::utils::progress::{set_progress_output, ProgressOutput};
+use crate::utils::system::{
+    expand_env_in_arg, init_backtrace, load_dotenv, print_error, set_json_errors, QuietExit,
+};
+use crate::utils::ui::{set_confirmation_mode, ConfirmationMode};
 use crate::utils::update::run_sentrycli_update_nagger;
-use crate::utils::value_parsers::auth_token_parser;
+use crate::utils::value_parsers::{auth_token_parser, rate_limit_parser};
 
 mod derive_parser;
 
 macro_rules! each_subcommand {
     ($mac:ident) => {
         $mac!(bash_hook);
+        $mac!(config);
         $mac!(debug_files);
         $mac!(deploys);
         $mac!(events);
@@ -82,6 +98,18 @@ const UPDATE_NAGGER_CMDS: &[&str] = &[
     "sourcemaps",
 ];
 
+/// Subcommands after which the metrics summary is printed.
+const UPLOAD_CMDS: &[&str] = &[
+    "appcenter",
+    "gradle",
+    "upload",
+    "upload-dif",
+    "upload-dsym",
+    "upload-proguard",
+    "upload-sourcemaps",
+    "xcode",
+];
+
 /// The long auth token argument (--auth-token).
 const AUTH_TOKEN_ARG: &str = "auth-token";
 
@@ -122,6 +150,86 @@ fn configure_args(config: &mut Config, matches: &ArgMatches) -> Result<()> {
         config.set_base_url(url);
     }
 
+    if let Some(api_version) = matches.get_one::<u32>("api_version") {
+        config.set_api_version(*api_version);
+    }
+
+    if let Some(keepalive) = matches.get_one::<u64>("http_keepalive") {
+        config.set_keepalive_timeout(*keepalive);
+    }
+
+    if let Some(connect_timeout) = matches.get_one::<u64>("connect_timeout") {
+        config.set_preflight_connect_timeout(*connect_timeout);
+    }
+
+    if let Some(rate_limit) = matches.get_one::<f64>("rate_limit") {
+        config.set_rate_limit(*rate_limit);
+    }
+
+    // Upload options are only defined by the commands that upload files.
+    let mut command_matches = matches;
+    while let Some((_, sub_matches)) = command_matches.subcommand() {
+        command_matches = sub_matches;
+    }
+
+    if let Ok(Some(retries)) = command_matches.try_get_one::<u32>("upload_max_retries") {
+        config.set_upload_max_retries(*retries);
+    }
+
+    if let Ok(Some(seconds)) = command_matches.try_get_one::<u64>("assemble_poll_interval") {
+        config.set_assemble_poll_interval(*seconds);
+    }
+
+    if let Ok(Some(attempts)) = command_matches.try_get_one::<u32>("assemble_max_attempts") {
+        config.set_assemble_max_attempts(*attempts);
+    }
+
+    if let Ok(Some(max)) = command_matches.try_get_one::<u32>("max_concurrent_assembles") {
+        config.set_max_concurrent_assembles(*max);
+    }
+
+    if let Some(max_chunk_batch) = matches.get_one::<u64>("max_chunk_batch") {
+        config.set_max_chunk_batch(*max_chunk_batch);
+    }
+
+    if matches.get_flag("retry_idempotent_post") {
+        config.set_retry_idempotent_post();
+    }
+
+    if let Some(max_redirects) = matches.get_one::<u32>("max_redirects") {
+        config.set_max_redirects(*max_redirects);
+    }
+
+    if let Some(charset) = matches
+        .get_one::<String>("multipart_boundary_charset")
+        .and_then(|name| MultipartBoundaryCharset::from_name(name))
+    {
+        config.set_multipart_boundary_charset(charset);
+    }
+
+    if let Some(protocol) = matches
+        .get_one::<String>("upload_protocol")
+        .and_then(|name| UploadProtocol::from_name(name))
+    {
+        config.set_upload_protocol(protocol);
+    }
+
+    if let Some(dir) = matches.get_one::<PathBuf>("dump_request_bodies") {
+        config.set_dump_request_bodies(dir, matches.get_flag("dump_full"));
+    }
+
+    if let Some(proxy_url) = matches.get_one::<String>("proxy_url") {
+        config.set_proxy_url(proxy_url);
+    }
+
+    if let Some(proxy_username) = matches.get_one::<String>("proxy_username") {
+        config.set_proxy_username(proxy_username);
+    }
+
+    if let Some(proxy_password) = matches.get_one::<String>("proxy_password") {
+        config.set_proxy_password(proxy_password);
+    }
+
     if let Some(headers) = matches.get_many::<String>("headers") {
         let headers = headers.map(|h| h.to_owned()).collect();
         config.set_headers(headers);
@@ -142,6 +250,48 @@ pub fn get_log_level(matches: &ArgMatches) -> Result<Option<LevelFilter>> {
     }
 }
 
+fn get_progress_output(matches: &ArgMatches) -> ProgressOutput {
+    if matches.get_flag("dumb_terminal") {
+        return ProgressOutput::Plain;
+    }
+    match matches
+        .get_one::<String>("output_progress")
+        .map(String::as_str)
+    {
+        Some("bar") => ProgressOutput::Bar,
+        Some("plain") => ProgressOutput::Plain,
+        _ => ProgressOutput::Auto,
+    }
+}
+
+/// Overrides the detected color support.  `--force-foreground-color` wins over
+/// `NO_COLOR`, which wins over `--dumb-terminal`.
+fn configure_colors(matches: &ArgMatches) {
+    let colors = if matches.get_flag("force_foreground_color") {
+        Some(true)
+    } else if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+        || matches.get_flag("dumb_terminal")
+    {
+        Some(false)
+    } else {
+        None
+    };
+    if let Some(colors) = colors {
+        console::set_colors_enabled(colors);
+        console::set_colors_enabled_stderr(colors);
+    }
+}
+
+fn get_confirmation_mode(matches: &ArgMatches) -> ConfirmationMode {
+    if matches.get_flag("assume_yes") {
+        ConfirmationMode::AssumeYes
+    } else if matches.get_flag("interactive") {
+        ConfirmationMode::Interactive
+    } else {
+        ConfirmationMode::Auto
+    }
+}
+
 fn app() -> Command {
     Command::new("sentry-cli")
         .version(VERSION)
@@ -202,6 +352,298 @@ fn app() -> Command {
               .hide(true)
               .help("Always return 0 exit code."),
         )
+        .arg(
+            Arg::new("api_version")
+                .value_name("VERSION")
+                .long("api-version")
+                .value_parser(value_parser!(u32))
+                .help(format!(
+                    "The version of the Sentry API to target.{{n}}\
+                     [default: {DEFAULT_API_VERSION}]"
+                )),
+        )
+        .arg(
+            Arg::new("http_keepalive")
+                .value_name("SECONDS")
+                .long("http-keepalive")
+                .value_parser(value_parser!(u64))
+                .help(
+                    "How long idle connections are kept open for reuse \
+                     between requests.  Use 0 to disable keep-alive.",
+                ),
+        )
+        .arg(
+            Arg::new("proxy_url")
+                .value_name("URL")
+                .long("proxy-url")
+                .value_parser(validate_proxy_url)
+                .help(
+                    "The proxy to connect through (http, https or socks5).  \
+                     Takes precedence over proxy environment variables.",
+                ),
+        )
+        .arg(
+            Arg::new("proxy_username")
+                .value_name("USERNAME")
+                .long("proxy-username")
+                .help(
+                    "The username to authenticate with the proxy.  \
+                     Can also be set with SENTRY_HTTP_PROXY_USERNAME.",
+                ),
+        )
+        .arg(
+            Arg::new("proxy_password")
+                .value_name("PASSWORD")
+                .long("proxy-password")
+                .help(
+                    "The password to authenticate with the proxy.  \
+                     Can also be set with SENTRY_HTTP_PROXY_PASSWORD.",
+                ),
+        )
+        .arg(
+            Arg::new("max_chunk_batch")
+                .value_name("N")
+                .long("max-chunk-batch")
+                .value_parser(value_parser!(u64).range(1..))
+                .help(
+                    "Send at most this many chunks per upload request.  \
+                     The server's limits are never exceeded.",
+                ),
+        )
+        .arg(
+            Arg::new("retry_idempotent_post")
+                .long("retry-idempotent-post")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Also retry assemble requests on connection errors and \
+                     internal server errors.",
+                ),
+        )
+        .arg(
+            Arg::new("output_progress")
+                .value_name("MODE")
+                .long("output-progress")
+                .value_parser(["auto", "bar", "plain"])
+                .default_value("auto")
+                .help(
+                    "How to render progress.  `plain` prints periodic status \
+                     lines suitable for CI logs.  `auto` uses `plain` if stdout \
+                     is not a terminal.",
+                ),
+        )
+        .arg(
+            Arg::new("assume_yes")
+                .long("yes")
+                .visible_alias("assume-yes")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Skip confirmation prompts of destructive operations \
+                     (releases delete, releases archive, files delete, uninstall).  \
+                     Without this flag, these operations fail if the session is not \
+                     interactive.",
+                ),
+        )
+        .arg(
+            Arg::new("interactive")
+                .long("interactive")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .conflicts_with("assume_yes")
+                .help(
+                    "Prompt for confirmation of destructive operations even if the \
+                     session is not interactive.",
+                ),
+        )
+        .arg(
+            Arg::new("no_newline")
+                .long("no-newline")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Do not print a trailing newline after the result of commands that print \
+                     a single value or JSON document, for capturing it in shell scripts.",
+                ),
+        )
+        .arg(
+            Arg::new("no_metrics_summary")
+                .long("no-metrics-summary")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Do not print the line with the number of requests, retries, uploaded \
+                     bytes and deduplicated chunks to stderr when an upload command \
+                     finishes.  Can also be disabled with SENTRY_METRICS_SUMMARY=0.",
+                ),
+        )
+        .arg(
+            Arg::new("force_foreground_color")
+                .long("force-foreground-color")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Always color the output, even if no terminal is detected.  This takes \
+                     precedence over NO_COLOR, which in turn takes precedence over \
+                     --dumb-terminal and the detected terminal capabilities.",
+                ),
+        )
+        .arg(
+            Arg::new("dumb_terminal")
+                .long("dumb-terminal")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Assume a terminal without support for colors or cursor movement.  \
+                     Progress bars and spinners are replaced by plain status lines.",
+                ),
+        )
+        .arg(
+            Arg::new("error_format")
+                .value_name("FORMAT")
+                .long("error-format")
+                .value_parser(["text", "json"])
+                .default_value("text")
+                .global(true)
+                .help(
+                    "The format errors are printed to stderr in.  `json` prints a single \
+                     line with the error, its causes and, for failed requests, the HTTP \
+                     status, the server's request id and its response.",
+                ),
+        )
+        .arg(
+            Arg::new("max_redirects")
+                .value_name("N")
+                .long("max-redirects")
+                .value_parser(clap::value_parser!(u32))
+                .global(true)
+                .help(
+                    "The maximum number of redirects followed per request [default: 5].  \
+                     Credentials are not sent along redirects to other hosts.  Can also \
+                     be set with SENTRY_HTTP_MAX_REDIRECTS.",
+                ),
+        )
+        .arg(
+            Arg::new("output")
+                .value_name("FORMAT")
+                .long("output")
+                .value_parser(["json", "yaml"])
+                .default_value("json")
+                .help(
+                    "The format of the structured output that commands print with \
+                     `--json`.  Both formats describe the same data, other output is \
+                     not affected.  Must be given before the subcommand.",
+                ),
+        )
+        .arg(
+            Arg::new("expand_env_in_args")
+                .long("expand-env-in-args")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Expand `${VAR}` references to environment variables in option \
+                     values, for runners that do not expand them.  Unset variables \
+                     are an error unless a default is given as `${VAR:-default}`, \
+                     which is also used for empty variables.",
+                ),
+        )
+        .arg(
+            Arg::new("no_lock")
+                .long("no-lock")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Do not lock the cache directory.  By default, concurrent sentry-cli \
+                     processes take turns accessing it.",
+                ),
+        )
+        .arg(
+            Arg::new("lock_timeout")
+                .value_name("SECONDS")
+                .long("lock-timeout")
+                .value_parser(value_parser!(u64))
+                .global(true)
+                .help(
+                    "How long to wait for another sentry-cli process to release the lock \
+                     on the cache directory before failing [default: 60].",
+                ),
+        )
+        .arg(
+            Arg::new("print_env")
+                .long("print-env")
+                .action(ArgAction::SetTrue)
+                .global(true)
+                .help(
+                    "Print the SENTRY_* and proxy environment variables to stderr before \
+                     running the command, with secrets redacted, and which config values \
+                     they set.  See `sentry-cli config dump` for the full configuration.",
+                ),
+        )
+        .arg(
+            Arg::new("connect_timeout")
+                .value_name("SECONDS")
+                .long("connect-timeout")
+                .value_parser(value_parser!(u64))
+                .global(true)
+                .help(
+                    "How long to wait for a connection to the server when uploads start \
+                     [default: 10].  Later requests of the upload wait longer.  Use 0 to \
+                     wait as long as for other requests.  Can also be set with \
+                     SENTRY_HTTP_CONNECT_TIMEOUT.",
+                ),
+        )
+        .arg(
+            Arg::new("rate_limit")
+                .value_name("N/SEC")
+                .long("rate-limit")
+                .value_parser(rate_limit_parser)
+                .global(true)
+                .help(
+                    "Send at most N requests per second, spaced out evenly, e.g. for \
+                     listing or updating many issues.  Requests rejected with status 429 \
+                     are retried after the time the server asks for.  Can also be set \
+                     with SENTRY_HTTP_RATE_LIMIT.",
+                ),
+        )
+        .arg(
+            Arg::new("multipart_boundary_charset")
+                .value_name("CHARSET")
+                .long("multipart-boundary-charset")
+                .value_parser(["default", "simple"])
+                .help(
+                    "The characters used for multipart upload boundaries.  \
+                     `simple` restricts them to letters and digits for proxies \
+                     that reject other characters.",
+                ),
+        )
+        .arg(
+            Arg::new("upload_protocol")
+                .value_name("PROTOCOL")
+                .long("upload-protocol")
+                .value_parser(["auto", "chunked", "legacy"])
+                .help(
+                    "Force chunked or legacy uploads instead of choosing based on \
+                     what the server supports.  Forcing a protocol the server does \
+                     not support fails the upload.",
+                ),
+        )
+        .arg(
+            Arg::new("dump_request_bodies")
+                .value_name("DIR")
+                .long("dump-request-bodies")
+                .value_parser(value_parser!(PathBuf))
+                .help(
+                    "Write the body of every request into this directory, along \
+                     with the method, URL and redacted headers.  Bodies larger \
+                     than 1 MiB are truncated.",
+                ),
+        )
+        .arg(
+            Arg::new("dump_full")
+                .long("dump-full")
+                .action(ArgAction::SetTrue)
+                .requires("dump_request_bodies")
+                .help("Do not truncate large bodies written by --dump-request-bodies."),
+        )
         .subcommand(
             Command::new("completions")
             .about("Generate completions for the specified shell.")
@@ -244,6 +686,128 @@ fn run_command(matches: &ArgMatches) -> Result<()> {
     unreachable!();
 }
 
+/// Returns the name and matches of the invoked subcommand.
+fn leaf_subcommand(matches: &ArgMatches) -> (Option<&str>, &ArgMatches) {
+    let mut matches = matches;
+    let mut name = None;
+    while let Some((sub_name, sub_matches)) = matches.subcommand() {
+        name = Some(sub_name);
+        matches = sub_matches;
+    }
+    (name, matches)
+}
+
+/// Indicates whether the invoked subcommand uploads files.
+fn is_upload_command(matches: &ArgMatches) -> bool {
+    let (name, _) = leaf_subcommand(matches);
+    name.is_some_and(|name| UPLOAD_CMDS.contains(&name))
+}
+
+/// Looks up the slugs of projects given by their numeric ID and binds a
+/// config that returns slugs for all projects of the invoked subcommand.
+fn resolve_project_ids(matches: &ArgMatches) -> Result<()> {
+    let config = Config::current();
+    let (_, matches) = leaf_subcommand(matches);
+    let projects: Vec<String> = match matches.try_get_many::<String>("project") {
+        Ok(Some(projects)) => projects.cloned().collect(),
+        Ok(None) => config.get_project_default().into_iter().collect(),
+        // The subcommand does not take a project.
+        Err(_) => return Ok(()),
+    };
+    if !projects.iter().any(|project| is_project_id(project)) {
+        return Ok(());
+    }
+
+    let org = config.get_org(matches)?;
+    debug!("resolving project IDs in organization {org}");
+    let slugs: HashMap<_, _> = Api::with_config(config.clone())
+        .authenticated()?
+        .list_organization_projects(&org, None)?
+        .into_iter()
+        .map(|project| (project.id, project.slug))
+        .collect();
+
+    let mut config = (*config).clone();
+    config.set_project_slugs(slugs);
+    config.bind_to_process();
+    Ok(())
+}
+
+/// Returns the command line arguments, with environment variables in option
+/// values expanded if `--expand-env-in-args` is passed.  This happens before
+/// parsing, so that the expanded values are validated like any other.
+/// Positional arguments and everything after `--` are passed through as is.
+fn get_args(cmd: &Command) -> Result<Vec<OsString>> {
+    let args: Vec<OsString> = env::args_os().collect();
+    if !args
+        .iter()
+        .skip(1)
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == "--expand-env-in-args")
+    {
+        return Ok(args);
+    }
+
+    // The commands whose options may appear, from the root command down to
+    // the current subcommand.
+    let mut commands = vec![cmd];
+    let takes_value = |commands: &[&Command], matches: &dyn Fn(&Arg) -> bool| {
+        commands
+            .iter()
+            .flat_map(|cmd| cmd.get_arguments())
+            .find(|arg| matches(arg))
+            .is_some_and(|arg| arg.get_action().takes_values())
+    };
+
+    let mut expanded = Vec::with_capacity(args.len());
+    let mut args = args.into_iter();
+    expanded.extend(args.next());
+    let mut is_value = false;
+    while let Some(arg) = args.next() {
+        let Some(value) = arg.to_str() else {
+            expanded.push(arg);
+            is_value = false;
+            continue;
+        };
+
+        if is_value {
+            expanded.push(expand_env_in_arg(value)?.into());
+            is_value = false;
+        } else if value == "--" {
+            expanded.push(arg);
+            expanded.extend(args.by_ref());
+        } else if let Some(long) = value.strip_prefix("--") {
+            if let Some((name, option_value)) = long.split_once('=') {
+                expanded.push(format!("--{name}={}", expand_env_in_arg(option_value)?).into());
+            } else {
+                is_value = takes_value(&commands, &|arg| {
+                    arg.get_long_and_visible_aliases()
+                        .is_some_and(|names| names.contains(&long))
+                });
+                expanded.push(arg);
+            }
+        } else if let Some(short) = value.strip_prefix('-').and_then(|s| s.chars().next()) {
+            let takes_value = takes_value(&commands, &|arg| {
+                arg.get_short_and_visible_aliases()
+                    .is_some_and(|names| names.contains(&short))
+            });
+            let attached = &value[1 + short.len_utf8()..];
+            if takes_value && !attached.is_empty() {
+                expanded.push(format!("-{short}{}", expand_env_in_arg(attached)?).into());
+            } else {
+                is_value = takes_value;
+                expanded.push(arg);
+            }
+        } else {
+            if let Some(subcommand) = commands.last().and_then(|cmd| cmd.find_subcommand(value)) {
+                commands.push(subcommand);
+            }
+            expanded.push(arg);
+        }
+    }
+    Ok(expanded)
+}
+
 pub fn execute() -> Result<()> {
     // special case for the xcode integration for react native.  For more
     // information see commands/react_native_xcode.rs
@@ -253,7 +817,8 @@ pub fn execute() -> Result<()> {
 
     let mut cmd = app();
     cmd = add_commands(cmd);
-    let matches = cmd.get_matches();
+    let args = get_args(&cmd)?;
+    let matches = cmd.get_matches_from(args);
     let log_level = get_log_level(&matches)?;
     if let Some(log_level) = log_level {
         set_max_level(log_level);
@@ -261,13 +826,40 @@ pub fn execute() -> Result<()> {
     let mut config = Config::from_cli_config()?;
     configure_args(&mut config, &matches)?;
     set_quiet_mode(matches.get_flag("quiet"));
+    set_no_newline(matches.get_flag("no_newline"));
+    set_json_errors(
+        matches
+            .get_one::<String>("error_format")
+            .map(String::as_str)
+            == Some("json"),
+    );
+    set_yaml_output(matches.get_one::<String>("output").map(String::as_str) == Some("yaml"));
+    set_cache_locking(
+        !matches.get_flag("no_lock"),
+        Duration::from_secs(
+            matches
+                .get_one::<u64>("lock_timeout")
+                .copied()
+                .unwrap_or(DEFAULT_LOCK_TIMEOUT_SECS),
+        ),
+    );
+    configure_colors(&matches);
+    set_progress_output(get_progress_output(&matches));
+    set_confirmation_mode(get_confirmation_mode(&matches));
 
     if let Some(log_level) = log_level {
         config.set_log_level(log_level);
     }
 
+    // Print the environment before any network access, so that it is also
+    // shown if resolving the project fails.
+    if matches.get_flag("print_env") {
+        config::print_env(&config)?;
+    }
+
     // bind the config to the process and fetch an immutable reference to it
     config.bind_to_process();
+    resolve_project_ids(&matches)?;
     if Config::current().get_filename().exists() {
         info!(
             "Loaded config from {}",
@@ -316,7 +908,16 @@ pub fn execute() -> Result<()> {
         }
     }
 
-    match run_command(&matches) {
+    let started = Instant::now();
+    let result = run_command(&matches);
+    if is_upload_command(&matches)
+        && Config::current().get_metrics_summary(&matches)
+        && !is_quiet_mode()
+    {
+        eprintln!("{}", format_metrics_summary(started.elapsed()));
+    }
+
+    match result {
         Ok(()) => Ok(()),
         Err(e) => {
             if Config::current().get_allow_failure(&matches) {
@@ -343,6 +944,9 @@ fn setup() {
     set_max_level(LevelFilter::Debug);
     set_logger(&Logger).unwrap();
 
+    // make sure temporary files do not outlive an interrupted run
+    install_temp_cleanup_handler();
+
     if let Err(e) = load_dotenv_result {
         log::warn!("Failed to load .env file: {}", e);
     }
@@ -1,11 +1,14 @@
 use chrono_tz::Tz;
 use std::process;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-use anyhow::Result;
-use clap::{Arg, ArgMatches, Command};
+use anyhow::{bail, Result};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 use console::style;
+use rand::Rng;
 
 use sentry::protocol::{MonitorCheckIn, MonitorCheckInStatus, MonitorConfig, MonitorSchedule};
 
@@ -13,6 +16,34 @@ use crate::api::envelopes_api::EnvelopesApi;
 use crate::utils::system::QuietExit;
 use crate::utils::value_parsers::auth_token_parser;
 
+/// The check-in margin in minutes that Sentry uses if none is configured.
+const DEFAULT_CHECKIN_MARGIN: u64 = 1;
+
+/// Sends in-progress check-ins while the wrapped command runs.
+struct KeepAlive {
+    interval: Duration,
+    jitter: Duration,
+}
+
+impl KeepAlive {
+    /// Returns the time until the next in-progress check-in.  Jitter only ever
+    /// sends check-ins early, so they are never later than the interval.
+    fn next_delay(&self) -> Duration {
+        self.interval - random_delay(self.jitter)
+    }
+}
+
+fn random_delay(max: Duration) -> Duration {
+    rand::thread_rng().gen_range(Duration::ZERO..=max)
+}
+
+/// Returns the maximum delay of the start check-in, which must not exceed the
+/// check-in margin or the check-in is considered missed.
+fn max_start_delay(jitter: Duration, checkin_margin: Option<u64>) -> Duration {
+    let margin = checkin_margin.unwrap_or(DEFAULT_CHECKIN_MARGIN);
+    jitter.min(Duration::from_secs(margin * 60))
+}
+
 pub fn make_command(command: Command) -> Command {
     command
         .about("Wraps a command")
@@ -96,6 +127,58 @@ pub fn make_command(command: Command) -> Command {
                      issue. Requires --schedule.",
                 ),
         )
+        .arg(
+            Arg::new("allow_failure")
+                .long("allow-failure")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("propagate_exit")
+                .help(
+                    "Always exit with code 0, even if the wrapped command fails. The \
+                     check-in is still reported as failed in that case.",
+                ),
+        )
+        .arg(
+            Arg::new("propagate_exit")
+                .long("propagate-exit")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Exit with the exit code of the wrapped command. This is the default \
+                     unless --allow-failure is passed.",
+                ),
+        )
+        .arg(
+            Arg::new("keep_alive_checkins")
+                .long("keep-alive-checkins")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .help(
+                    "Send an in-progress check-in every SECONDS seconds while the command \
+                     runs.",
+                ),
+        )
+        .arg(
+            Arg::new("heartbeat_jitter")
+                .long("heartbeat-jitter")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .requires("keep_alive_checkins")
+                .help(
+                    "Send every in-progress check-in up to SECONDS seconds early, chosen at \
+                     random, so that check-ins from many hosts spread out. Must be less than \
+                     --keep-alive-checkins.",
+                ),
+        )
+        .arg(
+            Arg::new("start_jitter")
+                .long("start-jitter")
+                .value_name("SECONDS")
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "Delay the start of the command and its check-in by up to SECONDS \
+                     seconds, chosen at random, so that jobs scheduled at the same time do \
+                     not check in at once. The delay never exceeds the check-in margin.",
+                ),
+        )
         // Hide auth token from --help output
         .arg(
             Arg::new("auth_token")
@@ -133,6 +216,7 @@ fn execute_checkin(
     monitor_slug: &str,
     environment: &str,
     monitor_config: Option<MonitorConfig>,
+    keep_alive: Option<KeepAlive>,
 ) -> Result<(bool, Option<i32>)> {
     let check_in_id = Uuid::new_v4();
 
@@ -152,7 +236,31 @@ fn execute_checkin(
         log::info!("Continuing to run program...");
     }
 
-    let (success, code, elapsed) = run_program(args, monitor_slug);
+    let (success, code, elapsed) = thread::scope(|scope| {
+        // Dropping the sender stops the in-progress check-ins.
+        let (_stop, stopped) = mpsc::channel::<()>();
+        if let Some(keep_alive) = keep_alive {
+            let envelopes_api = &envelopes_api;
+            scope.spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) =
+                    stopped.recv_timeout(keep_alive.next_delay())
+                {
+                    let keep_alive_checkin = MonitorCheckIn {
+                        check_in_id,
+                        monitor_slug: monitor_slug.to_string(),
+                        status: MonitorCheckInStatus::InProgress,
+                        duration: None,
+                        environment: Some(environment.to_string()),
+                        monitor_config: None,
+                    };
+                    if let Err(e) = envelopes_api.send_envelope(keep_alive_checkin) {
+                        log::error!("Failed to send keep-alive check-in envelope: {e}");
+                    }
+                }
+            });
+        }
+        run_program(args, monitor_slug)
+    });
 
     let status = if success {
         MonitorCheckInStatus::Ok
@@ -200,9 +308,41 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let environment = matches.get_one::<String>("environment").unwrap();
     let monitor_config = parse_monitor_config_args(matches)?;
 
-    let (success, code) = execute_checkin(args, monitor_slug, environment, monitor_config)?;
+    let keep_alive = match matches.get_one::<u64>("keep_alive_checkins") {
+        Some(&interval) => {
+            let jitter = matches
+                .get_one::<u64>("heartbeat_jitter")
+                .copied()
+                .unwrap_or(0);
+            if jitter >= interval {
+                bail!("--heartbeat-jitter must be less than --keep-alive-checkins");
+            }
+            Some(KeepAlive {
+                interval: Duration::from_secs(interval),
+                jitter: Duration::from_secs(jitter),
+            })
+        }
+        None => None,
+    };
+
+    if let Some(&jitter) = matches.get_one::<u64>("start_jitter") {
+        let max_delay = max_start_delay(
+            Duration::from_secs(jitter),
+            monitor_config
+                .as_ref()
+                .and_then(|config| config.checkin_margin),
+        );
+        let delay = random_delay(max_delay);
+        log::info!("Delaying the start by {}s", delay.as_secs_f64());
+        thread::sleep(delay);
+    }
+
+    let (success, code) =
+        execute_checkin(args, monitor_slug, environment, monitor_config, keep_alive)?;
 
-    if !success {
+    // The check-in status always reflects the outcome of the wrapped command,
+    // --allow-failure only decouples the exit code of sentry-cli from it.
+    if !success && !matches.get_flag("allow_failure") {
         return Err(QuietExit(code.unwrap_or(1)).into());
     }
 
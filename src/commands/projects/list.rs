@@ -3,17 +3,22 @@ use clap::{ArgMatches, Command};
 
 use crate::api::Api;
 use crate::config::Config;
+use crate::utils::args::{get_batch_size, ArgExt};
 use crate::utils::formatting::Table;
 
 pub fn make_command(command: Command) -> Command {
-    command.about("List all projects for an organization.")
+    command
+        .about("List all projects for an organization.")
+        .batch_size_arg()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
     let org = config.get_org(matches)?;
-    let mut projects = api.authenticated()?.list_organization_projects(&org)?;
+    let mut projects = api
+        .authenticated()?
+        .list_organization_projects(&org, get_batch_size(matches))?;
     projects.sort_by_key(|p| {
         (
             p.team.as_ref().map_or(String::new(), |t| t.name.clone()),
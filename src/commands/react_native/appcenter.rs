@@ -16,6 +16,7 @@ use crate::utils::appcenter::{get_appcenter_package, get_react_native_appcenter_
 use crate::utils::args::{validate_distribution, ArgExt};
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::UploadContext;
+use crate::utils::formatting::print_value;
 use crate::utils::sourcemaps::SourceMapProcessor;
 
 pub fn make_command(command: Command) -> Command {
@@ -106,6 +107,7 @@ pub fn make_command(command: Command) -> Command {
                      but at most for the given number of seconds.",
                 ),
         )
+        .chunk_upload_args()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -147,8 +149,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             .map(String::as_str),
     )?;
     if print_release_name {
-        println!("{release}");
-        return Ok(());
+        return print_value(release);
     }
 
     println!(
@@ -201,7 +202,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 note: None,
                 wait,
                 max_wait,
-                dedupe: false,
+                dedupe: true,
+                prefetch_missing_chunks: false,
+                chunked_threshold: None,
                 chunk_upload_options: chunk_upload_options.as_ref(),
             })?;
         }
@@ -220,7 +223,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                     note: None,
                     wait,
                     max_wait,
-                    dedupe: false,
+                    dedupe: true,
+                    prefetch_missing_chunks: false,
+                    chunked_threshold: None,
                     chunk_upload_options: chunk_upload_options.as_ref(),
                 })?;
             }
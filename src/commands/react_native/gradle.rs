@@ -66,6 +66,7 @@ pub fn make_command(command: Command) -> Command {
                      but at most for the given number of seconds.",
                 ),
         )
+        .chunk_upload_args()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -134,7 +135,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 note: None,
                 wait,
                 max_wait,
-                dedupe: false,
+                dedupe: true,
+                prefetch_missing_chunks: false,
+                chunked_threshold: None,
                 chunk_upload_options: chunk_upload_options.as_ref(),
             })?;
         }
@@ -148,7 +151,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             note: None,
             wait,
             max_wait,
-            dedupe: false,
+            dedupe: true,
+            prefetch_missing_chunks: false,
+            chunked_threshold: None,
             chunk_upload_options: chunk_upload_options.as_ref(),
         })?;
     }
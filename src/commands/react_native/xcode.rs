@@ -125,6 +125,7 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Don't try to automatically read release from Xcode project files."),
         )
+        .chunk_upload_args()
 }
 
 fn find_node() -> String {
@@ -351,7 +352,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             note: None,
             wait,
             max_wait,
-            dedupe: false,
+            dedupe: true,
+            prefetch_missing_chunks: false,
+            chunked_threshold: None,
             chunk_upload_options: chunk_upload_options.as_ref(),
         })?;
     } else {
@@ -387,7 +390,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                     note: None,
                     wait,
                     max_wait,
-                    dedupe: false,
+                    dedupe: true,
+                    prefetch_missing_chunks: false,
+                    chunked_threshold: None,
                     chunk_upload_options: chunk_upload_options.as_ref(),
                 })?;
             }
@@ -401,7 +406,9 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                         note: None,
                         wait,
                         max_wait,
-                        dedupe: false,
+                        dedupe: true,
+                        prefetch_missing_chunks: false,
+                        chunked_threshold: None,
                         chunk_upload_options: chunk_upload_options.as_ref(),
                     })?;
                 }
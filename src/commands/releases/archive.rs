@@ -4,6 +4,7 @@ use clap::{ArgMatches, Command};
 use crate::api::{Api, ReleaseStatus, UpdatedRelease};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
+use crate::utils::ui::confirm_destructive;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -17,6 +18,11 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let api = Api::current();
     let version = matches.get_one::<String>("version").unwrap();
 
+    if !confirm_destructive(&format!("Do you really want to archive release {version}?"))? {
+        println!("Aborted!");
+        return Ok(());
+    }
+
     let info_rv = api.authenticated()?.update_release(
         &config.get_org(matches)?,
         version,
@@ -4,6 +4,7 @@ use clap::{ArgMatches, Command};
 use crate::api::Api;
 use crate::config::Config;
 use crate::utils::args::ArgExt;
+use crate::utils::ui::confirm_destructive;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -18,6 +19,11 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let version = matches.get_one::<String>("version").unwrap();
     let project = config.get_project(matches).ok();
 
+    if !confirm_destructive(&format!("Do you really want to delete release {version}?"))? {
+        println!("Aborted!");
+        return Ok(());
+    }
+
     if api.authenticated()?.delete_release(
         &config.get_org(matches)?,
         project.as_deref(),
@@ -1,13 +1,14 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use chrono::{DateTime, Utc};
-use clap::{Arg, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use log::info;
 
-use crate::api::{Api, UpdatedRelease};
+use crate::api::{Api, AuthenticatedApi, UpdatedRelease};
 use crate::config::Config;
 use crate::utils::args::{get_timestamp, ArgExt};
 
 pub fn make_command(command: Command) -> Command {
-    command
+    let command = command
         .about("Mark a release as finalized and released.")
         .allow_hyphen_values(true)
         .version_arg(false)
@@ -30,16 +31,91 @@ pub fn make_command(command: Command) -> Command {
                 .value_parser(get_timestamp)
                 .value_name("TIMESTAMP")
                 .help("Set the release time. [defaults to the current time]"),
+        );
+    verify_args(command)
+}
+
+/// Adds the arguments for verifying a release before it is finalized.
+pub fn verify_args(command: Command) -> Command {
+    command
+        .arg(
+            Arg::new("verify_before_finalize")
+                .long("verify-before-finalize")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Refuse to finalize the release if it has neither artifacts \
+                    (such as source maps) nor commits.",
+                ),
+        )
+        .arg(
+            Arg::new("require_artifacts")
+                .long("require-artifacts")
+                .action(ArgAction::SetTrue)
+                .requires("verify_before_finalize")
+                .help("With --verify-before-finalize, require at least one artifact."),
+        )
+        .arg(
+            Arg::new("require_commits")
+                .long("require-commits")
+                .action(ArgAction::SetTrue)
+                .requires("verify_before_finalize")
+                .help("With --verify-before-finalize, require at least one commit."),
         )
 }
 
+/// Checks that the release has the artifacts or commits requested with
+/// `--verify-before-finalize` and fails if it does not.
+pub fn verify_release(
+    matches: &ArgMatches,
+    api: &AuthenticatedApi,
+    org: &str,
+    version: &str,
+) -> Result<()> {
+    if !matches.get_flag("verify_before_finalize") {
+        return Ok(());
+    }
+
+    let config = Config::current();
+    let project = config.get_project(matches).ok();
+    let has_artifacts = || -> Result<bool> {
+        let artifacts = api.list_release_files(org, project.as_deref(), version)?;
+        info!("release {} has {} artifacts", version, artifacts.len());
+        Ok(!artifacts.is_empty())
+    };
+    let has_commits = || -> Result<bool> {
+        let commits = api
+            .get_release_commits(org, project.as_deref(), version)?
+            .unwrap_or_default();
+        info!("release {} has {} commits", version, commits.len());
+        Ok(!commits.is_empty())
+    };
+
+    let require_artifacts = matches.get_flag("require_artifacts");
+    let require_commits = matches.get_flag("require_commits");
+    if require_artifacts && !has_artifacts()? {
+        bail!("Release {version} has no artifacts, refusing to finalize it.");
+    }
+    if require_commits && !has_commits()? {
+        bail!("Release {version} has no commits, refusing to finalize it.");
+    }
+    if !require_artifacts && !require_commits && !has_artifacts()? && !has_commits()? {
+        bail!("Release {version} has neither artifacts nor commits, refusing to finalize it.");
+    }
+
+    Ok(())
+}
+
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let org = config.get_org(matches)?;
     let version = matches.get_one::<String>("version").unwrap();
 
-    api.authenticated()?.update_release(
-        &config.get_org(matches)?,
+    verify_release(matches, &authenticated_api, &org, version)?;
+
+    authenticated_api.update_release(
+        &org,
         version,
         &UpdatedRelease {
             projects: config.get_projects(matches).ok(),
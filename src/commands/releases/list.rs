@@ -1,10 +1,13 @@
+use std::cmp::{Ordering, Reverse};
+
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 
-use crate::api::Api;
+use crate::api::{Api, ReleaseInfo};
 use crate::config::Config;
-use crate::utils::formatting::{HumanDuration, Table};
+use crate::utils::args::{get_batch_size, get_timestamp_or_duration, ArgExt};
+use crate::utils::formatting::{print_json, print_value, HumanDuration, Table};
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -38,16 +41,118 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .hide(true),
         )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("ORDER")
+                .value_parser(["date", "version", "sessions"])
+                .help(
+                    "Sort releases by creation date, version or number of sessions, \
+                    newest or largest first.  Sorting by sessions is done by the server.",
+                ),
+        )
+        .arg(
+            Arg::new("since")
+                .long("since")
+                .value_name("TIME")
+                .value_parser(get_timestamp_or_duration)
+                .help(
+                    "Only list releases created at or after this time.  Accepts an \
+                    ISO 8601 date or a duration such as 12h or 7d before now.",
+                ),
+        )
+        .arg(
+            Arg::new("until")
+                .long("until")
+                .value_name("TIME")
+                .value_parser(get_timestamp_or_duration)
+                .help(
+                    "Only list releases created at or before this time.  Accepts an \
+                    ISO 8601 date or a duration such as 12h or 7d before now.",
+                ),
+        )
+        .arg(
+            Arg::new("max_rows")
+                .long("max-rows")
+                .visible_alias("limit")
+                .value_name("MAX_ROWS")
+                .value_parser(clap::value_parser!(usize))
+                .help("Maximum number of releases to list."),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("raw")
+                .help("Format outputs as JSON."),
+        )
+        .batch_size_arg()
+}
+
+/// Compares release versions, using semantic versioning for the part after the
+/// package name where possible.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| semver::Version::parse(version.rsplit('@').next()?).ok();
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
     let project = config.get_project(matches).ok();
-    let releases = api
-        .authenticated()?
-        .list_releases(&config.get_org(matches)?, project.as_deref())?;
+    let sort = matches.get_one::<String>("sort").map(String::as_str);
+    let server_sort = match sort {
+        Some("version") | None => None,
+        Some(sort) => Some(sort),
+    };
+    let since = matches.get_one::<DateTime<Utc>>("since");
+    let until = matches.get_one::<DateTime<Utc>>("until");
+
+    // Without a time window, only the first page is listed.  Otherwise, pages
+    // are fetched until one reaches past `--since`, which requires the server
+    // to list the newest releases first.
+    let newest_first = server_sort != Some("sessions");
+    let more = |page: &[ReleaseInfo]| match since {
+        Some(since) if newest_first => page
+            .iter()
+            .all(|release_info| release_info.date_created >= *since),
+        _ => since.is_some() || until.is_some(),
+    };
+    let mut releases = api.authenticated()?.list_releases(
+        &config.get_org(matches)?,
+        project.as_deref(),
+        server_sort,
+        get_batch_size(matches),
+        more,
+    )?;
+
+    releases.retain(|release_info| {
+        since.map_or(true, |since| release_info.date_created >= *since)
+            && until.map_or(true, |until| release_info.date_created <= *until)
+    });
+
+    match sort {
+        Some("date") => releases.sort_by_key(|release_info| Reverse(release_info.date_created)),
+        Some("version") => releases.sort_by(|a, b| compare_versions(&b.version, &a.version)),
+        _ => {}
+    }
+
+    if let Some(&max_rows) = matches.get_one::<usize>("max_rows") {
+        releases.truncate(max_rows);
+    }
+
+    if matches.get_flag("json") {
+        print_json(&releases)?;
+        return Ok(());
+    }
 
+    print_releases(matches, releases)
+}
+
+fn print_releases(matches: &ArgMatches, releases: Vec<ReleaseInfo>) -> Result<()> {
     if matches.get_flag("raw") {
         let versions = releases
             .iter()
@@ -60,8 +165,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                     .unwrap_or("\n"),
             );
 
-        println!("{versions}");
-        return Ok(());
+        return print_value(versions);
     }
 
     let mut table = Table::new();
@@ -1,13 +1,15 @@
-use anyhow::Result;
-use chrono::Utc;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use log::warn;
 
-use crate::api::{Api, NewRelease};
+use crate::api::{Api, NewRelease, UpdatedRelease};
+use crate::commands::releases::finalize;
 use crate::config::Config;
-use crate::utils::args::ArgExt;
+use crate::utils::args::{get_timestamp, ArgExt};
 
 pub fn make_command(command: Command) -> Command {
-    command
+    let command = command
         .about("Create a new release.")
         .allow_hyphen_values(true)
         .version_arg(false)
@@ -23,23 +25,44 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Immediately finalize the release. (sets it to released)"),
         )
+        .arg(
+            Arg::new("date_created")
+                .long("date-created")
+                .value_parser(get_timestamp)
+                .value_name("TIMESTAMP")
+                .help(
+                    "Set the creation date of the release.  Use this to backfill \
+                    releases imported from other systems.",
+                ),
+        )
         // Legacy flag that has no effect, left hidden for backward compatibility
-        .arg(Arg::new("ref").long("ref").hide(true))
+        .arg(Arg::new("ref").long("ref").hide(true));
+    finalize::verify_args(command).mut_arg("verify_before_finalize", |arg| arg.requires("finalize"))
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let config = Config::current();
     let api = Api::current();
+    let authenticated_api = api.authenticated()?;
+    let org = config.get_org(matches)?;
     let version = matches.get_one::<String>("version").unwrap();
+    let projects = config.get_projects(matches)?;
+    // A release that needs to be verified can only be finalized once it exists.
+    let verify = matches.get_flag("verify_before_finalize");
+    let date_created = matches.get_one::<DateTime<Utc>>("date_created").copied();
+    if date_created.is_some_and(|date| date > Utc::now()) {
+        warn!("The creation date of the release is in the future.");
+    }
 
-    api.authenticated()?.new_release(
-        &config.get_org(matches)?,
+    authenticated_api.new_release(
+        &org,
         &NewRelease {
             version: version.to_owned(),
-            projects: config.get_projects(matches)?,
+            projects: projects.clone(),
             url: matches.get_one::<String>("url").cloned(),
-            date_started: Some(Utc::now()),
-            date_released: if matches.get_flag("finalize") {
+            date_created,
+            date_started: Some(date_created.unwrap_or_else(Utc::now)),
+            date_released: if matches.get_flag("finalize") && !verify {
                 Some(Utc::now())
             } else {
                 None
@@ -47,6 +70,20 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         },
     )?;
 
+    if verify {
+        finalize::verify_release(matches, &authenticated_api, &org, version)
+            .with_context(|| format!("Created release {version}, but did not finalize it"))?;
+        authenticated_api.update_release(
+            &org,
+            version,
+            &UpdatedRelease {
+                projects: Some(projects),
+                date_released: Some(Utc::now()),
+                ..Default::default()
+            },
+        )?;
+    }
+
     println!("Created release {version}");
     Ok(())
 }
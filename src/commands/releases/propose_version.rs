@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::{ArgMatches, Command};
 
+use crate::utils::formatting::print_value;
 use crate::utils::releases::detect_release_name;
 
 pub fn make_command(command: Command) -> Command {
@@ -8,6 +9,5 @@ pub fn make_command(command: Command) -> Command {
 }
 
 pub fn execute(_matches: &ArgMatches) -> Result<()> {
-    println!("{}", detect_release_name()?);
-    Ok(())
+    print_value(detect_release_name()?)
 }
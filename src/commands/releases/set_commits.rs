@@ -1,12 +1,16 @@
 use anyhow::{bail, Result};
-use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
 use lazy_static::lazy_static;
+use log::{info, warn};
 use regex::Regex;
+use serde::Serialize;
 
-use crate::api::{Api, NewRelease, NoneReleaseInfo, OptionalReleaseInfo, UpdatedRelease};
+use crate::api::{
+    Api, GitCommit, NewRelease, NoneReleaseInfo, OptionalReleaseInfo, Ref, UpdatedRelease,
+};
 use crate::config::Config;
 use crate::utils::args::ArgExt;
-use crate::utils::formatting::Table;
+use crate::utils::formatting::{print_json, Table};
 use crate::utils::vcs::{
     find_heads, generate_patch_set, get_commits_from_git, get_repo_from_remote, CommitSpec,
 };
@@ -69,6 +73,196 @@ pub fn make_command(command: Command) -> Command {
             .long("ignore-empty")
             .action(ArgAction::SetTrue)
             .hide(true))
+        .arg(Arg::new("log")
+            .long("log")
+            .action(ArgAction::SetTrue)
+            .help("Print the commits that will be associated with the release before \
+                    setting them.  For repositories with an integration, the revisions \
+                    are printed, since Sentry discovers the commits between them."))
+        .arg(Arg::new("dry_run")
+            .long("dry-run")
+            .action(ArgAction::SetTrue)
+            .help("Print the commits like --log, but do not create the release or set \
+                    its commits."))
+        .group(ArgGroup::new("print_commits")
+            .args(["log", "dry_run"])
+            .multiple(true))
+        .arg(Arg::new("json")
+            .long("json")
+            .action(ArgAction::SetTrue)
+            .requires("print_commits")
+            .help("Print the commits of --log or --dry-run as JSON.  Requires one of \
+                    these options."))
+        .arg(Arg::new("fail_if_no_commits")
+            .long("fail-if-no-commits")
+            .action(ArgAction::SetTrue)
+            .help("Fail if no commits are found in the local git tree, instead of \
+                    leaving the release alone.  This catches CI setups that clone \
+                    too little history."))
+        .arg(Arg::new("on_missing_git")
+            .long("on-missing-git")
+            .value_name("ACTION")
+            .value_parser(["error", "warn", "skip"])
+            .default_value("error")
+            .help("What to do if commits are discovered from the local git tree, but the \
+                    command does not run in a git repository.  `warn` and `skip` create \
+                    the release without commits, with or without a warning.  This is \
+                    meant for environments that only contain build artifacts."))
+}
+
+/// Checks whether the command runs in a git repository.  Returns `true` if
+/// it does not and `--on-missing-git` allows to create the release without
+/// commits.
+fn proceed_without_git(matches: &ArgMatches, version: &str) -> Result<bool> {
+    let err = match git2::Repository::open_from_env() {
+        Ok(_) => return Ok(false),
+        Err(err) if err.code() == git2::ErrorCode::NotFound => err,
+        // Other errors are reported when the repository is used.
+        Err(_) => return Ok(false),
+    };
+    match matches
+        .get_one::<String>("on_missing_git")
+        .map(String::as_str)
+    {
+        Some("warn") => {
+            warn!(
+                "Not setting commits for release {version}, no git repository was found: {}",
+                err.message()
+            );
+            Ok(true)
+        }
+        Some("skip") => {
+            info!(
+                "Not setting commits for release {version}, no git repository was found \
+                 (--on-missing-git=skip)"
+            );
+            Ok(true)
+        }
+        _ => {
+            // The error is reported when commits are discovered.
+            info!("No git repository was found, failing (--on-missing-git=error)");
+            Ok(false)
+        }
+    }
+}
+
+/// A commit printed by `--log`.
+#[derive(Serialize)]
+struct LoggedCommit<'a> {
+    id: &'a str,
+    author_name: Option<&'a str>,
+    author_email: Option<&'a str>,
+    summary: &'a str,
+}
+
+/// A revision range printed by `--log`.
+#[derive(Serialize)]
+struct LoggedRef<'a> {
+    repository: &'a str,
+    commit: &'a str,
+    previous_commit: Option<&'a str>,
+}
+
+fn log_refs(heads: &[Ref], json: bool) -> Result<()> {
+    if json {
+        let refs: Vec<_> = heads
+            .iter()
+            .map(|head| LoggedRef {
+                repository: &head.repo,
+                commit: &head.rev,
+                previous_commit: head.prev_rev.as_deref(),
+            })
+            .collect();
+        return print_json(&refs);
+    }
+
+    if heads.is_empty() {
+        println!("Clearing commits for release.");
+        return Ok(());
+    }
+    let mut table = Table::new();
+    table.title_row().add("Repository").add("Revision");
+    for commit in heads {
+        let row = table.add_row();
+        row.add(&commit.repo);
+        if let Some(ref prev_rev) = commit.prev_rev {
+            row.add(format!(
+                "{} -> {}",
+                strip_sha(prev_rev),
+                strip_sha(&commit.rev)
+            ));
+        } else {
+            row.add(strip_sha(&commit.rev));
+        }
+    }
+    table.print();
+    Ok(())
+}
+
+fn log_commits(commits: &[GitCommit], json: bool) -> Result<()> {
+    let commits: Vec<_> = commits
+        .iter()
+        .map(|commit| LoggedCommit {
+            id: &commit.id,
+            author_name: commit.author_name.as_deref(),
+            author_email: commit.author_email.as_deref(),
+            summary: commit
+                .message
+                .as_deref()
+                .and_then(|message| message.lines().next())
+                .unwrap_or_default(),
+        })
+        .collect();
+    if json {
+        return print_json(&commits);
+    }
+
+    let mut table = Table::new();
+    table.title_row().add("Commit").add("Author").add("Message");
+    for commit in &commits {
+        table
+            .add_row()
+            .add(strip_sha(commit.id))
+            .add(commit.author_name.unwrap_or("-"))
+            .add(commit.summary);
+    }
+    table.print();
+    Ok(())
+}
+
+/// Explains why no commits were found in the local git tree.
+fn describe_missing_commits(
+    repo: &git2::Repository,
+    prev_commit_id: &str,
+    default_count: usize,
+) -> String {
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string());
+    let mut reasons = vec![];
+    if prev_commit_id.is_empty() {
+        reasons.push("no previous release with commits was found".to_owned());
+        if default_count == 0 {
+            reasons.push("--initial-depth is 0".to_owned());
+        }
+    } else if head.as_deref() == Some(prev_commit_id) {
+        reasons.push(format!(
+            "the previous release is already at HEAD ({})",
+            strip_sha(prev_commit_id)
+        ));
+    }
+    if head.is_none() {
+        reasons.push("the repository has no HEAD commit".to_owned());
+    }
+    if repo.is_shallow() {
+        reasons.push("the repository is a shallow clone".to_owned());
+    }
+    if reasons.is_empty() {
+        reasons.push("the commit range is empty".to_owned());
+    }
+    reasons.join(", ")
 }
 
 fn strip_sha(sha: &str) -> &str {
@@ -87,10 +281,19 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let authenticated_api = api.authenticated()?;
     let version = matches.get_one::<String>("version").unwrap();
     let org = config.get_org(matches)?;
+    let dry_run = matches.get_flag("dry_run");
+    let log = matches.get_flag("log") || dry_run;
+    let json = matches.get_flag("json");
     let repos = authenticated_api.list_organization_repos(&org)?;
     let mut commit_specs = vec![];
 
-    let heads = if repos.is_empty() {
+    // Without a repository integration, or without explicit commits, commits
+    // are discovered from the local git tree.
+    let needs_local_git =
+        repos.is_empty() || (!matches.get_flag("clear") && !matches.contains_id("commits"));
+    let skip_commits = needs_local_git && proceed_without_git(matches, version)?;
+
+    let heads = if repos.is_empty() || skip_commits {
         None
     } else if matches.get_flag("auto") {
         let commits = find_heads(None, &repos, Some(config.get_cached_vcs_remote()))?;
@@ -130,37 +333,27 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     };
 
     // make sure the release exists if projects are given
-    if let Ok(projects) = config.get_projects(matches) {
-        authenticated_api.new_release(
-            &org,
-            &NewRelease {
-                version: version.into(),
-                projects,
-                ..Default::default()
-            },
-        )?;
+    if !dry_run {
+        if let Ok(projects) = config.get_projects(matches) {
+            authenticated_api.new_release(
+                &org,
+                &NewRelease {
+                    version: version.into(),
+                    projects,
+                    ..Default::default()
+                },
+            )?;
+        }
+    }
+
+    if skip_commits {
+        return Ok(());
     }
 
     if let Some(heads) = heads {
-        if heads.is_empty() {
-            println!("Clearing commits for release.");
-        } else {
-            let mut table = Table::new();
-            table.title_row().add("Repository").add("Revision");
-            for commit in &heads {
-                let row = table.add_row();
-                row.add(&commit.repo);
-                if let Some(ref prev_rev) = commit.prev_rev {
-                    row.add(format!(
-                        "{} -> {}",
-                        strip_sha(prev_rev),
-                        strip_sha(&commit.rev)
-                    ));
-                } else {
-                    row.add(strip_sha(&commit.rev));
-                }
-            }
-            table.print();
+        log_refs(&heads, log && json)?;
+        if dry_run {
+            return Ok(());
         }
         authenticated_api.set_release_refs(&org, version, heads)?;
     } else {
@@ -173,7 +366,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             println!("Could not determine any commits to be associated with a repo-based integration. Proceeding to find commits from local git tree.");
         }
         // Get the commit of the most recent release.
-        let prev_commit = match authenticated_api
+        let prev_commit_id = match authenticated_api
             .get_previous_release_with_commits(&org, version)?
         {
             OptionalReleaseInfo::Some(prev) => prev.last_commit.map(|c| c.id).unwrap_or_default(),
@@ -190,16 +383,29 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         // Fetch all the commits upto the `prev_commit` or return the default (20).
         // Will return a tuple of Vec<GitCommits> and the `prev_commit` if it exists in the git tree.
         let (commit_log, prev_commit) =
-            get_commits_from_git(&repo, &prev_commit, default_count, ignore_missing)?;
+            get_commits_from_git(&repo, &prev_commit_id, default_count, ignore_missing)?;
 
         // Calculate the diff for each commit in the Vec<GitCommit>.
         let commits = generate_patch_set(&repo, commit_log, prev_commit, &parsed)?;
 
         if commits.is_empty() {
+            if matches.get_flag("fail_if_no_commits") {
+                bail!(
+                    "No commits found for release {version}: {}.",
+                    describe_missing_commits(&repo, &prev_commit_id, default_count)
+                );
+            }
             println!("No commits found. Leaving release alone. If you believe there should be some, change commits range or initial depth and try again.");
             return Ok(());
         }
 
+        if log {
+            log_commits(&commits, json)?;
+        }
+        if dry_run {
+            return Ok(());
+        }
+
         authenticated_api.update_release(
             &config.get_org(matches)?,
             version,
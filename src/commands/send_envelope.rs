@@ -1,12 +1,13 @@
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use glob::{glob_with, MatchOptions};
 use log::warn;
 use sentry::Envelope;
 
 use crate::api::envelopes_api::EnvelopesApi;
+use crate::utils::envelopes::StoredEnvelope;
 
 pub fn make_command(command: Command) -> Command {
     command
@@ -30,10 +31,30 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Send envelopes without attempting to parse their contents."),
         )
+        .arg(
+            Arg::new("validate")
+                .long("validate")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Check that the declared length of every envelope item matches its \
+                     payload, and do not send envelopes that fail the check.",
+                ),
+        )
+        .arg(
+            Arg::new("decode_base64_items")
+                .long("decode-base64-items")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Decode the payloads of items whose header declares \
+                     `\"encoding\": \"base64\"` before sending.",
+                ),
+        )
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
     let raw = matches.get_flag("raw");
+    let validate = matches.get_flag("validate");
+    let decode_base64 = matches.get_flag("decode_base64_items");
 
     let path = matches.get_one::<String>("path").unwrap();
 
@@ -49,7 +70,25 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
 
     for path in collected_paths {
         let p = path.as_path();
-        let envelope: Envelope = if raw {
+        let envelope: Envelope = if validate || decode_base64 {
+            let mut stored = StoredEnvelope::parse(&std::fs::read(p)?)?;
+            let problems = stored.validate(decode_base64);
+            for problem in &problems {
+                warn!("{}: {}", p.display(), problem);
+            }
+            if validate && !problems.is_empty() {
+                bail!("Envelope from file {} failed validation", p.display());
+            }
+            if decode_base64 {
+                stored.decode_base64_items()?;
+            }
+            let bytes = stored.to_bytes();
+            if raw {
+                Envelope::from_bytes_raw(bytes)
+            } else {
+                Envelope::from_slice(&bytes)
+            }
+        } else if raw {
             Envelope::from_path_raw(p)
         } else {
             Envelope::from_path(p)
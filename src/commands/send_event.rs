@@ -1,24 +1,32 @@
 use std::borrow::Cow;
+use std::collections::btree_map::Entry;
 use std::env;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
-use anyhow::{anyhow, format_err, Result};
+use anyhow::{anyhow, bail, format_err, Result};
 use chrono::{DateTime, Utc};
-use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
 use glob::{glob_with, MatchOptions};
 use itertools::Itertools;
-use log::warn;
-use sentry::protocol::{Event, Level, LogEntry, User};
+use log::{debug, warn};
+use rand::Rng;
+use sentry::integrations::contexts::utils::{device_context, os_context, server_name};
+use sentry::protocol::{
+    AppContext, Context, Event, Exception, Frame, Level, LogEntry, Stacktrace, User,
+};
 use sentry::types::Uuid;
 use sentry::{apply_defaults, Client, ClientOptions, Envelope};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use username::get_user_name;
 
 use crate::api::envelopes_api::EnvelopesApi;
-use crate::constants::USER_AGENT;
+use crate::constants::{USER_AGENT, VERSION};
 use crate::utils::args::{get_timestamp, validate_distribution};
+use crate::utils::auth_token::redact_token_from_string;
 use crate::utils::event::{attach_logfile, get_sdk_info};
+use crate::utils::formatting::print_json;
 use crate::utils::releases::detect_release_name;
 
 pub fn make_command(command: Command) -> Command {
@@ -82,6 +90,20 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Do not send environment variables along"),
         )
+        .arg(
+            Arg::new("with_locals")
+                .long("with-locals")
+                .action(ArgAction::SetTrue)
+                .help("Attach the hostname, OS, architecture and working directory.")
+                .long_help(
+                    "Attach information about the machine sending the event.{n}{n}\
+                     Adds the `os` and `device` contexts with the OS and architecture, \
+                     an `app` context with the sentry-cli version and the current working \
+                     directory, and the hostname as server name.  Contexts given with \
+                     --context take precedence.  With --no-environ, the hostname and working \
+                     directory are left out.",
+                ),
+        )
         .arg(
             Arg::new("message")
                 .value_name("MESSAGE")
@@ -113,6 +135,24 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::Append)
                 .help("Add a tag (key:value) to the event."),
         )
+        .arg(
+            Arg::new("tags_from_env")
+                .value_name("TAG=ENV_VAR")
+                .long("tag-from-env")
+                .action(ArgAction::Append)
+                .value_parser(parse_tag_from_env)
+                .help(
+                    "Add a tag to the event with the value of the given environment variable.  \
+                     Tags whose variable is not set are skipped.",
+                ),
+        )
+        .arg(
+            Arg::new("strict_env_tags")
+                .long("strict-env-tags")
+                .action(ArgAction::SetTrue)
+                .requires("tags_from_env")
+                .help("Fail if the variable of a --tag-from-env tag is not set."),
+        )
         .arg(
             Arg::new("extra")
                 .value_name("KEY:VALUE")
@@ -121,6 +161,16 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::Append)
                 .help("Add extra information (key:value) to the event."),
         )
+        .arg(
+            Arg::new("contexts")
+                .value_name("NAME:JSON")
+                .long("context")
+                .action(ArgAction::Append)
+                .help(
+                    "Add a context to the event.  The value must be a JSON object, \
+                     for example 'app:{\"app_version\": \"1.0\"}'.",
+                ),
+        )
         .arg(
             Arg::new("user_data")
                 .value_name("KEY:VALUE")
@@ -140,6 +190,56 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::Append)
                 .help("Change the fingerprint of the event."),
         )
+        .arg(
+            Arg::new("exception_type")
+                .value_name("TYPE")
+                .long("exception-type")
+                .help("Add an exception of this type to the event. [defaults to 'Error' if a stack frame is given]"),
+        )
+        .arg(
+            Arg::new("exception_value")
+                .value_name("VALUE")
+                .long("exception-value")
+                .help("Set the value (the message) of the exception."),
+        )
+        .arg(
+            Arg::new("stack_frames")
+                .value_name("FUNCTION:FILE:LINE")
+                .long("stack-frame")
+                .action(ArgAction::Append)
+                .value_parser(parse_stack_frame)
+                .help(
+                    "Add a frame to the stacktrace of the exception.  This argument can be \
+                     specified multiple times, see --frame-order for the order of the frames.",
+                ),
+        )
+        .arg(
+            Arg::new("frame_order")
+                .value_name("ORDER")
+                .long("frame-order")
+                .value_parser(PossibleValuesParser::new(["outermost-first", "innermost-first"]))
+                .default_value("outermost-first")
+                .requires("stack_frames")
+                .help("The order in which the stack frames are given."),
+        )
+        .arg(
+            Arg::new("field_size_limits")
+                .value_name("FIELD=BYTES")
+                .long("field-size-limit")
+                .action(ArgAction::Append)
+                .value_parser(parse_field_size_limit)
+                .help(
+                    "Override the size limit of an event field.  The serialized sizes of \
+                     `message` (8KiB), `extra` (256KiB), `contexts` (256KiB) and `breadcrumbs` \
+                     (256KiB, e.g. from --logfile) are checked before sending.",
+                ),
+        )
+        .arg(
+            Arg::new("strict_field_limits")
+                .long("strict-field-limits")
+                .action(ArgAction::SetTrue)
+                .help("Fail instead of warning if an event field exceeds its size limit."),
+        )
         .arg(
             Arg::new("logfile")
                 .value_name("PATH")
@@ -157,6 +257,141 @@ pub fn make_command(command: Command) -> Command {
                     eg. \"INFO: Something broke\" will be parsed as a breadcrumb \
                     \"{\"level\": \"info\", \"message\": \"Something broke\"}\"")
         )
+        .arg(
+            Arg::new("sample_rate")
+                .value_name("RATE")
+                .long("sample-rate")
+                .value_parser(parse_sample_rate)
+                .help(
+                    "Send the event only with the given probability between 0.0 and 1.0, \
+                     to reduce the volume of scripts that report often.  Each invocation \
+                     is sampled independently.  Dropped events exit successfully.",
+                ),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Print the event id as JSON.")
+                .long_help(
+                    "Print the event id as JSON.{n}{n}\
+                     Only the id of the dispatched event is printed to stdout, so it can be \
+                     captured by scripts.  With this flag, it is printed as \
+                     `{\"event_id\": ...}`, or as a list that also contains the paths when \
+                     sending events from files.",
+                ),
+        )
+}
+
+fn parse_sample_rate(value: &str) -> Result<f64> {
+    match value.parse() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        _ => bail!("expected a number between 0.0 and 1.0"),
+    }
+}
+
+/// Parses a tag given as `tag=ENV_VAR`.
+fn parse_tag_from_env(value: &str) -> Result<(String, String)> {
+    match value.split_once('=') {
+        Some((tag, var)) if !tag.is_empty() && !var.is_empty() => {
+            Ok((tag.to_owned(), var.to_owned()))
+        }
+        _ => bail!("expected TAG=ENV_VAR"),
+    }
+}
+
+/// Parses a stack frame given as `function:file:line`.  The function name
+/// must not contain a colon, but the file name may.
+fn parse_stack_frame(value: &str) -> Result<Frame> {
+    let (function, location) = value
+        .split_once(':')
+        .ok_or_else(|| format_err!("expected FUNCTION:FILE:LINE"))?;
+    let (file, line) = location
+        .rsplit_once(':')
+        .ok_or_else(|| format_err!("expected FUNCTION:FILE:LINE"))?;
+    let line = line
+        .parse()
+        .map_err(|_| format_err!("line number `{line}` is not an integer"))?;
+    Ok(Frame {
+        function: Some(function.to_owned()).filter(|f| !f.is_empty()),
+        filename: Some(file.to_owned()).filter(|f| !f.is_empty()),
+        lineno: Some(line),
+        ..Default::default()
+    })
+}
+
+/// Event fields whose serialized size is checked before sending, together
+/// with their default limits in bytes.
+const FIELD_SIZE_LIMITS: &[(&str, usize)] = &[
+    ("message", 8 * 1024),
+    ("extra", 256 * 1024),
+    ("contexts", 256 * 1024),
+    ("breadcrumbs", 256 * 1024),
+];
+
+fn parse_field_size_limit(value: &str) -> Result<(String, usize)> {
+    let (field, limit) = value
+        .split_once('=')
+        .ok_or_else(|| format_err!("expected FIELD=BYTES"))?;
+    if !FIELD_SIZE_LIMITS.iter().any(|(name, _)| *name == field) {
+        bail!(
+            "unknown field `{field}`, expected one of {}",
+            FIELD_SIZE_LIMITS.iter().map(|(name, _)| name).join(", ")
+        );
+    }
+    let limit = limit
+        .parse()
+        .map_err(|_| format_err!("limit `{limit}` is not a number of bytes"))?;
+    Ok((field.to_owned(), limit))
+}
+
+/// Returns the serialized size of an event field, or zero if it is empty.
+fn field_size(event: &Event, field: &str) -> Result<usize> {
+    let size = match field {
+        "message" => {
+            event.message.as_ref().map_or(0, String::len)
+                + match &event.logentry {
+                    Some(logentry) => serde_json::to_vec(logentry)?.len(),
+                    None => 0,
+                }
+        }
+        "extra" if !event.extra.is_empty() => serde_json::to_vec(&event.extra)?.len(),
+        "contexts" if !event.contexts.is_empty() => serde_json::to_vec(&event.contexts)?.len(),
+        "breadcrumbs" if !event.breadcrumbs.is_empty() => {
+            serde_json::to_vec(&event.breadcrumbs)?.len()
+        }
+        _ => 0,
+    };
+    Ok(size)
+}
+
+/// Checks the event fields against their size limits.  Sentry truncates
+/// oversized fields, so by default this only warns.
+fn check_field_sizes(event: &Event, matches: &ArgMatches) -> Result<()> {
+    let overrides: Vec<&(String, usize)> = matches
+        .get_many("field_size_limits")
+        .unwrap_or_default()
+        .collect();
+    let strict = matches.get_flag("strict_field_limits");
+
+    for &(field, default_limit) in FIELD_SIZE_LIMITS {
+        let limit = overrides
+            .iter()
+            .rev()
+            .find(|(name, _)| name == field)
+            .map_or(default_limit, |(_, limit)| *limit);
+        let size = field_size(event, field)?;
+        if size > limit {
+            let message = format!(
+                "Event field `{field}` is {size} bytes, exceeding its limit of {limit} bytes"
+            );
+            if strict {
+                bail!(message);
+            }
+            warn!("{message}. Sentry may truncate it.");
+        }
+    }
+    Ok(())
 }
 
 pub(super) fn send_raw_event(event: Event<'static>) -> Result<Uuid> {
@@ -168,12 +403,77 @@ pub(super) fn send_raw_event(event: Event<'static>) -> Result<Uuid> {
         .prepare_event(event, None)
         .ok_or(anyhow!("Event dropped during preparation"))?;
     let event_id = event.event_id;
-    EnvelopesApi::try_new()?.send_envelope(event)?;
-    Ok(event_id)
+    send_envelope(event, event_id)
+}
+
+/// Adds the contexts of `--with-locals` that are not set yet.  The hostname
+/// and working directory identify the machine, so they are only added if
+/// the environment may be sent.
+fn attach_locals(event: &mut Event, no_environ: bool) {
+    let contexts = &mut event.contexts;
+    if let Entry::Vacant(entry) = contexts.entry("os".into()) {
+        if let Some(os) = os_context() {
+            entry.insert(os);
+        }
+    }
+    contexts
+        .entry("device".into())
+        .or_insert_with(device_context);
+    contexts.entry("app".into()).or_insert_with(|| {
+        let mut app = AppContext {
+            app_name: Some("sentry-cli".into()),
+            app_version: Some(VERSION.into()),
+            ..Default::default()
+        };
+        if !no_environ {
+            if let Ok(cwd) = env::current_dir() {
+                let cwd = cwd.to_string_lossy();
+                let cwd = redact_token_from_string(&cwd, "[REDACTED]");
+                app.other
+                    .insert("cwd".into(), Value::String(cwd.into_owned()));
+            }
+        }
+        app.into()
+    });
+
+    if !no_environ && event.server_name.is_none() {
+        event.server_name = server_name().map(Cow::Owned);
+    }
+}
+
+/// Sends an envelope and returns the id of its event.  Prefers the id that
+/// the server reports as accepted over the one that was sent.
+fn send_envelope(envelope: impl Into<Envelope>, event_id: Uuid) -> Result<Uuid> {
+    #[derive(Deserialize)]
+    struct Accepted {
+        id: Option<Uuid>,
+    }
+
+    let response = EnvelopesApi::try_new()?.send_envelope(envelope)?;
+    Ok(response
+        .deserialize::<Accepted>()
+        .ok()
+        .and_then(|accepted| accepted.id)
+        .unwrap_or(event_id))
+}
+
+#[derive(Serialize)]
+struct DispatchedEvent {
+    event_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
+    if let Some(&sample_rate) = matches.get_one::<f64>("sample_rate") {
+        if !rand::thread_rng().gen_bool(sample_rate) {
+            debug!("Dropped the event due to --sample-rate {sample_rate}");
+            return Ok(());
+        }
+    }
+
     let raw = matches.get_flag("raw");
+    let json = matches.get_flag("json");
 
     if let Some(path) = matches.get_one::<String>("path") {
         let collected_paths: Vec<PathBuf> = glob_with(path, MatchOptions::new())
@@ -186,6 +486,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             return Ok(());
         }
 
+        let mut dispatched = vec![];
         for path in collected_paths {
             let raw_event = std::fs::read(&path)?;
 
@@ -206,16 +507,26 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
                 writeln!(buf, r#"{{"type":"event","length":{}}}"#, raw_event.len())?;
                 buf.extend(raw_event);
                 let envelope = Envelope::from_bytes_raw(buf)?;
-                EnvelopesApi::try_new()?.send_envelope(envelope)?;
-                id
+                send_envelope(envelope, id)?
             } else {
                 let event: Event = serde_json::from_slice(&raw_event)?;
+                check_field_sizes(&event, matches)?;
                 send_raw_event(event)?
             };
 
-            println!("Event from file {} dispatched: {}", path.display(), id);
+            eprintln!("Event from file {} dispatched.", path.display());
+            if !json {
+                println!("{id}");
+            }
+            dispatched.push(DispatchedEvent {
+                event_id: id,
+                path: Some(path),
+            });
         }
 
+        if json {
+            print_json(&dispatched)?;
+        }
         return Ok(());
     }
 
@@ -264,6 +575,22 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         event.tags.insert(key.into(), value.into());
     }
 
+    let strict_env_tags = matches.get_flag("strict_env_tags");
+    for (tag, var) in matches
+        .get_many::<(String, String)>("tags_from_env")
+        .unwrap_or_default()
+    {
+        match env::var(var) {
+            Ok(value) => {
+                event.tags.insert(tag.clone(), value);
+            }
+            Err(_) if strict_env_tags => {
+                bail!("environment variable `{var}` for tag `{tag}` is not set")
+            }
+            Err(_) => debug!("Skipping tag `{tag}`, environment variable `{var}` is not set"),
+        }
+    }
+
     if !matches.get_flag("no_environ") {
         event.extra.insert(
             "environ".into(),
@@ -282,6 +609,29 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         event.extra.insert(key.into(), Value::String(value.into()));
     }
 
+    for context in matches.get_many::<String>("contexts").unwrap_or_default() {
+        let (name, value) = context
+            .split_once([':', '='])
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| format_err!("missing context name in `{context}`"))?;
+        let mut value = match serde_json::from_str(value) {
+            Ok(Value::Object(map)) => map,
+            Ok(_) => bail!("context `{name}` must be a JSON object"),
+            Err(err) => bail!("context `{name}` is not valid JSON: {err}"),
+        };
+        // Well-known contexts such as `app` or `device` are recognized by their name.
+        value
+            .entry("type")
+            .or_insert_with(|| Value::String(name.into()));
+        let context: Context = serde_json::from_value(Value::Object(value))
+            .map_err(|err| format_err!("context `{name}` is invalid: {err}"))?;
+        event.contexts.insert(name.into(), context);
+    }
+
+    if matches.get_flag("with_locals") {
+        attach_locals(&mut event, matches.get_flag("no_environ"));
+    }
+
     if let Some(user_data) = matches.get_many::<String>("user_data") {
         let mut user = User::default();
         for pair in user_data {
@@ -321,12 +671,47 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             .into();
     }
 
+    let mut frames: Vec<Frame> = matches
+        .get_many::<Frame>("stack_frames")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let exception_type = matches.get_one::<String>("exception_type");
+    let exception_value = matches.get_one::<String>("exception_value");
+    if exception_type.is_some() || exception_value.is_some() || !frames.is_empty() {
+        // Sentry expects the outermost frame first.
+        if matches.get_one::<String>("frame_order").map(String::as_str) == Some("innermost-first") {
+            frames.reverse();
+        }
+        let exception = Exception {
+            ty: exception_type
+                .cloned()
+                .unwrap_or_else(|| "Error".to_owned()),
+            value: exception_value.cloned(),
+            stacktrace: (!frames.is_empty()).then(|| Stacktrace {
+                frames,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        event.exception = vec![exception].into();
+    }
+
     if let Some(logfile) = matches.get_one::<String>("logfile") {
         attach_logfile(&mut event, logfile, matches.get_flag("with_categories"))?;
     }
 
+    check_field_sizes(&event, matches)?;
     let id = send_raw_event(event)?;
-    println!("Event dispatched.\nEvent id: {id}");
+    eprintln!("Event dispatched.");
+    if json {
+        print_json(&DispatchedEvent {
+            event_id: id,
+            path: None,
+        })?;
+    } else {
+        println!("{id}");
+    }
 
     Ok(())
 }
@@ -283,7 +283,7 @@ fn print_sourcemap(file: &TempFile, line: u32, column: u32) -> Result<()> {
     if let Some(token) = sm.lookup_token(line, column) {
         if let Some(view) = token.get_source_view() {
             success("Sourcemap position resolves to:");
-            print_source(&token, view);
+            print_source(&token, view, 3);
         } else if token.get_source_view().is_none() {
             bail!("cannot find source");
         } else {
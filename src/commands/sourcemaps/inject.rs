@@ -1,10 +1,12 @@
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 
 use crate::utils::file_search::ReleaseFileSearch;
+use crate::utils::formatting::print_json;
 use crate::utils::fs::path_as_url;
+use crate::utils::logging::{quiet_println, set_quiet_mode};
 use crate::utils::sourcemaps::SourceMapProcessor;
 
 pub fn make_command(command: Command) -> Command {
@@ -64,7 +66,38 @@ pub fn make_command(command: Command) -> Command {
             Arg::new("dry_run")
                 .long("dry-run")
                 .action(ArgAction::SetTrue)
-                .help("Don't modify files on disk."),
+                .help(
+                    "Don't modify files on disk.  Instead, report which files would \
+                    gain a debug id, which already have one, and the debug ids.",
+                ),
+        )
+        .arg(
+            Arg::new("ignore_errors")
+                .long("ignore-errors")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Skip files that cannot be parsed or injected instead of aborting.  \
+                    Skipped files are listed in the report.",
+                ),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .action(ArgAction::SetTrue)
+                .requires("ignore_errors")
+                .help(
+                    "Exit with an error if any file was skipped.  \
+                    Requires --ignore-errors.",
+                ),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Format the report as JSON, with the path, action (inject or skip) \
+                    and debug id of every file.",
+                ),
         )
 }
 
@@ -76,6 +109,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .unwrap()
         .map(PathBuf::from);
     let dry_run = matches.get_flag("dry_run");
+    let json = matches.get_flag("json");
+
+    // Keep stdout free of anything but the JSON report.
+    if json {
+        set_quiet_mode(true);
+    }
 
     let ignore_file = matches
         .get_one::<String>("ignore_file")
@@ -95,7 +134,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     extensions.push("map");
 
     for path in paths {
-        println!("> Searching {}", path.display());
+        quiet_println!("> Searching {}", path.display());
         let sources = ReleaseFileSearch::new(path)
             .ignore_file(ignore_file)
             .ignores(&ignores)
@@ -107,6 +146,26 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
-    processor.inject_debug_ids(dry_run, &extensions)?;
+    let report =
+        processor.inject_debug_ids(dry_run, matches.get_flag("ignore_errors"), &extensions)?;
+    if json {
+        print_json(&report.entries())?;
+    } else if !report.is_empty() {
+        println!("{report}");
+    } else {
+        println!("> Nothing to inject")
+    }
+
+    if matches.get_flag("strict") && !report.failed.is_empty() {
+        bail!(
+            "{} {} could not be processed.",
+            report.failed.len(),
+            match report.failed.len() {
+                1 => "file",
+                _ => "files",
+            }
+        );
+    }
+
     Ok(())
 }
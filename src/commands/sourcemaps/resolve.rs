@@ -3,9 +3,12 @@ use std::fs;
 use std::path::PathBuf;
 
 use anyhow::{format_err, Result};
-use clap::{Arg, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use serde::Serialize;
 use sourcemap::{DecodedMap, SourceView, Token};
 
+use crate::utils::formatting::print_json;
+
 pub fn make_command(command: Command) -> Command {
     command
         .about("Resolve sourcemap for a given line/column position.")
@@ -30,6 +33,60 @@ pub fn make_command(command: Command) -> Command {
                 .value_parser(clap::value_parser!(u32))
                 .help("Column number for minified source."),
         )
+        .arg(
+            Arg::new("context_lines")
+                .long("context-lines")
+                .value_name("N")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("3")
+                .help(
+                    "Number of source lines to show before and after the resolved \
+                     line.  Requires the sourcemap to embed the sources.",
+                ),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help("Format outputs as JSON."),
+        )
+}
+
+/// The resolved token as emitted by `--json`.
+#[derive(Serialize)]
+struct ResolvedToken<'a> {
+    source: Option<&'a str>,
+    line: u32,
+    column: u32,
+    name: Option<&'a str>,
+    minified_line: u32,
+    minified_column: u32,
+    /// Whether the sourcemap embeds the source, which is required for the context.
+    sources_content: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<SourceContext<'a>>,
+}
+
+#[derive(Serialize)]
+struct SourceContext<'a> {
+    pre_lines: Vec<&'a str>,
+    context_line: Option<&'a str>,
+    post_lines: Vec<&'a str>,
+}
+
+impl<'a> SourceContext<'a> {
+    fn new(token: &Token<'_>, view: &'a SourceView, context_lines: u32) -> Self {
+        let line = token.get_src_line();
+        SourceContext {
+            pre_lines: (line.saturating_sub(context_lines)..line)
+                .filter_map(|l| view.get_line(l))
+                .collect(),
+            context_line: view.get_line(line),
+            post_lines: (line + 1..=line.saturating_add(context_lines))
+                .map_while(|l| view.get_line(l))
+                .collect(),
+        }
+    }
 }
 
 /// Returns the zero indexed position from matches
@@ -52,17 +109,14 @@ fn count_whitespace_prefix(test: &str) -> i32 {
     result
 }
 
-pub fn print_source(token: &Token<'_>, view: &SourceView) {
-    let mut lines: Vec<&str> = vec![];
-    for offset in &[-3, -2, -1, 0, 1, 2, 3] {
-        let line = token.get_src_line() as isize + offset;
-        if line < 0 {
-            continue;
-        }
-        if let Some(line) = view.get_line(line as u32) {
-            lines.push(line);
-        }
-    }
+pub fn print_source(token: &Token<'_>, view: &SourceView, context_lines: u32) {
+    let context = SourceContext::new(token, view, context_lines);
+    let lines: Vec<&str> = context
+        .pre_lines
+        .into_iter()
+        .chain(context.context_line)
+        .chain(context.post_lines)
+        .collect();
     let lowest_indent = lines
         .iter()
         .map(|l| count_whitespace_prefix(l))
@@ -86,7 +140,7 @@ fn as_string_len<T: ToString>(to_string: T) -> usize {
     to_string.to_string().len()
 }
 
-fn print_token(token: &Token<'_>) {
+fn print_token(token: &Token<'_>, context_lines: u32) {
     let token_display_name = match token.get_name() {
         Some(name) => format!("token \"{name}\""),
         None => String::from("token (unnamed)"),
@@ -128,7 +182,7 @@ fn print_token(token: &Token<'_>) {
 
     if let Some(view) = token.get_source_view() {
         println!("  Source code:");
-        print_source(token, view);
+        print_source(token, view, context_lines);
     } else if token.get_source_view().is_none() {
         println!("  Cannot find source");
     } else {
@@ -142,6 +196,29 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         .ok_or_else(|| format_err!("Sourcemap not provided"))?;
 
     let sm = sourcemap::decode_slice(&fs::read(PathBuf::from(sourcemap_path))?)?;
+    let context_lines = *matches.get_one::<u32>("context_lines").unwrap();
+
+    if matches.get_flag("json") {
+        let (line, column) = lookup_pos(matches).unwrap_or_default();
+        let token = sm.lookup_token(line, column);
+        let resolved = token.as_ref().map(|token| {
+            let (src_line, src_col) = src_location(token);
+            let (dst_line, dst_col) = dst_location(token);
+            let view = token.get_source_view();
+            ResolvedToken {
+                source: token.get_source(),
+                line: src_line,
+                column: src_col,
+                name: token.get_name(),
+                minified_line: dst_line,
+                minified_column: dst_col,
+                sources_content: view.is_some(),
+                context: view.map(|view| SourceContext::new(token, view, context_lines)),
+            }
+        });
+        print_json(&resolved)?;
+        return Ok(());
+    }
 
     let ty = match sm {
         DecodedMap::Regular(..) => "regular",
@@ -160,7 +237,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             column + 1
         );
         if let Some(token) = sm.lookup_token(line, column) {
-            print_token(&token);
+            print_token(&token, context_lines);
         } else {
             println!("  - no token found!");
         }
@@ -1,21 +1,30 @@
 use std::env;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use console::style;
 use glob::{glob_with, MatchOptions};
 use itertools::Itertools;
 use log::{debug, warn};
+use regex::Regex;
 
 use crate::api::{Api, ChunkUploadCapability};
 use crate::config::Config;
 use crate::constants::DEFAULT_MAX_WAIT;
-use crate::utils::args::validate_distribution;
+use crate::utils::args::{get_url_prefix, validate_distribution, ArgExt};
 use crate::utils::file_search::ReleaseFileSearch;
 use crate::utils::file_upload::UploadContext;
+use crate::utils::formatting::print_json;
 use crate::utils::fs::path_as_url;
+use crate::utils::logging::set_quiet_mode;
+use crate::utils::path_list::get_path_list;
+use crate::utils::sourcemaps::source_paths::RewriteSourcesOptions;
+use crate::utils::sourcemaps::webpack::WebpackManifest;
 use crate::utils::sourcemaps::SourceMapProcessor;
+use crate::utils::value_parsers::dist_pattern_parser;
 
 const DEFAULT_EXTENSIONS: &[&str] = &["js", "cjs", "mjs", "map", "jsbundle", "bundle"];
 
@@ -27,7 +36,14 @@ pub fn make_command(command: Command) -> Command {
         .arg(
             Arg::new("paths")
                 .value_name("PATHS")
-                .required_unless_present_any(["bundle", "bundle_sourcemap"])
+                .required_unless_present_any([
+                    "bundle",
+                    "bundle_sourcemap",
+                    "stdin_paths",
+                    "paths_from",
+                    "webpack_manifest",
+                ])
+                .conflicts_with_all(["stdin_paths", "paths_from", "webpack_manifest"])
                 .num_args(1..)
                 .action(ArgAction::Append)
                 .help("The files to upload."),
@@ -37,7 +53,20 @@ pub fn make_command(command: Command) -> Command {
                 .short('u')
                 .long("url-prefix")
                 .value_name("PREFIX")
-                .help("The URL prefix to prepend to all filenames."),
+                .help(
+                    "The URL prefix to prepend to all filenames.  Relative URLs start with \
+                     `~/`, which matches any scheme and host.  Trailing slashes are removed, \
+                     except for those of an empty host: `~/static/` becomes `~/static` and \
+                     `app:///` becomes `app://`.  Prefixes without `~/` or a scheme and other \
+                     suspicious forms cause a warning with a suggested correction.",
+                ),
+        )
+        .arg(
+            Arg::new("strict_url_prefix")
+                .long("strict-url-prefix")
+                .action(ArgAction::SetTrue)
+                .requires("url_prefix")
+                .help("Fail instead of warning if the --url-prefix looks suspicious."),
         )
         .arg(
             Arg::new("url_suffix")
@@ -65,6 +94,15 @@ pub fn make_command(command: Command) -> Command {
                 .action(ArgAction::SetTrue)
                 .help("Enable basic sourcemap validation."),
         )
+        .arg(
+            Arg::new("fail_on_empty_sourcemap")
+                .long("fail-on-empty-sourcemap")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Fail instead of warning when a source map is empty, has no \
+                    mappings or no sources.",
+                ),
+        )
         .arg(
             Arg::new("decompress")
                 .long("decompress")
@@ -187,13 +225,35 @@ pub fn make_command(command: Command) -> Command {
                 .requires("bundle")
                 .help("Path to the bundle sourcemap"),
         )
+        .arg(
+            Arg::new("webpack_manifest")
+                .long("webpack-manifest")
+                .value_name("FILE")
+                .conflicts_with_all(["bundle", "stdin_paths", "paths_from"])
+                .help(
+                    "Upload the assets listed in a webpack stats file, as written by \
+                    `webpack --json`.  The URLs of the files are derived from the \
+                    `publicPath` of the build unless --url-prefix is given.",
+                ),
+        )
+        .arg(
+            Arg::new("since_release")
+                .long("since-release")
+                .value_name("VERSION")
+                .help(
+                    "Only upload files that changed since the given prior release.  \
+                    Files with debug ids whose name and contents match an artifact of \
+                    that release are reused instead of uploaded again.  Requires a \
+                    server that supports artifact bundles.",
+                ),
+        )
         .arg(
             Arg::new("no_dedupe")
                 .long("no-dedupe")
                 .action(ArgAction::SetTrue)
                 .help(
                     "Skip artifacts deduplication prior to uploading. \
-                    This will force all artifacts to be uploaded, \
+                    This will force all artifacts and their chunks to be uploaded, \
                     no matter whether they are already present on the server.",
                 ),
         )
@@ -250,6 +310,107 @@ pub fn make_command(command: Command) -> Command {
                 .short('v')
                 .hide(true),
         )
+        .path_list_args()
+        .arg(
+            Arg::new("externalize_inline_maps")
+                .long("externalize-inline-maps")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Extract source maps embedded as data URLs into separate .map \
+                    files and upload them next to their sources.  The uploaded \
+                    sources reference the extracted files instead of carrying \
+                    the inline map, unless --keep-inline-maps is passed.",
+                ),
+        )
+        .arg(
+            Arg::new("keep_inline_maps")
+                .long("keep-inline-maps")
+                .action(ArgAction::SetTrue)
+                .requires("externalize_inline_maps")
+                .help(
+                    "With --externalize-inline-maps, upload the sources with their \
+                    inline maps unchanged.",
+                ),
+        )
+        .arg(
+            Arg::new("prefetch_missing_chunks")
+                .long("prefetch-missing-chunks")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("no_dedupe")
+                .help(
+                    "Start uploading the first batch of chunks while asking the \
+                    server which chunks are missing.  This reduces latency for \
+                    large bundles, but may send chunks the server already has.",
+                ),
+        )
+        .arg(
+            Arg::new("chunked_threshold")
+                .long("chunked-threshold")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64))
+                .help(
+                    "Upload files that are smaller than BYTES in total as individual \
+                    release files instead of chunks, which takes fewer requests for small \
+                    uploads.  Requires a release.  By default, chunked uploads are used \
+                    whenever the server supports them, because release files cannot be \
+                    looked up by debug id.",
+                ),
+        )
+        .arg(
+            Arg::new("dist_from_filename")
+                .long("dist-from-filename")
+                .value_name("PATTERN")
+                .value_parser(dist_pattern_parser)
+                .help(
+                    "Derive the distribution of every file from its file name, using \
+                    the `dist` capture group of the given regular expression, e.g. \
+                    '\\.(?P<dist>ios|android)\\.js'.  Files that do not match use --dist.",
+                ),
+        )
+        .arg(
+            Arg::new("rewrite_sources")
+                .long("rewrite-sources")
+                .value_name("TRANSFORMS")
+                .value_parser(["strip-scheme", "normalize-paths"])
+                .value_delimiter(',')
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_values(["strip-scheme", "normalize-paths"])
+                .help(
+                    "Rewrite the `sources` entries of all source maps.  `strip-scheme` \
+                    removes bundler prefixes such as `webpack://`, `normalize-paths` \
+                    resolves `./` and `../` segments.  Applies both if no \
+                    comma-separated list of transformations is given.",
+                ),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Print a single JSON document instead of the upload report, with \
+                    the URL, path, type and status (uploaded or skipped) of the files \
+                    from all given paths and a summary of the counts.",
+                ),
+        )
+        .chunk_upload_args()
+}
+
+/// Applies the `--rewrite-sources` transformations, if requested.
+fn rewrite_sources_from_args(
+    matches: &ArgMatches,
+    processor: &mut SourceMapProcessor,
+) -> Result<()> {
+    let Some(transforms) = matches.get_many::<String>("rewrite_sources") else {
+        return Ok(());
+    };
+    let transforms: Vec<_> = transforms.map(String::as_str).collect();
+    let options = RewriteSourcesOptions {
+        strip_schemes: transforms.contains(&"strip-scheme"),
+        normalize_paths: transforms.contains(&"normalize-paths"),
+    };
+    debug!("Rewriting sources: {options:?}");
+    processor.rewrite_sources(&options)
 }
 
 fn get_prefixes_from_args(matches: &ArgMatches) -> Vec<&str> {
@@ -271,14 +432,7 @@ fn process_sources_from_bundle(
         .get_one::<String>("url_suffix")
         .map(String::as_str)
         .unwrap_or_default();
-    let mut url_prefix = matches
-        .get_one::<String>("url_prefix")
-        .map(String::as_str)
-        .unwrap_or("~");
-    // remove a single slash from the end.  so ~/ becomes ~ and app:/// becomes app://
-    if url_prefix.ends_with('/') {
-        url_prefix = &url_prefix[..url_prefix.len() - 1];
-    }
+    let url_prefix = get_url_prefix(matches)?.unwrap_or_else(|| "~".into());
 
     let bundle_path = PathBuf::from(matches.get_one::<String>("bundle").unwrap());
     let bundle_url = format!(
@@ -327,6 +481,7 @@ fn process_sources_from_bundle(
     debug!("Prefixes: {:?}", prefixes);
 
     processor.rewrite(&prefixes)?;
+    rewrite_sources_from_args(matches, processor)?;
     processor.add_sourcemap_references()?;
     processor.add_debug_id_references()?;
 
@@ -337,7 +492,6 @@ fn process_sources_from_paths(
     matches: &ArgMatches,
     processor: &mut SourceMapProcessor,
 ) -> Result<()> {
-    let paths = matches.get_many::<String>("paths").unwrap();
     let ignore_file = matches
         .get_one::<String>("ignore_file")
         .map(String::as_str)
@@ -351,8 +505,23 @@ fn process_sources_from_paths(
         .map(|ignores| ignores.map(|i| format!("!{i}")).collect())
         .unwrap_or_default();
 
-    let opts = MatchOptions::new();
-    let collected_paths = paths.flat_map(|path| glob_with(path, opts).unwrap().flatten());
+    let collected_paths: Vec<PathBuf> = match get_path_list(matches)? {
+        Some(paths) => paths,
+        None => {
+            let opts = MatchOptions::new();
+            matches
+                .get_many::<String>("paths")
+                .unwrap()
+                .flat_map(|path| glob_with(path, opts).unwrap().flatten())
+                .collect()
+        }
+    };
+
+    let url_suffix = matches
+        .get_one::<String>("url_suffix")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let url_prefix = get_url_prefix(matches)?.unwrap_or_else(|| "~".into());
 
     for path in collected_paths {
         // if we start walking over something that is an actual file then
@@ -378,19 +547,6 @@ fn process_sources_from_paths(
 
         let sources = search.collect_files()?;
 
-        let url_suffix = matches
-            .get_one::<String>("url_suffix")
-            .map(String::as_str)
-            .unwrap_or_default();
-        let mut url_prefix = matches
-            .get_one::<String>("url_prefix")
-            .map(String::as_str)
-            .unwrap_or("~");
-        // remove a single slash from the end.  so ~/ becomes ~ and app:/// becomes app://
-        if url_prefix.ends_with('/') {
-            url_prefix = &url_prefix[..url_prefix.len() - 1];
-        }
-
         for source in sources {
             let local_path = source.path.strip_prefix(base_path).unwrap();
             let url = format!("{}/{}{}", url_prefix, path_as_url(local_path), url_suffix);
@@ -398,11 +554,71 @@ fn process_sources_from_paths(
         }
     }
 
+    finish_processing(matches, processor)
+}
+
+fn process_sources_from_webpack_manifest(
+    matches: &ArgMatches,
+    processor: &mut SourceMapProcessor,
+) -> Result<()> {
+    let manifest_path = Path::new(matches.get_one::<String>("webpack_manifest").unwrap());
+    let manifest = WebpackManifest::from_path(manifest_path)?;
+    let extensions = matches
+        .get_many::<String>("extensions")
+        .map(|extensions| extensions.map(|ext| ext.trim_start_matches('.')).collect())
+        .unwrap_or_else(|| DEFAULT_EXTENSIONS.to_vec());
+
+    let url_suffix = matches
+        .get_one::<String>("url_suffix")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let url_prefix = match get_url_prefix(matches)? {
+        Some(url_prefix) => url_prefix,
+        None => manifest.url_prefix(),
+    };
+    debug!("URL prefix from webpack manifest: {}", url_prefix);
+
+    for name in manifest.assets() {
+        let path = manifest.output_path().join(name);
+        let has_extension = path
+            .extension()
+            .and_then(OsStr::to_str)
+            .is_some_and(|ext| extensions.contains(&ext));
+        if !has_extension {
+            continue;
+        }
+        if !path.is_file() {
+            warn!(
+                "File {} listed in the webpack manifest does not exist",
+                path.display()
+            );
+            continue;
+        }
+        let url = format!(
+            "{}/{}{}",
+            url_prefix,
+            name.trim_start_matches('/'),
+            url_suffix
+        );
+        processor.add(&url, ReleaseFileSearch::collect_file(path)?)?;
+    }
+
+    finish_processing(matches, processor)
+}
+
+/// Processes the added sources as requested by the command line arguments.
+fn finish_processing(matches: &ArgMatches, processor: &mut SourceMapProcessor) -> Result<()> {
+    if matches.get_flag("externalize_inline_maps") {
+        processor.externalize_inline_sourcemaps(!matches.get_flag("keep_inline_maps"))?;
+    }
+
     if !matches.get_flag("no_rewrite") {
         let prefixes = get_prefixes_from_args(matches);
         processor.rewrite(&prefixes)?;
     }
 
+    rewrite_sources_from_args(matches, processor)?;
+
     if !matches.get_flag("no_sourcemap_reference") {
         processor.add_sourcemap_references()?;
     }
@@ -424,6 +640,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let (org, project) = config.get_org_and_project(matches)?;
     let api = Api::current();
     let mut processor = SourceMapProcessor::new();
+    let json = matches.get_flag("json");
+
+    // Keep stdout free of anything but the JSON report.
+    if json {
+        set_quiet_mode(true);
+    }
     let mut chunk_upload_options = api.authenticated()?.get_chunk_upload_options(&org)?;
 
     if matches.get_flag("use_artifact_bundle")
@@ -436,15 +658,44 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         }
     }
 
+    if let Some(pattern) = matches.get_one::<Regex>("dist_from_filename") {
+        processor.dist_from_filename(pattern.clone());
+    }
+
     if matches.contains_id("bundle") && matches.contains_id("bundle_sourcemap") {
         process_sources_from_bundle(matches, &mut processor)?;
+    } else if matches.contains_id("webpack_manifest") {
+        process_sources_from_webpack_manifest(matches, &mut processor)?;
     } else {
         process_sources_from_paths(matches, &mut processor)?;
     }
 
+    let empty_sourcemaps = processor.find_empty_sourcemaps();
+    if !empty_sourcemaps.is_empty() {
+        eprintln!(
+            "{} {}: found {} empty source {}",
+            style(">").dim(),
+            style("Warning").red(),
+            empty_sourcemaps.len(),
+            match empty_sourcemaps.len() {
+                1 => "map",
+                _ => "maps",
+            }
+        );
+        for (url, reason) in &empty_sourcemaps {
+            eprintln!("    {url} ({reason})");
+        }
+        if matches.get_flag("fail_on_empty_sourcemap") {
+            bail!("Refusing to upload empty source maps.");
+        }
+    }
+
     let wait_for_secs = matches.get_one::<u64>("wait_for").copied();
     let wait = matches.get_flag("wait") || wait_for_secs.is_some();
     let max_wait = wait_for_secs.map_or(DEFAULT_MAX_WAIT, Duration::from_secs);
+    if matches.get_flag("no_dedupe") {
+        warn!("--no-dedupe uploads all chunks, which increases bandwidth usage.");
+    }
     let upload_context = UploadContext {
         org: &org,
         project: Some(&project),
@@ -454,14 +705,24 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         wait,
         max_wait,
         dedupe: !matches.get_flag("no_dedupe"),
+        prefetch_missing_chunks: matches.get_flag("prefetch_missing_chunks"),
+        chunked_threshold: matches.get_one::<u64>("chunked_threshold").copied(),
         chunk_upload_options: chunk_upload_options.as_ref(),
     };
 
+    if let Some(prior_release) = matches.get_one::<String>("since_release") {
+        processor.reuse_from_release(&upload_context, prior_release)?;
+    }
+
     if matches.get_flag("strict") {
         processor.upload_strict(&upload_context)?;
     } else {
         processor.upload(&upload_context)?;
     }
 
+    if json {
+        print_json(&processor.upload_report())?;
+    }
+
     Ok(())
 }
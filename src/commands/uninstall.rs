@@ -7,7 +7,7 @@ use console::style;
 
 use crate::utils::fs::is_writable;
 use crate::utils::system::{is_homebrew_install, is_npm_install, QuietExit};
-use crate::utils::ui::prompt_to_continue;
+use crate::utils::ui::confirm_destructive;
 
 pub fn make_command(command: Command) -> Command {
     let command = command.about("Uninstall the sentry-cli executable.").arg(
@@ -60,7 +60,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     }
 
     if !matches.get_flag("confirm")
-        && !prompt_to_continue("Do you really want to uninstall sentry-cli?")?
+        && !confirm_destructive("Do you really want to uninstall sentry-cli?")?
     {
         println!("Aborted!");
         return Ok(());
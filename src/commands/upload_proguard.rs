@@ -1,6 +1,7 @@
+use std::borrow::Cow;
 use std::env;
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -8,6 +9,7 @@ use anyhow::{bail, Error, Result};
 use clap::ArgAction;
 use clap::{Arg, ArgMatches, Command};
 use console::style;
+use encoding::EncodingRef;
 use log::info;
 use proguard::ProguardMapping;
 use symbolic::common::ByteView;
@@ -18,9 +20,11 @@ use crate::api::AssociateProguard;
 use crate::config::Config;
 use crate::utils::android::dump_proguard_uuids_as_properties;
 use crate::utils::args::ArgExt;
-use crate::utils::fs::TempFile;
+use crate::utils::chunks::UploadProtocol;
+use crate::utils::fs::{zip_file_options, TempFile};
 use crate::utils::proguard_upload;
 use crate::utils::system::QuietExit;
+use crate::utils::text_encoding::{normalize_line_endings, transcode_to_utf8};
 use crate::utils::ui::{copy_with_progress, make_byte_progress_bar};
 
 const CHUNK_UPLOAD_ENV_VAR: &str = "SENTRY_EXPERIMENTAL_PROGUARD_CHUNK_UPLOAD";
@@ -143,6 +147,18 @@ pub fn make_command(command: Command) -> Command {
                      a file with a forced UUID you can only upload a single proguard file.",
                 ),
         )
+        .arg(
+            Arg::new("normalize_line_endings")
+                .long("normalize-line-endings")
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Convert CRLF line endings to LF and strip trailing whitespace \
+                     before computing the UUID and uploading, so that the same mapping \
+                     gets the same UUID regardless of the platform that produced it.",
+                ),
+        )
+        .input_encoding_arg()
+        .chunk_upload_args()
 }
 
 pub fn execute(matches: &ArgMatches) -> Result<()> {
@@ -163,6 +179,12 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         );
     }
 
+    let input_encoding = matches.get_one::<EncodingRef>("input_encoding").copied();
+    let normalize = matches.get_flag("normalize_line_endings");
+    // Mappings that had to be transcoded or normalized are uploaded from temporary files,
+    // which need to live until the upload is done.
+    let mut transcoded_mappings = vec![];
+
     // since the mappings are quite small we don't bother doing a second http
     // request to figure out if any of the checksums are missing.  We just ship
     // them all up.
@@ -170,18 +192,53 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
         match fs::metadata(path) {
             Ok(md) => {
                 let byteview = ByteView::open(path).map_err(Error::new)?;
-                let mapping = ProguardMapping::new(&byteview);
+                let mut contents = transcode_to_utf8(&byteview, input_encoding, path);
+                if normalize {
+                    if let Some(normalized) = normalize_line_endings(&contents) {
+                        let original_uuid = ProguardMapping::new(&contents).uuid();
+                        let normalized_uuid = ProguardMapping::new(&normalized).uuid();
+                        if original_uuid == normalized_uuid {
+                            eprintln!(
+                                "warning: normalized line endings of proguard mapping \
+                                 '{path}', its UUID is unchanged."
+                            );
+                        } else {
+                            eprintln!(
+                                "warning: normalized line endings of proguard mapping \
+                                 '{path}', which changed its UUID from {original_uuid} \
+                                 to {normalized_uuid}."
+                            );
+                        }
+                        contents = Cow::Owned(normalized);
+                    }
+                }
+                let mapping = ProguardMapping::new(&contents);
                 if !mapping.has_line_info() {
                     eprintln!(
                         "warning: proguard mapping '{path}' was ignored because it \
                          does not contain any line information."
                     );
                 } else {
-                    mappings.push(MappingRef {
-                        path: PathBuf::from(path),
-                        size: md.len(),
-                        uuid: forced_uuid.copied().unwrap_or_else(|| mapping.uuid()),
-                    });
+                    let uuid = forced_uuid.copied().unwrap_or_else(|| mapping.uuid());
+                    let mapping = match contents {
+                        Cow::Borrowed(_) => MappingRef {
+                            path: PathBuf::from(path),
+                            size: md.len(),
+                            uuid,
+                        },
+                        Cow::Owned(contents) => {
+                            let temp_file = TempFile::create()?;
+                            temp_file.open()?.write_all(&contents)?;
+                            let mapping = MappingRef {
+                                path: temp_file.path().to_owned(),
+                                size: contents.len() as u64,
+                                uuid,
+                            };
+                            transcoded_mappings.push(temp_file);
+                            mapping
+                        }
+                    };
+                    mappings.push(mapping);
                 }
             }
             Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
@@ -208,7 +265,19 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
     let authenticated_api;
     let (org, project);
 
-    if env::var(CHUNK_UPLOAD_ENV_VAR) == Ok("1".into()) {
+    let chunked = match config.get_upload_protocol() {
+        UploadProtocol::Auto => env::var(CHUNK_UPLOAD_ENV_VAR) == Ok("1".into()),
+        UploadProtocol::Chunked => {
+            info!("using chunked upload, forced by --upload-protocol");
+            true
+        }
+        UploadProtocol::Legacy => {
+            info!("using legacy upload, forced by --upload-protocol");
+            false
+        }
+    };
+
+    if chunked {
         log::warn!(
             "EXPERIMENTAL FEATURE: Uploading proguard mappings using chunked uploading. \
              Some functionality may be unavailable when using chunked uploading. Please unset \
@@ -245,10 +314,7 @@ pub fn execute(matches: &ArgMatches) -> Result<()> {
             let mut zip = zip::ZipWriter::new(tf.open()?);
             for mapping in &mappings {
                 let pb = make_byte_progress_bar(mapping.size);
-                zip.start_file(
-                    format!("proguard/{}.txt", mapping.uuid),
-                    zip::write::FileOptions::default(),
-                )?;
+                zip.start_file(format!("proguard/{}.txt", mapping.uuid), zip_file_options())?;
                 copy_with_progress(&pb, &mut fs::File::open(&mapping.path)?, &mut zip)?;
                 pb.finish_and_clear();
             }
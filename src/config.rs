@@ -1,10 +1,12 @@
 //! This module implements config access.
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{bail, format_err, Context, Error, Result};
 use clap::ArgMatches;
@@ -15,13 +17,19 @@ use parking_lot::Mutex;
 use secrecy::ExposeSecret;
 use sentry::types::Dsn;
 
+use crate::api::MultipartBoundaryCharset;
 use crate::constants::CONFIG_INI_FILE_PATH;
 use crate::constants::DEFAULT_MAX_DIF_ITEM_SIZE;
 use crate::constants::DEFAULT_MAX_DIF_UPLOAD_SIZE;
-use crate::constants::{CONFIG_RC_FILE_NAME, DEFAULT_RETRIES, DEFAULT_URL};
+use crate::constants::{CONFIG_RC_FILE_NAME, DEFAULT_API_VERSION, DEFAULT_RETRIES, DEFAULT_URL};
+use crate::constants::{
+    DEFAULT_ASSEMBLE_POLL_INTERVAL, DEFAULT_MAX_REDIRECTS, DEFAULT_PREFLIGHT_CONNECT_TIMEOUT,
+};
 use crate::utils::auth_token::AuthToken;
 use crate::utils::auth_token::AuthTokenPayload;
+use crate::utils::chunks::UploadProtocol;
 use crate::utils::http::is_absolute_url;
+use crate::utils::value_parsers::rate_limit_parser;
 
 #[cfg(target_os = "macos")]
 use crate::utils::xcode;
@@ -44,6 +52,24 @@ pub struct Config {
     ini: Ini,
     cached_auth: Option<Auth>,
     cached_base_url: String,
+    cached_api_version: u32,
+    cached_keepalive_timeout: Option<u64>,
+    cached_proxy_url: Option<String>,
+    cached_proxy_username: Option<String>,
+    cached_proxy_password: Option<String>,
+    cached_max_chunk_batch: Option<u64>,
+    cached_upload_protocol: Option<UploadProtocol>,
+    cached_project_slugs: HashMap<String, String>,
+    cached_preflight_connect_timeout: Option<u64>,
+    cached_multipart_boundary_charset: Option<MultipartBoundaryCharset>,
+    cached_retry_idempotent_post: Option<bool>,
+    cached_max_concurrent_assembles: Option<u32>,
+    cached_assemble_max_attempts: Option<u32>,
+    cached_assemble_poll_interval: Option<u64>,
+    cached_upload_max_retries: Option<u32>,
+    cached_rate_limit: Option<f64>,
+    cached_dump_request_bodies: Option<(PathBuf, bool)>,
+    cached_max_redirects: Option<u32>,
     cached_headers: Option<Vec<String>>,
     cached_log_level: log::LevelFilter,
     cached_vcs_remote: String,
@@ -83,6 +109,24 @@ impl Config {
             process_bound: false,
             cached_auth: auth,
             cached_base_url: url,
+            cached_api_version: DEFAULT_API_VERSION,
+            cached_keepalive_timeout: None,
+            cached_proxy_url: None,
+            cached_proxy_username: None,
+            cached_proxy_password: None,
+            cached_max_chunk_batch: None,
+            cached_upload_protocol: None,
+            cached_project_slugs: HashMap::new(),
+            cached_preflight_connect_timeout: None,
+            cached_multipart_boundary_charset: None,
+            cached_retry_idempotent_post: None,
+            cached_max_concurrent_assembles: None,
+            cached_assemble_max_attempts: None,
+            cached_assemble_poll_interval: None,
+            cached_upload_max_retries: None,
+            cached_rate_limit: None,
+            cached_dump_request_bodies: None,
+            cached_max_redirects: None,
             cached_headers: get_default_headers(&ini),
             cached_log_level: get_default_log_level(&ini),
             cached_vcs_remote: get_default_vcs_remote(&ini),
@@ -161,6 +205,30 @@ impl Config {
         Ok(())
     }
 
+    /// Returns the data embedded in the auth token, if any.
+    pub fn get_token_data(&self) -> Option<&AuthTokenPayload> {
+        self.cached_token_data.as_ref()
+    }
+
+    /// Returns a raw config value, including overrides from the command line.
+    pub fn get_ini_value(&self, section: &str, key: &str) -> Option<&str> {
+        self.ini.get_from(Some(section), key)
+    }
+
+    /// Returns the section and key of every raw config value, including
+    /// overrides from the command line.
+    pub fn get_ini_keys(&self) -> Vec<(String, String)> {
+        self.ini
+            .iter()
+            .filter_map(|(section, props)| Some((section?, props)))
+            .flat_map(|(section, props)| {
+                props
+                    .iter()
+                    .map(move |(key, _)| (section.to_owned(), key.to_owned()))
+            })
+            .collect()
+    }
+
     /// Returns the auth info
     pub fn get_auth(&self) -> Option<&Auth> {
         self.cached_auth.as_ref()
@@ -228,6 +296,21 @@ impl Config {
         }
     }
 
+    /// Returns the version of the Sentry API that requests target.
+    pub fn get_api_version(&self) -> u32 {
+        self.cached_api_version
+    }
+
+    /// Overrides the version of the Sentry API that requests target.
+    pub fn set_api_version(&mut self, version: u32) {
+        self.cached_api_version = version;
+    }
+
+    /// Returns the `Accept` header value that negotiates the API version.
+    pub fn get_api_accept_header(&self) -> String {
+        format!("application/json; version={}", self.get_api_version())
+    }
+
     /// Sets headers that should be attached to all requests
     pub fn set_headers(&mut self, headers: Vec<String>) {
         self.cached_headers = Some(headers);
@@ -262,8 +345,12 @@ impl Config {
 
     /// Indicates whether keepalive support should be enabled.  This
     /// mostly corresponds to an ini config but also has some sensible
-    /// default handling.
+    /// default handling.  An explicit keepalive timeout of zero always
+    /// disables it.
     pub fn allow_keepalive(&self) -> bool {
+        if let Some(timeout) = self.get_keepalive_timeout() {
+            return !timeout.is_zero();
+        }
         let val = self.ini.get_from(Some("http"), "keepalive");
         match val {
             // keepalive is broken on our dev server.  Since this makes local development
@@ -274,9 +361,46 @@ impl Config {
         }
     }
 
+    /// Returns the keepalive timeout if one was configured.
+    ///
+    /// This is how long idle connections are kept around for reuse and
+    /// how long a connection is idle before TCP keepalive probes are sent.
+    pub fn get_keepalive_timeout(&self) -> Option<Duration> {
+        self.cached_keepalive_timeout
+            .or_else(|| {
+                env::var("SENTRY_HTTP_KEEPALIVE")
+                    .ok()
+                    .and_then(|val| val.parse().ok())
+            })
+            .or_else(|| {
+                self.ini
+                    .get_from(Some("http"), "keepalive_timeout")
+                    .and_then(|val| val.parse().ok())
+            })
+            .map(Duration::from_secs)
+    }
+
+    /// Returns the interval between TCP keepalive probes if one was
+    /// configured.
+    pub fn get_keepalive_interval(&self) -> Option<Duration> {
+        env::var("SENTRY_HTTP_KEEPALIVE_INTERVAL")
+            .ok()
+            .as_deref()
+            .or_else(|| self.ini.get_from(Some("http"), "keepalive_interval"))
+            .and_then(|val| val.parse().ok())
+            .map(Duration::from_secs)
+    }
+
+    /// Overrides the keepalive timeout in seconds.  Zero disables keepalive.
+    pub fn set_keepalive_timeout(&mut self, seconds: u64) {
+        self.cached_keepalive_timeout = Some(seconds);
+    }
+
     /// Returns the proxy URL if defined.
     pub fn get_proxy_url(&self) -> Option<String> {
-        if env::var_os("http_proxy").is_some() {
+        if let Some(ref proxy_url) = self.cached_proxy_url {
+            Some(proxy_url.clone())
+        } else if env::var_os("http_proxy").is_some() {
             env::var("http_proxy").ok()
         } else {
             self.ini
@@ -285,14 +409,56 @@ impl Config {
         }
     }
 
+    /// Overrides the proxy URL, taking precedence over the environment.
+    pub fn set_proxy_url(&mut self, proxy_url: &str) {
+        for var in [
+            "http_proxy",
+            "https_proxy",
+            "HTTPS_PROXY",
+            "all_proxy",
+            "ALL_PROXY",
+        ] {
+            if env::var_os(var).is_some() {
+                debug!("--proxy-url overrides the proxy set in {var}");
+            }
+        }
+        self.cached_proxy_url = Some(proxy_url.to_owned());
+    }
+
+    /// Overrides the proxy username.  The override is not saved to the
+    /// config file.
+    pub fn set_proxy_username(&mut self, username: &str) {
+        self.cached_proxy_username = Some(username.to_owned());
+    }
+
+    /// Overrides the proxy password.  The override is not saved to the
+    /// config file.
+    pub fn set_proxy_password(&mut self, password: &str) {
+        self.cached_proxy_password = Some(password.to_owned());
+    }
+
     /// Returns the proxy username if defined.
-    pub fn get_proxy_username(&self) -> Option<&str> {
-        self.ini.get_from(Some("http"), "proxy_username")
+    pub fn get_proxy_username(&self) -> Option<String> {
+        self.cached_proxy_username
+            .clone()
+            .or_else(|| env::var("SENTRY_HTTP_PROXY_USERNAME").ok())
+            .or_else(|| {
+                self.ini
+                    .get_from(Some("http"), "proxy_username")
+                    .map(str::to_owned)
+            })
     }
 
     /// Returns the proxy password if defined.
-    pub fn get_proxy_password(&self) -> Option<&str> {
-        self.ini.get_from(Some("http"), "proxy_password")
+    pub fn get_proxy_password(&self) -> Option<String> {
+        self.cached_proxy_password
+            .clone()
+            .or_else(|| env::var("SENTRY_HTTP_PROXY_PASSWORD").ok())
+            .or_else(|| {
+                self.ini
+                    .get_from(Some("http"), "proxy_password")
+                    .map(str::to_owned)
+            })
     }
 
     /// Indicates if SSL is enabled or disabled for the server.
@@ -300,6 +466,121 @@ impl Config {
         self.get_base_url().unwrap_or("").starts_with("http://")
     }
 
+    /// Indicates whether idempotent POST requests, such as assembling
+    /// uploaded chunks, should also be retried on connection and server errors.
+    pub fn retry_idempotent_post(&self) -> bool {
+        if let Some(retry) = self.cached_retry_idempotent_post {
+            return retry;
+        }
+        if let Ok(val) = env::var("SENTRY_HTTP_RETRY_IDEMPOTENT_POST") {
+            return val == "1" || val == "true";
+        }
+        self.ini.get_from(Some("http"), "retry_idempotent_post") == Some("true")
+    }
+
+    /// Enables retrying idempotent POST requests.
+    pub fn set_retry_idempotent_post(&mut self) {
+        self.cached_retry_idempotent_post = Some(true);
+    }
+
+    /// Returns the directory request bodies are dumped to, if any.
+    pub fn get_dump_request_bodies_dir(&self) -> Option<PathBuf> {
+        if let Some((ref dir, _)) = self.cached_dump_request_bodies {
+            return Some(dir.clone());
+        }
+        self.ini
+            .get_from(Some("http"), "dump_request_bodies")
+            .map(PathBuf::from)
+    }
+
+    /// Indicates whether dumped request bodies are written completely,
+    /// regardless of their size.
+    pub fn dump_full_request_bodies(&self) -> bool {
+        if let Some((_, full)) = self.cached_dump_request_bodies {
+            return full;
+        }
+        self.ini.get_from(Some("http"), "dump_full") == Some("true")
+    }
+
+    /// Enables dumping of request bodies to the given directory.
+    pub fn set_dump_request_bodies(&mut self, dir: &Path, full: bool) {
+        self.cached_dump_request_bodies = Some((dir.to_owned(), full));
+    }
+
+    /// Returns the maximum number of redirects followed per request.
+    pub fn get_max_redirects(&self) -> u32 {
+        self.cached_max_redirects
+            .or_else(|| {
+                env::var("SENTRY_HTTP_MAX_REDIRECTS")
+                    .ok()
+                    .as_deref()
+                    .or_else(|| self.ini.get_from(Some("http"), "max_redirects"))
+                    .and_then(|val| val.parse().ok())
+            })
+            .unwrap_or(DEFAULT_MAX_REDIRECTS)
+    }
+
+    /// Overrides the maximum number of redirects followed per request.
+    pub fn set_max_redirects(&mut self, max_redirects: u32) {
+        self.cached_max_redirects = Some(max_redirects);
+    }
+
+    /// Returns the connect timeout of the server info request that precedes
+    /// uploads.  A zero timeout waits as long as for any other request.
+    pub fn get_preflight_connect_timeout(&self) -> Duration {
+        let seconds = self.cached_preflight_connect_timeout.unwrap_or_else(|| {
+            env::var("SENTRY_HTTP_CONNECT_TIMEOUT")
+                .ok()
+                .as_deref()
+                .or_else(|| self.ini.get_from(Some("http"), "connect_timeout"))
+                .and_then(|val| val.parse().ok())
+                .unwrap_or(DEFAULT_PREFLIGHT_CONNECT_TIMEOUT)
+        });
+        Duration::from_secs(seconds)
+    }
+
+    /// Overrides the connect timeout in seconds of the server info request.
+    pub fn set_preflight_connect_timeout(&mut self, seconds: u64) {
+        self.cached_preflight_connect_timeout = Some(seconds);
+    }
+
+    /// Returns the maximum number of requests per second, if requests are
+    /// throttled.
+    pub fn get_rate_limit(&self) -> Option<f64> {
+        self.cached_rate_limit.or_else(|| {
+            env::var("SENTRY_HTTP_RATE_LIMIT")
+                .ok()
+                .as_deref()
+                .or_else(|| self.ini.get_from(Some("http"), "rate_limit"))
+                .and_then(|val| rate_limit_parser(val).ok())
+        })
+    }
+
+    /// Overrides the maximum number of requests per second.
+    pub fn set_rate_limit(&mut self, per_second: f64) {
+        self.cached_rate_limit = Some(per_second);
+    }
+
+    /// Returns the set of characters used for multipart form boundaries.
+    pub fn get_multipart_boundary_charset(&self) -> MultipartBoundaryCharset {
+        self.cached_multipart_boundary_charset.unwrap_or_else(|| {
+            env::var("SENTRY_HTTP_MULTIPART_BOUNDARY_CHARSET")
+                .ok()
+                .as_deref()
+                .or_else(|| {
+                    self.ini
+                        .get_from(Some("http"), "multipart_boundary_charset")
+                })
+                .and_then(MultipartBoundaryCharset::from_name)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Overrides the set of characters used for multipart form boundaries.
+    pub fn set_multipart_boundary_charset(&mut self, charset: MultipartBoundaryCharset) {
+        self.cached_multipart_boundary_charset = Some(charset);
+    }
+
     /// Indicates whether SSL verification should be on or off.
     pub fn should_verify_ssl(&self) -> bool {
         let val = self.ini.get_from(Some("http"), "verify_ssl");
@@ -385,12 +666,40 @@ impl Config {
     }
 
     /// Given a match object from clap, this returns the projects from it.
+    /// Numeric project IDs are resolved to their slugs.
     pub fn get_projects(&self, matches: &ArgMatches) -> Result<Vec<String>> {
-        if let Some(projects) = matches.get_many::<String>("project") {
-            Ok(projects.cloned().collect())
+        let projects = if let Some(projects) = matches.get_many::<String>("project") {
+            projects.cloned().collect()
         } else {
-            Ok(vec![self.get_project_default()?])
+            vec![self.get_project_default()?]
+        };
+        projects
+            .into_iter()
+            .map(|project| self.resolve_project_slug(matches, project))
+            .collect()
+    }
+
+    /// Sets the slugs of the projects in the organization, keyed by project ID.
+    pub fn set_project_slugs(&mut self, slugs: HashMap<String, String>) {
+        self.cached_project_slugs = slugs;
+    }
+
+    /// Returns the slug of a numeric project ID.  Slugs are returned
+    /// unchanged, as Sentry does not allow purely numeric slugs.
+    fn resolve_project_slug(&self, matches: &ArgMatches, project: String) -> Result<String> {
+        if !is_project_id(&project) {
+            return Ok(project);
         }
+
+        self.cached_project_slugs
+            .get(&project)
+            .cloned()
+            .ok_or_else(|| {
+                format_err!(
+                    "Project ID {project} does not match any project in organization {}.",
+                    self.get_org(matches).unwrap_or_default()
+                )
+            })
     }
 
     /// Given a match object from clap, this returns a tuple in the
@@ -471,6 +780,115 @@ impl Config {
         }
     }
 
+    /// Returns how often requests that transfer files are retried.  Defaults
+    /// to the retry count of other requests.
+    pub fn get_upload_max_retries(&self) -> Result<u32> {
+        if let Some(retries) = self.cached_upload_max_retries {
+            Ok(retries)
+        } else if env::var_os("SENTRY_HTTP_UPLOAD_MAX_RETRIES").is_some() {
+            Ok(env::var("SENTRY_HTTP_UPLOAD_MAX_RETRIES")?.parse()?)
+        } else if let Some(val) = self.ini.get_from(Some("http"), "upload_max_retries") {
+            Ok(val.parse()?)
+        } else {
+            self.get_max_retry_count()
+        }
+    }
+
+    /// Overrides how often requests that transfer files are retried.
+    pub fn set_upload_max_retries(&mut self, retries: u32) {
+        self.cached_upload_max_retries = Some(retries);
+    }
+
+    /// Returns the interval between assemble requests while waiting for the
+    /// server to process uploaded files.
+    pub fn get_assemble_poll_interval(&self) -> Duration {
+        self.cached_assemble_poll_interval
+            .or_else(|| {
+                env::var("SENTRY_HTTP_ASSEMBLE_POLL_INTERVAL")
+                    .ok()
+                    .as_deref()
+                    .or_else(|| self.ini.get_from(Some("http"), "assemble_poll_interval"))
+                    .and_then(|val| val.parse().ok())
+            })
+            .map_or(DEFAULT_ASSEMBLE_POLL_INTERVAL, Duration::from_secs)
+    }
+
+    /// Overrides the interval in seconds between assemble requests.
+    pub fn set_assemble_poll_interval(&mut self, seconds: u64) {
+        self.cached_assemble_poll_interval = Some(seconds);
+    }
+
+    /// Returns the maximum number of assemble requests sent while waiting for
+    /// the server to process uploaded files, if limited.
+    pub fn get_assemble_max_attempts(&self) -> Option<u32> {
+        self.cached_assemble_max_attempts.or_else(|| {
+            env::var("SENTRY_HTTP_ASSEMBLE_MAX_ATTEMPTS")
+                .ok()
+                .as_deref()
+                .or_else(|| self.ini.get_from(Some("http"), "assemble_max_attempts"))
+                .and_then(|val| val.parse().ok())
+        })
+    }
+
+    /// Overrides the maximum number of assemble requests.
+    pub fn set_assemble_max_attempts(&mut self, attempts: u32) {
+        self.cached_assemble_max_attempts = Some(attempts);
+    }
+
+    /// Returns the maximum number of assemble batches polled at once, if
+    /// limited explicitly.
+    pub fn get_max_concurrent_assembles(&self) -> Result<Option<u32>> {
+        if let Some(max) = self.cached_max_concurrent_assembles {
+            return Ok(Some(max));
+        }
+        let max: u32 = if env::var_os("SENTRY_HTTP_MAX_CONCURRENT_ASSEMBLES").is_some() {
+            env::var("SENTRY_HTTP_MAX_CONCURRENT_ASSEMBLES")?.parse()?
+        } else if let Some(val) = self.ini.get_from(Some("http"), "max_concurrent_assembles") {
+            val.parse()?
+        } else {
+            return Ok(None);
+        };
+        if max == 0 {
+            bail!("The maximum number of concurrent assembles must be at least 1");
+        }
+        Ok(Some(max))
+    }
+
+    /// Overrides the maximum number of assemble batches polled at once.
+    pub fn set_max_concurrent_assembles(&mut self, max: u32) {
+        self.cached_max_concurrent_assembles = Some(max);
+    }
+
+    /// Returns the maximum number of chunks to send per upload request, if
+    /// it was lowered from the server's limit.
+    pub fn get_max_chunk_batch(&self) -> Option<u64> {
+        self.cached_max_chunk_batch.or_else(|| {
+            self.ini
+                .get_from(Some("http"), "max_chunk_batch")
+                .and_then(|val| val.parse().ok())
+        })
+    }
+
+    /// Lowers the number of chunks sent per upload request.
+    pub fn set_max_chunk_batch(&mut self, max_chunks: u64) {
+        self.cached_max_chunk_batch = Some(max_chunks);
+    }
+
+    /// Returns the protocol that uploads should use.
+    pub fn get_upload_protocol(&self) -> UploadProtocol {
+        self.cached_upload_protocol.unwrap_or_else(|| {
+            self.ini
+                .get_from(Some("http"), "upload_protocol")
+                .and_then(UploadProtocol::from_name)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Overrides the protocol that uploads should use.
+    pub fn set_upload_protocol(&mut self, protocol: UploadProtocol) {
+        self.cached_upload_protocol = Some(protocol);
+    }
+
     /// Return the DSN
     pub fn get_dsn(&self) -> Result<Dsn> {
         if let Ok(val) = env::var("SENTRY_DSN") {
@@ -517,6 +935,21 @@ impl Config {
                 false
             }
     }
+
+    /// Indicates whether a summary of the requests made should be printed.
+    pub fn get_metrics_summary(&self, matches: &ArgMatches) -> bool {
+        !matches.get_flag("no_metrics_summary")
+            && if let Ok(var) = env::var("SENTRY_METRICS_SUMMARY") {
+                &var != "0" && &var != "false"
+            } else {
+                true
+            }
+    }
+}
+
+/// Indicates whether the project is given as a numeric ID instead of a slug.
+pub fn is_project_id(project: &str) -> bool {
+    !project.is_empty() && project.bytes().all(|b| b.is_ascii_digit())
 }
 
 fn warn_about_conflicting_urls(token_url: &str, manually_configured_url: Option<&str>) {
@@ -595,10 +1028,31 @@ fn failed_local_config_load_message(file_desc: &str) -> String {
     msg
 }
 
-fn load_cli_config() -> Result<(PathBuf, Ini)> {
-    let (global_filename, mut rv) = load_global_config_file()?;
+/// A config source that contributes to the effective configuration.
+pub struct ConfigLayer {
+    /// Describes where the values come from.
+    pub description: String,
+    pub ini: Ini,
+}
+
+/// Loads every config file separately, from lowest to highest precedence.
+///
+/// The returned path is the file that the config is stored to.
+pub fn load_config_layers() -> Result<(PathBuf, Vec<ConfigLayer>)> {
+    let (global_filename, global_ini) = load_global_config_file()?;
+    let mut layers = vec![];
+    if !global_filename.as_os_str().is_empty() {
+        layers.push(ConfigLayer {
+            description: format!(
+                "{} file from the home folder ({})",
+                CONFIG_RC_FILE_NAME,
+                global_filename.display()
+            ),
+            ini: global_ini,
+        });
+    }
 
-    let (path, mut rv) = if let Some(project_config_path) = find_project_config_file() {
+    let path = if let Some(project_config_path) = find_project_config_file() {
         let file_desc = format!(
             "{} file from project path ({})",
             CONFIG_RC_FILE_NAME,
@@ -607,14 +1061,13 @@ fn load_cli_config() -> Result<(PathBuf, Ini)> {
         let mut f = fs::File::open(&project_config_path)
             .context(failed_local_config_load_message(&file_desc))?;
         let ini = Ini::read_from(&mut f).context(format!("Failed to parse {file_desc}"))?;
-        for (section, props) in ini.iter() {
-            for (key, value) in props.iter() {
-                rv.set_to(section, key.to_string(), value.to_owned());
-            }
-        }
-        (project_config_path, rv)
+        layers.push(ConfigLayer {
+            description: file_desc,
+            ini,
+        });
+        project_config_path
     } else {
-        (global_filename, rv)
+        global_filename
     };
 
     if let Ok(prop_path) = env::var("SENTRY_PROPERTIES") {
@@ -630,15 +1083,20 @@ fn load_cli_config() -> Result<(PathBuf, Ini)> {
                     "Loaded file referenced by SENTRY_PROPERTIES ({})",
                     &prop_path
                 );
+                let mut ini = Ini::new();
                 for (key, value) in props {
                     let mut iter = key.rsplitn(2, '.');
                     if let Some(key) = iter.next() {
                         let section = iter.next();
-                        rv.set_to(section, key.to_string(), value);
+                        ini.set_to(section, key.to_string(), value);
                     } else {
                         debug!("Incorrect properties file key: {}", key);
                     }
                 }
+                layers.push(ConfigLayer {
+                    description: format!("file referenced by SENTRY_PROPERTIES ({prop_path})"),
+                    ini,
+                });
             }
             Err(err) => {
                 if err.kind() != io::ErrorKind::NotFound {
@@ -656,6 +1114,20 @@ fn load_cli_config() -> Result<(PathBuf, Ini)> {
         }
     }
 
+    Ok((path, layers))
+}
+
+fn load_cli_config() -> Result<(PathBuf, Ini)> {
+    let (path, layers) = load_config_layers()?;
+    let mut rv = Ini::new();
+    for layer in layers {
+        for (section, props) in layer.ini.iter() {
+            for (key, value) in props.iter() {
+                rv.set_to(section, key.to_string(), value.to_owned());
+            }
+        }
+    }
+
     Ok((path, rv))
 }
 
@@ -667,6 +1139,24 @@ impl Clone for Config {
             ini: self.ini.clone(),
             cached_auth: self.cached_auth.clone(),
             cached_base_url: self.cached_base_url.clone(),
+            cached_api_version: self.cached_api_version,
+            cached_keepalive_timeout: self.cached_keepalive_timeout,
+            cached_proxy_url: self.cached_proxy_url.clone(),
+            cached_proxy_username: self.cached_proxy_username.clone(),
+            cached_proxy_password: self.cached_proxy_password.clone(),
+            cached_max_chunk_batch: self.cached_max_chunk_batch,
+            cached_upload_protocol: self.cached_upload_protocol,
+            cached_project_slugs: self.cached_project_slugs.clone(),
+            cached_preflight_connect_timeout: self.cached_preflight_connect_timeout,
+            cached_multipart_boundary_charset: self.cached_multipart_boundary_charset,
+            cached_retry_idempotent_post: self.cached_retry_idempotent_post,
+            cached_max_concurrent_assembles: self.cached_max_concurrent_assembles,
+            cached_assemble_max_attempts: self.cached_assemble_max_attempts,
+            cached_assemble_poll_interval: self.cached_assemble_poll_interval,
+            cached_upload_max_retries: self.cached_upload_max_retries,
+            cached_rate_limit: self.cached_rate_limit,
+            cached_dump_request_bodies: self.cached_dump_request_bodies.clone(),
+            cached_max_redirects: self.cached_max_redirects,
             cached_headers: self.cached_headers.clone(),
             cached_log_level: self.cached_log_level,
             cached_vcs_remote: self.cached_vcs_remote.clone(),
@@ -744,19 +1234,41 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_get_api_endpoint() {
-        let config = Config {
+    fn test_config() -> Config {
+        Config {
             filename: PathBuf::from("/path/to/config"),
             process_bound: false,
             ini: Default::default(),
             cached_auth: None,
             cached_base_url: "https://sentry.io/".to_string(),
+            cached_api_version: DEFAULT_API_VERSION,
+            cached_keepalive_timeout: None,
+            cached_proxy_url: None,
+            cached_proxy_username: None,
+            cached_proxy_password: None,
+            cached_max_chunk_batch: None,
+            cached_upload_protocol: None,
+            cached_project_slugs: HashMap::new(),
+            cached_preflight_connect_timeout: None,
+            cached_multipart_boundary_charset: None,
+            cached_retry_idempotent_post: None,
+            cached_max_concurrent_assembles: None,
+            cached_assemble_max_attempts: None,
+            cached_assemble_poll_interval: None,
+            cached_upload_max_retries: None,
+            cached_rate_limit: None,
+            cached_dump_request_bodies: None,
+            cached_max_redirects: None,
             cached_headers: None,
             cached_log_level: LevelFilter::Off,
             cached_vcs_remote: String::new(),
             cached_token_data: None,
-        };
+        }
+    }
+
+    #[test]
+    fn test_get_api_endpoint() {
+        let config = test_config();
 
         assert_eq!(
             config
@@ -782,4 +1294,30 @@ mod tests {
             "https://us.sentry.io/api/0/organizations/test-org/chunk-upload/"
         );
     }
+
+    #[test]
+    fn test_get_api_endpoint_with_api_version() {
+        let mut config = test_config();
+        config.set_api_version(1);
+
+        assert_eq!(
+            config
+                .get_api_endpoint("/organizations/test-org/chunk-upload/", None)
+                .unwrap(),
+            "https://sentry.io/api/0/organizations/test-org/chunk-upload/"
+        );
+        assert_eq!(
+            config.get_api_accept_header(),
+            "application/json; version=1"
+        );
+    }
+
+    #[test]
+    fn test_zero_keepalive_timeout_disables_keepalive() {
+        let mut config = test_config();
+        assert!(config.allow_keepalive());
+
+        config.set_keepalive_timeout(0);
+        assert!(!config.allow_keepalive());
+    }
 }
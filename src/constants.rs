@@ -11,6 +11,9 @@ pub const DEFAULT_URL: &str = "https://sentry.io/";
 /// The version of the library
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// The version of the Sentry API this CLI was built against
+pub const DEFAULT_API_VERSION: u32 = 0;
+
 /// The name of the configuration file.
 pub const CONFIG_RC_FILE_NAME: &str = ".sentryclirc";
 
@@ -39,6 +42,13 @@ pub const DEFAULT_INITIAL_INTERVAL: u64 = 1000;
 pub const DEFAULT_MAX_INTERVAL: u64 = 5000;
 /// Default number of retry attempts
 pub const DEFAULT_RETRIES: u32 = 5;
+/// Default interval between assemble requests while waiting for processing.
+pub const DEFAULT_ASSEMBLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Default maximum number of redirects followed per request.
+pub const DEFAULT_MAX_REDIRECTS: u32 = 5;
+/// Default connect timeout in seconds of the server info request that
+/// precedes uploads.
+pub const DEFAULT_PREFLIGHT_CONNECT_TIMEOUT: u64 = 10;
 /// Default maximum file size of DIF uploads.
 pub const DEFAULT_MAX_DIF_SIZE: u64 = 2 * 1024 * 1024 * 1024; // 2GB
 /// Default maximum file size of a single file inside DIF bundle.
@@ -1,6 +1,11 @@
-use anyhow::{bail, Result};
-use chrono::{DateTime, TimeZone, Utc};
-use clap::{Arg, ArgAction, Command};
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use log::info;
+
+use crate::api::MAX_PAGE_SIZE;
+use crate::utils::file_upload::normalize_url_prefix;
+use crate::utils::text_encoding::parse_encoding;
 
 fn validate_org(v: &str) -> Result<String, String> {
     if v.contains('/') || v == "." || v == ".." || v.contains(' ') {
@@ -65,6 +70,15 @@ pub fn validate_distribution(v: &str) -> Result<String, String> {
     }
 }
 
+pub fn validate_proxy_url(v: &str) -> Result<String, String> {
+    const SCHEMES: &[&str] = &["http://", "https://", "socks5://", "socks5h://"];
+    if SCHEMES.iter().any(|scheme| v.starts_with(scheme)) {
+        Ok(v.to_owned())
+    } else {
+        Err("Invalid proxy URL. Supported schemes are http, https, socks5 and socks5h.".to_string())
+    }
+}
+
 pub fn get_timestamp(value: &str) -> Result<DateTime<Utc>> {
     if let Ok(int) = value.parse::<i64>() {
         Ok(Utc.timestamp_opt(int, 0).single().unwrap())
@@ -77,11 +91,39 @@ pub fn get_timestamp(value: &str) -> Result<DateTime<Utc>> {
     }
 }
 
+/// Parses a timestamp as accepted by `get_timestamp`, or a duration such as
+/// `12h` or `7d` that is subtracted from the current time.
+pub fn get_timestamp_or_duration(value: &str) -> Result<DateTime<Utc>> {
+    if let Some(duration) = parse_duration(value) {
+        return Ok(Utc::now() - duration);
+    }
+    get_timestamp(value).map_err(|_| {
+        anyhow!("Not in valid format. ISO 8601 date or duration (e.g. 12h or 7d) expected.")
+    })
+}
+
+fn parse_duration(value: &str) -> Option<Duration> {
+    let unit = value.chars().last()?;
+    let amount = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        's' => Duration::try_seconds(amount),
+        'm' => Duration::try_minutes(amount),
+        'h' => Duration::try_hours(amount),
+        'd' => Duration::try_days(amount),
+        'w' => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
 pub trait ArgExt: Sized {
     fn org_arg(self) -> Self;
     fn project_arg(self, multiple: bool) -> Self;
     fn release_arg(self) -> Self;
     fn version_arg(self, global: bool) -> Self;
+    fn path_list_args(self) -> Self;
+    fn input_encoding_arg(self) -> Self;
+    fn batch_size_arg(self) -> Self;
+    fn chunk_upload_args(self) -> Self;
 }
 
 impl<'a: 'b, 'b> ArgExt for Command {
@@ -138,4 +180,123 @@ impl<'a: 'b, 'b> ArgExt for Command {
                 .help("The version of the release"),
         )
     }
+
+    fn path_list_args(self) -> Command {
+        self.arg(
+            Arg::new("stdin_paths")
+                .long("stdin-paths")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("paths_from")
+                .help(
+                    "Read the list of files to upload from stdin instead of \
+                    searching the given paths.  Entries are separated by newlines.",
+                ),
+        )
+        .arg(
+            Arg::new("paths_from")
+                .long("paths-from")
+                .value_name("FILE")
+                .help("Read the list of files to upload from the given file."),
+        )
+        .arg(
+            Arg::new("null")
+                .long("null")
+                .short('0')
+                .action(ArgAction::SetTrue)
+                .help("Entries in the file list are separated by NUL characters."),
+        )
+        .arg(
+            Arg::new("allow_missing")
+                .long("allow-missing")
+                .action(ArgAction::SetTrue)
+                .help("Skip files from the file list that do not exist instead of failing."),
+        )
+    }
+
+    fn input_encoding_arg(self) -> Command {
+        self.arg(
+            Arg::new("input_encoding")
+                .long("input-encoding")
+                .value_name("ENCODING")
+                .value_parser(parse_encoding)
+                .help(
+                    "The encoding of the input files, for example `latin1` or \
+                    `utf-16`.  Inputs are transcoded to UTF-8 before processing.  \
+                    UTF-16 with a byte order mark is detected automatically.  \
+                    Defaults to UTF-8.",
+                ),
+        )
+    }
+    fn batch_size_arg(self) -> Command {
+        self.arg(
+            Arg::new("batch_size")
+                .long("batch-size")
+                .value_name("N")
+                .value_parser(clap::builder::RangedU64ValueParser::<usize>::new().range(1..))
+                .help(
+                    "The number of items to request per page.  Larger pages need \
+                    fewer requests, smaller pages return the first results sooner.  \
+                    Values above the server maximum of 100 are clamped.",
+                ),
+        )
+    }
+
+    fn chunk_upload_args(self) -> Command {
+        self.arg(
+            Arg::new("upload_max_retries")
+                .value_name("N")
+                .long("upload-max-retries")
+                .value_parser(value_parser!(u32))
+                .help(
+                    "How often requests that transfer files, such as chunk uploads, are \
+                         retried after server errors.  Defaults to the retry count of other \
+                         requests [default: 5].  Can also be set with \
+                         SENTRY_HTTP_UPLOAD_MAX_RETRIES.",
+                ),
+        )
+        .arg(
+            Arg::new("assemble_poll_interval")
+                .value_name("SECONDS")
+                .long("assemble-poll-interval")
+                .value_parser(value_parser!(u64).range(1..))
+                .help(
+                    "How long to wait between requests that check whether the server has \
+                         processed uploaded files [default: 1].  Can also be set with \
+                         SENTRY_HTTP_ASSEMBLE_POLL_INTERVAL.",
+                ),
+        )
+        .arg(
+            Arg::new("assemble_max_attempts")
+                .value_name("N")
+                .long("assemble-max-attempts")
+                .value_parser(value_parser!(u32).range(1..))
+                .help(
+                    "Send at most N requests that check whether the server has processed \
+                         uploaded files.  By default, they are sent until processing is done \
+                         or the upload waited for too long.  Can also be set with \
+                         SENTRY_HTTP_ASSEMBLE_MAX_ATTEMPTS.",
+                ),
+        )
+    }
+}
+
+/// Returns the normalized `--url-prefix`, which fails the command if it looks
+/// suspicious and `--strict-url-prefix` is set.
+pub fn get_url_prefix(matches: &ArgMatches) -> Result<Option<String>> {
+    matches
+        .get_one::<String>("url_prefix")
+        .map(|prefix| normalize_url_prefix(prefix, matches.get_flag("strict_url_prefix")))
+        .transpose()
+}
+
+/// Returns the page size passed with `--batch-size`, clamped to the maximum
+/// the server accepts.
+pub fn get_batch_size(matches: &ArgMatches) -> Option<usize> {
+    let requested = *matches.get_one::<usize>("batch_size")?;
+    let batch_size = requested.min(MAX_PAGE_SIZE);
+    if batch_size < requested {
+        info!("Batch size {requested} exceeds the server maximum, using {batch_size}");
+    }
+    info!("Requesting {batch_size} items per page");
+    Some(batch_size)
 }
@@ -8,18 +8,105 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
-use log::info;
+use anyhow::{bail, Result};
+use log::{debug, info};
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 use sha1_smol::Digest;
 
-use crate::api::{Api, ChunkUploadOptions};
+use crate::api::{Api, ChunkCompression, ChunkUploadCapability, ChunkUploadOptions};
+use crate::config::Config;
 use crate::utils::progress::{ProgressBar, ProgressBarMode, ProgressStyle};
 
-/// Timeout for polling all assemble endpoints.
-pub const ASSEMBLE_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+/// How assemble endpoints are polled until the server has processed the
+/// uploaded files, see `--assemble-poll-interval` and `--assemble-max-attempts`.
+#[derive(Clone, Copy, Debug)]
+pub struct AssemblePolling {
+    pub interval: Duration,
+    /// The maximum number of assemble requests, if limited.
+    pub max_attempts: Option<u32>,
+}
+
+impl AssemblePolling {
+    pub fn from_config(config: &Config) -> Self {
+        let polling = AssemblePolling {
+            interval: config.get_assemble_poll_interval(),
+            max_attempts: config.get_assemble_max_attempts(),
+        };
+        debug!(
+            "polling assemble requests every {}s, at most {} times",
+            polling.interval.as_secs(),
+            polling
+                .max_attempts
+                .map_or_else(|| "unlimited".to_owned(), |n| n.to_string())
+        );
+        polling
+    }
+
+    /// Returns whether another assemble request may be sent after `attempts`
+    /// requests.
+    pub fn may_poll(&self, attempts: u32) -> bool {
+        self.max_attempts.map_or(true, |max| attempts < max)
+    }
+}
+
+/// The protocol used for uploads, see `--upload-protocol`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UploadProtocol {
+    /// Use chunked uploads if the server supports them.
+    #[default]
+    Auto,
+    /// Always use chunked uploads.
+    Chunked,
+    /// Never use chunked uploads.
+    Legacy,
+}
+
+impl UploadProtocol {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(UploadProtocol::Auto),
+            "chunked" => Some(UploadProtocol::Chunked),
+            "legacy" => Some(UploadProtocol::Legacy),
+            _ => None,
+        }
+    }
+}
+
+/// Decides whether files requiring `capability` are uploaded in chunks,
+/// given the server's chunk upload options.  Fails if chunked uploads were
+/// forced but the server does not support them.
+pub fn use_chunked_upload(
+    chunk_options: Option<&ChunkUploadOptions>,
+    capability: ChunkUploadCapability,
+) -> Result<bool> {
+    let supported = chunk_options.is_some_and(|options| options.supports(capability));
+    match Config::current().get_upload_protocol() {
+        UploadProtocol::Legacy => {
+            info!("using legacy upload, forced by --upload-protocol");
+            Ok(false)
+        }
+        UploadProtocol::Chunked if supported => {
+            info!("using chunked upload, forced by --upload-protocol");
+            Ok(true)
+        }
+        UploadProtocol::Chunked => bail!(
+            "Chunked upload was forced with --upload-protocol, but the server does not \
+             support chunked uploads of {capability:?}."
+        ),
+        UploadProtocol::Auto if supported => {
+            info!("using chunked upload, the server supports chunked uploads of {capability:?}");
+            Ok(true)
+        }
+        UploadProtocol::Auto => {
+            info!(
+                "using legacy upload, the server does not support chunked uploads of {capability:?}"
+            );
+            Ok(false)
+        }
+    }
+}
 
 /// A trait required by `BatchedSliceExt` to determine the logical size of a
 /// batch. Semantics and unit of the size depend on the batching use case,
@@ -163,27 +250,24 @@ pub fn upload_chunks(
     chunk_options: &ChunkUploadOptions,
     progress_style: ProgressStyle,
 ) -> Result<()> {
-    let total_bytes = chunks.iter().map(|&Chunk((_, data))| data.len()).sum();
+    let total_bytes = chunks
+        .iter()
+        .map(|&Chunk((_, data))| data.len() as u64)
+        .sum();
 
     // Chunks are uploaded in batches, but the progress bar is shared between
     // multiple requests to simulate one continuous upload to the user. Since we
     // have to embed the progress bar into a ProgressBarMode and move it into
     // `Api::upload_chunks`, the progress bar is created in an Arc.
-    let pb = Arc::new(ProgressBar::new(total_bytes));
+    let pb = Arc::new(ProgressBar::new_bytes(total_bytes));
     pb.set_style(progress_style);
 
-    // Select the best available compression mechanism. We assume that every
-    // compression algorithm has been implemented for uploading, except `Other`
-    // which is used for unknown compression algorithms. In case the server
-    // does not support compression, we fall back to `Uncompressed`.
-    let compression = chunk_options
-        .compression
-        .iter()
-        .max()
-        .cloned()
-        .unwrap_or_default();
-
+    let compression = select_compression(chunk_options);
     info!("using '{}' compression for chunk upload", compression);
+    debug!(
+        "retrying chunk uploads at most {} times",
+        Config::current().get_upload_max_retries()?
+    );
 
     // The upload is executed in parallel batches. Each batch aggregates objects
     // until it exceeds the maximum size configured in ChunkUploadOptions. We
@@ -191,9 +275,12 @@ pub fn upload_chunks(
     // occurs, all subsequent requests will be cancelled and the error returned.
     // Otherwise, the after every successful update, the overall progress is
     // updated and rendered.
-    let batches: Vec<_> = chunks
-        .batches(chunk_options.max_size, chunk_options.max_chunks)
-        .collect();
+    let max_chunks = max_chunks_per_request(chunk_options);
+    info!(
+        "uploading at most {} chunks and {} bytes per request",
+        max_chunks, chunk_options.max_size
+    );
+    let batches: Vec<_> = chunks.batches(chunk_options.max_size, max_chunks).collect();
 
     // We count the progress of each batch separately to avoid synchronization
     // issues. For a more consistent progress bar in repeated uploads, we also
@@ -219,3 +306,51 @@ pub fn upload_chunks(
 
     Ok(())
 }
+
+/// Returns the chunks that `upload_chunks` would send with its first request.
+pub fn first_batch<'c, 'data>(
+    chunks: &'c [Chunk<'data>],
+    chunk_options: &ChunkUploadOptions,
+) -> &'c [Chunk<'data>] {
+    Batches::new(
+        chunks,
+        chunk_options.max_size,
+        max_chunks_per_request(chunk_options),
+    )
+    .next()
+    .map_or(&[], |(batch, _)| batch)
+}
+
+/// Uploads a single batch of chunks with one request and without a progress
+/// bar. The batch must fit into the limits of `chunk_options`, see
+/// `first_batch`.
+pub fn upload_chunk_batch(chunks: &[Chunk<'_>], chunk_options: &ChunkUploadOptions) -> Result<()> {
+    Api::current().upload_chunks(
+        &chunk_options.url,
+        chunks,
+        ProgressBarMode::Disabled,
+        select_compression(chunk_options),
+    )?;
+    Ok(())
+}
+
+/// Selects the best available compression mechanism. We assume that every
+/// compression algorithm has been implemented for uploading, except `Other`
+/// which is used for unknown compression algorithms. In case the server
+/// does not support compression, we fall back to `Uncompressed`.
+fn select_compression(chunk_options: &ChunkUploadOptions) -> ChunkCompression {
+    chunk_options
+        .compression
+        .iter()
+        .max()
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Returns the maximum number of chunks sent with a single request.
+fn max_chunks_per_request(chunk_options: &ChunkUploadOptions) -> u64 {
+    match Config::current().get_max_chunk_batch() {
+        Some(max_chunk_batch) => max_chunk_batch.min(chunk_options.max_chunks),
+        None => chunk_options.max_chunks,
+    }
+}
@@ -132,6 +132,19 @@ impl ObjectDifFeatures {
     fn has_some(self) -> bool {
         self.debug || self.symtab || self.unwind || self.sources
     }
+
+    /// Returns the names of all included features.
+    pub fn names(self) -> Vec<&'static str> {
+        [
+            (self.symtab, "symtab"),
+            (self.debug, "debug"),
+            (self.unwind, "unwind"),
+            (self.sources, "sources"),
+        ]
+        .into_iter()
+        .filter_map(|(included, name)| included.then_some(name))
+        .collect()
+    }
 }
 
 impl Default for ObjectDifFeatures {
@@ -142,31 +155,39 @@ impl Default for ObjectDifFeatures {
 
 impl fmt::Display for ObjectDifFeatures {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut written = false;
-
-        macro_rules! append {
-            ($condition:expr, $feature:literal) => {
-                if $condition {
-                    if written {
-                        write!(f, ", ")?;
-                    }
-                    write!(f, $feature)?;
-                    written = true;
-                }
-            };
+        let names = self.names();
+        if names.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", names.join(", "))
         }
+    }
+}
 
-        append!(self.symtab, "symtab");
-        append!(self.debug, "debug");
-        append!(self.unwind, "unwind");
-        append!(self.sources, "sources");
-
-        if !written {
-            write!(f, "none")?;
-        }
+/// Returns whether the format of an object can describe inlined functions.
+/// Managed formats such as Portable PDBs have no inline frames.
+pub fn supports_inline_info(object: &Object) -> bool {
+    object.has_debug_info()
+        && matches!(
+            object.file_format(),
+            FileFormat::Breakpad
+                | FileFormat::Elf
+                | FileFormat::MachO
+                | FileFormat::Pdb
+                | FileFormat::Wasm
+        )
+}
 
-        Ok(())
-    }
+/// Checks whether the debug information of an object describes inlined
+/// functions.  Without it, inline frames cannot be symbolicated.
+pub fn has_inline_info(object: &Object) -> bool {
+    object.has_debug_info()
+        && object.debug_session().is_ok_and(|session| {
+            session
+                .functions()
+                .filter_map(Result::ok)
+                .any(|function| !function.inlinees.is_empty())
+        })
 }
 
 pub struct SelfProguard<'a>(ProguardMapping<'a>);
@@ -374,6 +395,29 @@ impl<'a> DifFile<'a> {
         }
     }
 
+    /// Returns whether any object can describe inlined functions.
+    pub fn supports_inline_info(&self) -> bool {
+        match self {
+            DifFile::Archive(archive) => archive
+                .get()
+                .objects()
+                .filter_map(Result::ok)
+                .any(|object| supports_inline_info(&object)),
+            DifFile::Proguard(..) => false,
+        }
+    }
+
+    pub fn has_inline_info(&self) -> bool {
+        match self {
+            DifFile::Archive(archive) => archive
+                .get()
+                .objects()
+                .filter_map(Result::ok)
+                .any(|object| has_inline_info(&object)),
+            DifFile::Proguard(..) => false,
+        }
+    }
+
     pub fn is_usable(&self) -> bool {
         match self {
             DifFile::Archive(_) => self.has_ids() && self.features().has_some(),
@@ -416,10 +460,11 @@ impl Serialize for DifFile<'_> {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("DifFile", 6)?;
+        let mut state = serializer.serialize_struct("DifFile", 7)?;
         state.serialize_field("type", &self.ty())?;
         state.serialize_field("variants", &self.variants())?;
         state.serialize_field("features", &self.features().to_string())?;
+        state.serialize_field("has_inline_info", &self.has_inline_info())?;
         state.serialize_field("is_usable", &self.is_usable())?;
         state.serialize_field("problem", &self.get_problem())?;
         state.serialize_field("note", &self.get_note())?;
@@ -9,40 +9,52 @@ use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Seek, Write};
 use std::iter::IntoIterator;
 use std::mem::transmute;
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::path::{Component, Path, PathBuf};
 use std::process::Command;
 use std::slice::{Chunks, Iter};
 use std::str;
+use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use anyhow::{bail, format_err, Error, Result};
+use anyhow::{bail, format_err, Context, Error, Result};
 use console::style;
 use indicatif::HumanBytes;
-use log::{debug, info, warn};
+use log::{debug, info, log_enabled, warn, Level};
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use serde::Serialize;
 use sha1_smol::Digest;
 use symbolic::common::{Arch, AsSelf, ByteView, DebugId, SelfCell, Uuid};
 use symbolic::debuginfo::macho::{BcSymbolMap, UuidMapping};
 use symbolic::debuginfo::pe::PeObject;
-use symbolic::debuginfo::sourcebundle::{SourceBundleWriter, SourceFileDescriptor};
+use symbolic::debuginfo::sourcebundle::{
+    SourceBundleErrorKind, SourceBundleWriter, SourceFileDescriptor, SourceFileInfo, SourceFileType,
+};
 use symbolic::debuginfo::{Archive, FileEntry, FileFormat, Object};
 use symbolic::il2cpp::ObjectLineMapping;
 use walkdir::WalkDir;
 use which::which;
 use zip::result::ZipError;
-use zip::{write::FileOptions, ZipArchive, ZipWriter};
+use zip::{ZipArchive, ZipWriter};
 
 use crate::api::{
-    Api, ChunkUploadCapability, ChunkUploadOptions, ChunkedDifRequest, ChunkedFileState,
+    Api, ApiError, AssembleDifsRequest, AssembleDifsResponse, ChunkUploadCapability,
+    ChunkUploadOptions, ChunkedDifRequest, ChunkedDifResponse, ChunkedFileState,
 };
 use crate::config::Config;
 use crate::constants::{DEFAULT_MAX_DIF_SIZE, DEFAULT_MAX_WAIT};
 use crate::utils::chunks::{
-    upload_chunks, BatchedSliceExt, Chunk, ItemSize, ASSEMBLE_POLL_INTERVAL,
+    upload_chunks, use_chunked_upload, AssemblePolling, BatchedSliceExt, Chunk, ItemSize,
+};
+use crate::utils::dif::{has_inline_info, supports_inline_info, ObjectDifFeatures};
+use crate::utils::fs::{
+    get_sha1_checksum, get_sha1_checksums, zip_file_options, TempDir, TempFile,
 };
-use crate::utils::dif::ObjectDifFeatures;
-use crate::utils::fs::{get_sha1_checksum, get_sha1_checksums, TempDir, TempFile};
+use crate::utils::logging::quiet_println;
+use crate::utils::metrics_summary;
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 use crate::utils::ui::{copy_with_progress, make_byte_progress_bar};
 
@@ -52,6 +64,35 @@ pub use crate::api::DebugInfoFile;
 /// Fallback maximum number of chunks in a batch for the legacy upload.
 static MAX_CHUNKS: u64 = 64;
 
+/// Maximum number of DIFs polled with a single assemble request.
+const ASSEMBLE_BATCH_SIZE: usize = 64;
+
+/// How to handle several files with the same debug id in one upload.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Only upload the file with debug information, or the first if none has.
+    PreferDebug,
+    /// Only upload the file found first.
+    PreferFirst,
+    /// Upload all files, since companion files share debug ids.
+    #[default]
+    UploadBoth,
+    /// Fail the upload.
+    Error,
+}
+
+impl ConflictPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "prefer-debug" => Some(ConflictPolicy::PreferDebug),
+            "prefer-first" => Some(ConflictPolicy::PreferFirst),
+            "upload-both" => Some(ConflictPolicy::UploadBoth),
+            "error" => Some(ConflictPolicy::Error),
+            _ => None,
+        }
+    }
+}
+
 /// An iterator over chunks of data in a `ChunkedDifMatch` object.
 ///
 /// This struct is returned by `ChunkedDifMatch::chunks`.
@@ -60,6 +101,15 @@ struct DifChunks<'a> {
     iter: Chunks<'a, u8>,
 }
 
+impl<'a> DifChunks<'a> {
+    fn new(data: &'a [u8], checksums: &'a [Digest], chunk_size: u64) -> Self {
+        DifChunks {
+            checksums: checksums.iter(),
+            iter: data.chunks(chunk_size as usize),
+        }
+    }
+}
+
 impl<'a> Iterator for DifChunks<'a> {
     type Item = Chunk<'a>;
 
@@ -112,6 +162,8 @@ struct DifMatch<'data> {
     name: String,
     debug_id: Option<DebugId>,
     attachments: Option<BTreeMap<String, ByteView<'static>>>,
+    /// The file this DIF was read from, unless it was extracted or generated.
+    local_path: Option<PathBuf>,
 }
 
 impl<'data> DifMatch<'data> {
@@ -130,6 +182,7 @@ impl<'data> DifMatch<'data> {
             name: name.into(),
             debug_id,
             attachments: None,
+            local_path: None,
         })
     }
 
@@ -150,6 +203,7 @@ impl<'data> DifMatch<'data> {
             name: name.into(),
             debug_id,
             attachments: None,
+            local_path: None,
         })
     }
     /// Creates a [`DifMatch`] from a `.bcsymbolmap` file.
@@ -167,6 +221,7 @@ impl<'data> DifMatch<'data> {
             name,
             debug_id: Some(uuid),
             attachments: None,
+            local_path: None,
         })
     }
 
@@ -181,6 +236,7 @@ impl<'data> DifMatch<'data> {
             name,
             debug_id: Some(uuid),
             attachments: None,
+            local_path: None,
         })
     }
 
@@ -333,11 +389,21 @@ impl<'data> ChunkedDifMatch<'data> {
     /// checksums for every chunk as well as the entire DIF.
     pub fn from(inner: DifMatch<'data>, chunk_size: u64) -> Result<Self> {
         let (checksum, chunks) = get_sha1_checksums(inner.data(), chunk_size)?;
-        Ok(ChunkedDifMatch {
+        Ok(Self::with_checksums(inner, checksum, chunks, chunk_size))
+    }
+
+    /// Creates a `ChunkedDifMatch` from checksums that have already been computed.
+    fn with_checksums(
+        inner: DifMatch<'data>,
+        checksum: Digest,
+        chunks: Vec<Digest>,
+        chunk_size: u64,
+    ) -> Self {
+        ChunkedDifMatch {
             inner: HashedDifMatch { inner, checksum },
             chunks,
             chunk_size,
-        })
+        }
     }
 
     /// Returns an iterator over all chunk checksums.
@@ -536,7 +602,8 @@ where
 /// nested ZIPs.
 ///
 /// To avoid unnecessary file operations, the file extension is already checked
-/// for every entry before opening it.
+/// for every entry before opening it. Directories matching
+/// `DifUpload::exclude_dirs` are pruned without being traversed.
 fn walk_difs_directory<F, P>(location: P, options: &DifUpload, mut func: F) -> Result<()>
 where
     P: AsRef<Path>,
@@ -550,9 +617,17 @@ where
     };
 
     debug!("searching location {}", location.display());
+    let mut pruned_dirs = 0;
     for entry in WalkDir::new(location)
         .follow_links(true)
         .into_iter()
+        .filter_entry(|entry| {
+            let excluded = options.is_excluded_dir(entry);
+            if excluded {
+                pruned_dirs += 1;
+            }
+            !excluded
+        })
         .filter_map(Result::ok)
     {
         if !entry.metadata()?.is_file() {
@@ -601,6 +676,7 @@ where
         func(DifSource::FileSystem(path), name, buffer)?;
     }
 
+    debug!("pruned {} excluded directories", pruned_dirs);
     debug!("finished location {}", directory.display());
     Ok(())
 }
@@ -723,31 +799,106 @@ fn search_difs(options: &DifUpload) -> Result<Vec<DifMatch<'static>>> {
     }
 
     pb.finish_and_clear();
+    let collected = resolve_conflicts(collected, options.on_conflict)?;
+
+    let count_with_sources = collected
+        .iter()
+        .filter(|dif| match dif.object() {
+            Some(object) => object.has_sources(),
+            None => false,
+        })
+        .count();
 
-    print!(
-        "{} Found {} debug information {}",
+    quiet_println!(
+        "{} Found {} debug information {}{}",
         style(">").dim(),
         style(collected.len()).yellow(),
         match collected.len() {
             1 => "file",
             _ => "files",
+        },
+        match count_with_sources {
+            0 => String::new(),
+            _ => format!(" ({count_with_sources} with embedded sources)"),
         }
     );
 
-    let count_with_sources = collected
-        .iter()
-        .filter(|dif| match dif.object() {
-            Some(object) => object.has_sources(),
-            None => false,
-        })
-        .count();
+    Ok(collected)
+}
 
-    match count_with_sources {
-        0 => println!(),
-        _ => println!(" ({count_with_sources} with embedded sources)"),
+/// Reports object files that share a debug id and removes the ones that should
+/// not be uploaded according to the conflict policy.
+fn resolve_conflicts(
+    difs: Vec<DifMatch<'static>>,
+    policy: ConflictPolicy,
+) -> Result<Vec<DifMatch<'static>>> {
+    let mut by_id: BTreeMap<DebugId, Vec<usize>> = BTreeMap::new();
+    for (index, dif) in difs.iter().enumerate() {
+        if let Some(object) = dif.object() {
+            let debug_id = dif.debug_id.unwrap_or_else(|| object.debug_id());
+            by_id.entry(debug_id).or_default().push(index);
+        }
+    }
+    by_id.retain(|_, indexes| indexes.len() > 1);
+    if by_id.is_empty() {
+        return Ok(difs);
     }
 
-    Ok(collected)
+    quiet_println!(
+        "{} Found {} debug {} in more than one file",
+        style(">").dim(),
+        style(by_id.len()).yellow(),
+        match by_id.len() {
+            1 => "id",
+            _ => "ids",
+        }
+    );
+
+    let mut skipped = BTreeSet::new();
+    for (debug_id, indexes) in &by_id {
+        let keep = match policy {
+            ConflictPolicy::UploadBoth | ConflictPolicy::Error => None,
+            ConflictPolicy::PreferFirst => Some(indexes[0]),
+            ConflictPolicy::PreferDebug => Some(
+                indexes
+                    .iter()
+                    .copied()
+                    .find(|&index| difs[index].object().is_some_and(|o| o.has_debug_info()))
+                    .unwrap_or(indexes[0]),
+            ),
+        };
+        quiet_println!("  {}", style(debug_id).dim());
+        for &index in indexes {
+            let resolution = match keep {
+                _ if policy == ConflictPolicy::Error => "conflict",
+                Some(keep) if keep != index => {
+                    skipped.insert(index);
+                    "skipped"
+                }
+                Some(_) | None => "uploaded",
+            };
+            let features = difs[index]
+                .object()
+                .map_or_else(ObjectDifFeatures::none, |object| ObjectDifFeatures {
+                    symtab: object.has_symbols(),
+                    debug: object.has_debug_info(),
+                    unwind: object.has_unwind_info(),
+                    sources: object.has_sources(),
+                });
+            quiet_println!("    {} ({features}): {resolution}", difs[index].path());
+        }
+    }
+
+    if policy == ConflictPolicy::Error {
+        bail!("Several files have the same debug id. Use --on-conflict to choose which to upload.");
+    }
+
+    Ok(difs
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !skipped.contains(index))
+        .map(|(_, dif)| dif)
+        .collect())
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -828,6 +979,10 @@ fn collect_object_dif<'a>(
     age_overrides: &mut BTreeMap<Uuid, u32>,
 ) -> Vec<DifMatch<'a>> {
     let mut collected = Vec::with_capacity(2);
+    let local_path = match source {
+        DifSource::FileSystem(path) => Some(path.to_owned()),
+        DifSource::Zip(..) => None,
+    };
 
     // Try to parse a potential object file. If this is not possible,
     // then we're not dealing with an object file, thus silently
@@ -880,7 +1035,9 @@ fn collect_object_dif<'a>(
 
         // If this is a PE file with an embedded Portable PDB, we extract and process the PPDB separately.
         if let Object::Pe(pe) = &object {
-            if let Ok(Some(ppdb_dif)) = extract_embedded_ppdb(pe, name.as_str()) {
+            if let Ok(Some(mut ppdb_dif)) = extract_embedded_ppdb(pe, name.as_str()) {
+                // The PE file is kept if its embedded PPDB fails to upload.
+                ppdb_dif.local_path = local_path.clone();
                 if options.validate_dif(&ppdb_dif) {
                     collected.push(ppdb_dif);
                 }
@@ -920,6 +1077,7 @@ fn collect_object_dif<'a>(
             name: name.clone(),
             debug_id: Some(id),
             attachments,
+            local_path: local_path.clone(),
         };
 
         // Skip this file if we don't want to process it.
@@ -941,7 +1099,7 @@ fn collect_object_dif<'a>(
 /// incur significant I/O for larger debug files.
 fn resolve_hidden_symbols<'a>(dif: DifMatch<'a>, symbol_map: &Path) -> Result<DifMatch<'a>> {
     if dif.attachments.is_none() {
-        println!(
+        quiet_println!(
             "{} {}: Could not locate UUID mapping for {}",
             style(">").dim(),
             style("Warning").red(),
@@ -1020,7 +1178,7 @@ where
     }
 
     pb.finish_and_clear();
-    println!(
+    quiet_println!(
         "{} Prepared debug information {} for upload",
         style(">").dim(),
         match calculated.len() {
@@ -1051,7 +1209,7 @@ fn process_symbol_maps<'a>(
     let symbol_map = match symbol_map {
         Some(path) => path,
         _ => {
-            println!(
+            quiet_println!(
                 "{} {}: Found {} symbol files with hidden symbols (need BCSymbolMaps)",
                 style(">").dim(),
                 style("Warning").red(),
@@ -1063,6 +1221,16 @@ fn process_symbol_maps<'a>(
         }
     };
 
+    // `dsymutil` only accepts a flat directory of `<UUID>.bcsymbolmap` files,
+    // so nested layouts such as Xcode archives are flattened into a temp dir.
+    let flattened;
+    let symbol_map = if symbol_map.is_dir() {
+        flattened = flatten_symbol_maps(&with_hidden, &index_symbol_maps(symbol_map)?)?;
+        flattened.path()
+    } else {
+        symbol_map
+    };
+
     let len = with_hidden.len();
     let progress_style = ProgressStyle::default_bar().template(
         "{prefix:.dim} Resolving BCSymbolMaps... {msg:.dim}\
@@ -1080,7 +1248,7 @@ fn process_symbol_maps<'a>(
     }
 
     pb.finish_and_clear();
-    println!(
+    quiet_println!(
         "{} Resolved BCSymbolMaps for {} debug information {}",
         style(">").dim(),
         style(len).yellow(),
@@ -1093,6 +1261,93 @@ fn process_symbol_maps<'a>(
     Ok(without_hidden)
 }
 
+/// Recursively searches the given directory for `.bcsymbolmap` files and
+/// indexes them by their UUID.
+fn index_symbol_maps(dir: &Path) -> Result<BTreeMap<DebugId, PathBuf>> {
+    let mut index = BTreeMap::new();
+    for entry in WalkDir::new(dir).follow_links(true) {
+        let path = entry?.into_path();
+        if path.extension() != Some(OsStr::new("bcsymbolmap")) {
+            continue;
+        }
+
+        let uuid = path
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .and_then(|stem| stem.parse().ok());
+        match uuid {
+            Some(uuid) => {
+                index.insert(uuid, path);
+            }
+            None => warn!(
+                "Skipping BCSymbolMap with invalid filename: {}",
+                path.display()
+            ),
+        }
+    }
+
+    debug!("Indexed {} BCSymbolMaps in {}", index.len(), dir.display());
+    Ok(index)
+}
+
+/// Returns the UUIDs of the BCSymbolMaps required by the given dSYM, based
+/// on the UUID mappings attached to it.
+fn required_symbol_maps(dif: &DifMatch<'_>) -> Vec<DebugId> {
+    dif.attachments()
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, view)| {
+            let dsym_uuid = Path::new(name).file_stem()?.to_str()?.parse().ok()?;
+            UuidMapping::parse_plist(dsym_uuid, view)
+                .ok()
+                .map(|mapping| mapping.original_uuid())
+        })
+        .collect()
+}
+
+/// Links all BCSymbolMaps needed by the given dSYMs from the index into a
+/// flat temporary directory.  Reports dSYM UUIDs without a matching map and
+/// maps that do not belong to any dSYM.
+fn flatten_symbol_maps(
+    difs: &[DifMatch<'_>],
+    index: &BTreeMap<DebugId, PathBuf>,
+) -> Result<TempDir> {
+    let temp_dir = TempDir::create()?;
+    let mut used = BTreeSet::new();
+
+    for dif in difs {
+        for uuid in required_symbol_maps(dif) {
+            match index.get(&uuid) {
+                Some(path) => {
+                    if used.insert(uuid) {
+                        let target = temp_dir.path().join(path.file_name().unwrap());
+                        if fs::hard_link(path, &target).is_err() {
+                            fs::copy(path, &target)?;
+                        }
+                    }
+                }
+                None => quiet_println!(
+                    "{} {}: No BCSymbolMap found for {} ({})",
+                    style(">").dim(),
+                    style("Warning").red(),
+                    style(uuid).yellow(),
+                    dif.file_name()
+                ),
+            }
+        }
+    }
+
+    for uuid in index.keys().filter(|uuid| !used.contains(*uuid)) {
+        quiet_println!(
+            "{} BCSymbolMap {} does not belong to any dSYM",
+            style(">").dim(),
+            style(uuid).yellow()
+        );
+    }
+
+    Ok(temp_dir)
+}
+
 /// Checks whether the `PeObject` contains an embedded Portable PDB and extracts it as a separate  `DifMatch`.
 fn extract_embedded_ppdb<'a>(pe: &PeObject, pe_name: &str) -> Result<Option<DifMatch<'a>>> {
     if let Some(embedded_ppdb) = pe.embedded_ppdb()? {
@@ -1117,7 +1372,6 @@ pub fn filter_bad_sources(
     entry: &FileEntry,
     embedded_source: &Option<SourceFileDescriptor>,
 ) -> bool {
-    let max_size = Config::current().get_max_dif_item_size();
     let path = &entry.abs_path_str();
 
     // Ignore pch files.
@@ -1132,29 +1386,139 @@ pub fn filter_bad_sources(
     }
 
     // Ignore files larger than limit (defaults to `DEFAULT_MAX_DIF_ITEM_SIZE`).
-    if let Ok(meta) = fs::metadata(path) {
-        let item_size = meta.len();
-        if item_size > max_size {
+    if exceeds_max_item_size(path) {
+        return false;
+    }
+
+    // if a file metadata could not be read it will be skipped later.
+    debug!("Trying to add source file: {}", path);
+    true
+}
+
+/// Returns whether a source file is larger than the maximum item size and logs a warning if so.
+fn exceeds_max_item_size(path: &str) -> bool {
+    let max_size = Config::current().get_max_dif_item_size();
+    match fs::metadata(path) {
+        Ok(meta) if meta.len() > max_size => {
             warn!(
                 "Source exceeded maximum item size limit ({}). {}",
-                item_size, path
+                meta.len(),
+                path
             );
-            return false;
+            true
         }
+        _ => false,
     }
+}
 
-    // if a file metadata could not be read it will be skipped later.
-    debug!("Trying to add source file: {}", path);
-    true
+/// Returns the path of a source file within a source bundle, matching the paths written by
+/// `SourceBundleWriter::write_object`.
+fn source_bundle_path(path: &str) -> String {
+    path.split(['/', '\\'])
+        .filter(|component| !component.is_empty())
+        .map(|component| component.strip_suffix(':').unwrap_or(component))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns where a source file referenced by DWARF debug information can be read from disk.
+///
+/// With a `source_root`, the file is first looked up there by its path relative to the
+/// compilation directory.  Otherwise, or if it is not found there, the original path is used.
+fn resolve_dwarf_source(file: &FileEntry, source_root: Option<&Path>) -> Option<PathBuf> {
+    let path = PathBuf::from(file.abs_path_str());
+    if let Some(source_root) = source_root {
+        let compilation_dir = file.compilation_dir_str();
+        let relative = path.strip_prefix(&*compilation_dir).unwrap_or(&path);
+        let rebased = source_root.join(relative);
+        if rebased.is_file() {
+            return Some(rebased);
+        }
+    }
+    path.is_file().then_some(path)
+}
+
+/// Writes the source files referenced by the DWARF debug information of an ELF or Mach-O object
+/// into a source bundle.  Returns whether any source was written, along with the number of
+/// sources found and the number of sources referenced.
+///
+/// Sources are resolved with `resolve_dwarf_source`, but always stored under their original
+/// path so that they can be matched to the debug information.  Sources that cannot be found on
+/// disk or cannot be read are reported as warnings.
+fn write_dwarf_sources<W>(
+    mut writer: SourceBundleWriter<W>,
+    object: &Object,
+    object_name: &str,
+    source_root: Option<&Path>,
+) -> Result<(bool, usize, usize)>
+where
+    W: Write + Seek,
+{
+    writer.set_attribute("arch", object.arch().to_string());
+    writer.set_attribute("debug_id", object.debug_id().to_string());
+    writer.set_attribute("object_name", object_name);
+    if let Some(code_id) = object.code_id() {
+        writer.set_attribute("code_id", code_id.to_string());
+    }
+
+    let session = object.debug_session()?;
+    let mut handled = BTreeSet::new();
+    let mut found = 0;
+    let mut missing = 0;
+    for file in session.files() {
+        let file = file?;
+        let path = file.abs_path_str();
+        if (path.starts_with('<') && path.ends_with('>')) || !handled.insert(path.clone()) {
+            continue;
+        }
+        if !filter_bad_sources(&file, &session.source_by_path(&path)?) {
+            continue;
+        }
+
+        let Some(source_path) = resolve_dwarf_source(&file, source_root) else {
+            warn!("Source file not found: {}", path);
+            missing += 1;
+            continue;
+        };
+        let source_path_str = source_path.to_string_lossy();
+        if source_path_str != path && exceeds_max_item_size(&source_path_str) {
+            continue;
+        }
+
+        let mut info = SourceFileInfo::new();
+        info.set_ty(SourceFileType::Source);
+        info.set_path(path.clone());
+        let source = match File::open(&source_path) {
+            Ok(source) => source,
+            Err(err) => {
+                warn!("Failed to open source file {}: {}", source_path_str, err);
+                missing += 1;
+                continue;
+            }
+        };
+        match writer.add_file(source_bundle_path(&path), BufReader::new(source), info) {
+            Ok(()) => found += 1,
+            Err(err) if err.kind() == SourceBundleErrorKind::ReadFailed => {
+                info!("Skipped file {} due to: {}", path, err);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let written = !writer.is_empty();
+    writer.finish()?;
+    Ok((written, found, found + missing))
 }
 
 /// Creates a source bundle containing the source files referenced by the input DIFs.
 ///
 /// If `include_il2cpp_sources` is true, C# files referenced by il2cpp line mapping comments
-/// will also be included.
+/// will also be included.  Otherwise, sources referenced by DWARF debug information are
+/// resolved relative to `source_root`, see `write_dwarf_sources`.
 fn create_source_bundles<'a>(
     difs: &[DifMatch<'a>],
     include_il2cpp_sources: bool,
+    source_root: Option<&Path>,
 ) -> Result<Vec<DifMatch<'a>>> {
     let mut source_bundles = Vec::new();
 
@@ -1167,6 +1531,7 @@ fn create_source_bundles<'a>(
     pb.set_style(progress_style);
     pb.set_prefix(">");
 
+    let mut coverage = Vec::new();
     for dif in difs {
         let name = dif.path();
         pb.inc(1);
@@ -1185,9 +1550,17 @@ fn create_source_bundles<'a>(
         // Resolve source files from the object and write their contents into the archive. Skip to
         // upload this bundle if no source could be written. This can happen if there is no file or
         // line information in the object file, or if none of the files could be resolved.
-        let written = writer
-            .with_skipped_file_callback(|skipped_info| info!("{skipped_info}"))
-            .write_object_with_filter(object, dif.file_name(), filter_bad_sources)?;
+        let written = match object.file_format() {
+            FileFormat::Elf | FileFormat::MachO if !include_il2cpp_sources => {
+                let (written, found, total) =
+                    write_dwarf_sources(writer, object, dif.file_name(), source_root)?;
+                coverage.push((dif.file_name(), found, total));
+                written
+            }
+            _ => writer
+                .with_skipped_file_callback(|skipped_info| info!("{skipped_info}"))
+                .write_object_with_filter(object, dif.file_name(), filter_bad_sources)?,
+        };
         if !written {
             debug!("No sources found for {}", name);
             continue;
@@ -1198,7 +1571,16 @@ fn create_source_bundles<'a>(
 
     let len = source_bundles.len();
     pb.finish_and_clear();
-    println!(
+    for (object_name, found, total) in coverage {
+        quiet_println!(
+            "{} Found {} of {} source files referenced by {}",
+            style(">").dim(),
+            style(found).yellow(),
+            style(total).yellow(),
+            object_name
+        );
+    }
+    quiet_println!(
         "{} Resolved source code for {} debug information {}",
         style(">").dim(),
         style(len).yellow(),
@@ -1249,7 +1631,7 @@ fn create_il2cpp_mappings<'a>(difs: &[DifMatch<'a>]) -> Result<Vec<DifMatch<'a>>
 
     let len = line_mappings.len();
     pb.finish_and_clear();
-    println!(
+    quiet_println!(
         "{} Resolved il2cpp mappings for {} debug information {}",
         style(">").dim(),
         style(len).yellow(),
@@ -1270,6 +1652,7 @@ fn create_il2cpp_mappings<'a>(difs: &[DifMatch<'a>]) -> Result<Vec<DifMatch<'a>>
 fn try_assemble_difs<'data, 'm>(
     difs: &'m [ChunkedDifMatch<'data>],
     options: &DifUpload,
+    project: &str,
 ) -> Result<MissingDifsInfo<'data, 'm>> {
     let api = Api::current();
     let request = difs
@@ -1278,7 +1661,7 @@ fn try_assemble_difs<'data, 'm>(
         .collect();
     let response = api
         .authenticated()?
-        .assemble_difs(&options.org, &options.project, &request)?;
+        .assemble_difs(&options.org, project, &request)?;
 
     // We map all DIFs by their checksum, so we can access them faster when
     // iterating through the server response below. Since the caller will invoke
@@ -1298,47 +1681,237 @@ fn try_assemble_difs<'data, 'm>(
             .get(&checksum)
             .ok_or_else(|| format_err!("Server returned unexpected checksum"))?;
 
-        match file_response.state {
-            ChunkedFileState::Error => {
-                // One of the files could not be uploaded properly and resulted
-                // in an error. We include this file in the return value so that
-                // it shows up in the final report.
-                difs.push(chunked_match);
+        if let Some(missing_chunks) = missing_chunks(file_response, chunked_match.chunks()) {
+            difs.push(chunked_match);
+            chunks.extend(missing_chunks);
+        }
+    }
+
+    Ok((difs, chunks))
+}
+
+/// Evaluates the response of an initial assemble request for a single DIF.
+///
+/// Returns `None` if no further action is required for the DIF. Otherwise,
+/// returns its missing chunks, which need to be uploaded before the DIF is
+/// polled until it has been assembled.
+fn missing_chunks<'a>(
+    file_response: &ChunkedDifResponse,
+    chunks: DifChunks<'a>,
+) -> Option<Vec<Chunk<'a>>> {
+    match file_response.state {
+        ChunkedFileState::Error => {
+            // One of the files could not be uploaded properly and resulted
+            // in an error. We include this file in the return value so that
+            // it shows up in the final report.
+            Some(vec![])
+        }
+        ChunkedFileState::Assembling => {
+            // This file is currently assembling. The caller will have to poll this file later
+            // until it either resolves or errors.
+            Some(vec![])
+        }
+        ChunkedFileState::NotFound => {
+            // Assembling for one of the files has not started because some
+            // (or all) of its chunks have not been found. We report its
+            // missing chunks to the caller and then continue. The caller
+            // will have to call `try_assemble_difs` again after uploading
+            // them.
+            let (missing_chunks, present_chunks): (Vec<_>, Vec<_>) =
+                chunks.partition(|&Chunk((c, _))| file_response.missing_chunks.contains(&c));
+            metrics_summary::record_deduped_chunks(present_chunks.len());
+
+            // Usually every file that is NotFound should also contain a set
+            // of missing chunks. However, if we tried to upload an empty
+            // file or the server returns an invalid response, we need to
+            // make sure that this match is not included in the missing
+            // difs.
+            (!missing_chunks.is_empty()).then_some(missing_chunks)
+        }
+        _ => {
+            // This file has already finished. No action required anymore.
+            metrics_summary::record_deduped_chunks(chunks.count());
+            None
+        }
+    }
+}
+
+/// Hashes DIFs and uploads their missing chunks at the same time.
+///
+/// The DIFs are hashed on a separate thread. Once the hashed DIFs add up to
+/// enough data to keep all upload connections busy, the server is asked which
+/// of their chunks are missing, and these are uploaded while the remaining DIFs
+/// are still being hashed. Batches only depend on the sizes of the DIFs, so the
+/// requests are the same on every run.
+///
+/// Returns the chunked DIFs in their original order, along with the indices of
+/// the DIFs that need to be polled until they are assembled.
+fn upload_difs_pipelined<'data>(
+    difs: Vec<DifMatch<'data>>,
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+    project: &str,
+) -> Result<(Vec<ChunkedDifMatch<'data>>, Vec<usize>)> {
+    let chunk_size = chunk_options.chunk_size;
+    let batch_size = chunk_options
+        .max_size
+        .saturating_mul(chunk_options.concurrency.into());
+
+    let mut hashes = Vec::with_capacity(difs.len());
+    let mut pending = Vec::new();
+    let mut uploaded = false;
+
+    let data: Vec<_> = difs.iter().map(DifMatch::data).collect();
+    let (sender, receiver) = mpsc::sync_channel(chunk_options.concurrency.into());
+    thread::scope(|scope| -> Result<()> {
+        let hasher = scope.spawn(move || -> Result<()> {
+            for data in data {
+                // The receiver hangs up if an upload fails.
+                if sender.send(get_sha1_checksums(data, chunk_size)?).is_err() {
+                    break;
+                }
             }
-            ChunkedFileState::Assembling => {
-                // This file is currently assembling. The caller will have to poll this file later
-                // until it either resolves or errors.
-                difs.push(chunked_match);
+            Ok(())
+        });
+
+        let mut batch_start = 0;
+        let mut batch_bytes = 0;
+        for hash in receiver {
+            batch_bytes += difs[hashes.len()].size();
+            hashes.push(hash);
+            if batch_bytes >= batch_size {
+                let batch = batch_start..hashes.len();
+                uploaded |= upload_hashed_batch(
+                    &difs,
+                    &hashes,
+                    batch,
+                    options,
+                    chunk_options,
+                    project,
+                    &mut pending,
+                )?;
+                batch_start = hashes.len();
+                batch_bytes = 0;
             }
-            ChunkedFileState::NotFound => {
-                // Assembling for one of the files has not started because some
-                // (or all) of its chunks have not been found. We report its
-                // missing chunks to the caller and then continue. The caller
-                // will have to call `try_assemble_difs` again after uploading
-                // them.
-                let mut missing_chunks = chunked_match
-                    .chunks()
-                    .filter(|&Chunk((c, _))| file_response.missing_chunks.contains(&c))
-                    .peekable();
-
-                // Usually every file that is NotFound should also contain a set
-                // of missing chunks. However, if we tried to upload an empty
-                // file or the server returns an invalid response, we need to
-                // make sure that this match is not included in the missing
-                // difs.
-                if missing_chunks.peek().is_some() {
-                    difs.push(chunked_match);
-                }
+        }
 
-                chunks.extend(missing_chunks);
+        hasher
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+        quiet_println!(
+            "{} Prepared debug information {} for upload",
+            style(">").dim(),
+            match difs.len() {
+                1 => "file",
+                _ => "files",
             }
-            _ => {
-                // This file has already finished. No action required anymore.
+        );
+
+        if batch_start < hashes.len() {
+            let batch = batch_start..hashes.len();
+            uploaded |= upload_hashed_batch(
+                &difs,
+                &hashes,
+                batch,
+                options,
+                chunk_options,
+                project,
+                &mut pending,
+            )?;
+        }
+        Ok(())
+    })?;
+
+    if uploaded {
+        quiet_println!(
+            "{} Uploaded {} missing debug information {}",
+            style(">").dim(),
+            style(pending.len().to_string()).yellow(),
+            match pending.len() {
+                1 => "file",
+                _ => "files",
             }
+        );
+    }
+
+    let chunked = difs
+        .into_iter()
+        .zip(hashes)
+        .map(|(dif, (checksum, chunks))| {
+            ChunkedDifMatch::with_checksums(dif, checksum, chunks, chunk_size)
+        })
+        .collect();
+    Ok((chunked, pending))
+}
+
+/// Sends the initial assemble request for a batch of hashed DIFs and uploads
+/// their missing chunks. The indices of DIFs that need to be polled are added
+/// to `pending`. Returns whether any chunks were uploaded.
+fn upload_hashed_batch(
+    difs: &[DifMatch<'_>],
+    hashes: &[(Digest, Vec<Digest>)],
+    batch: Range<usize>,
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+    project: &str,
+    pending: &mut Vec<usize>,
+) -> Result<bool> {
+    let request = batch
+        .clone()
+        .map(|index| {
+            let (checksum, ref chunks) = hashes[index];
+            let dif = &difs[index];
+            (
+                checksum,
+                ChunkedDifRequest {
+                    name: dif.file_name(),
+                    debug_id: if options.pdbs_allowed {
+                        dif.debug_id
+                    } else {
+                        None
+                    },
+                    chunks,
+                },
+            )
+        })
+        .collect();
+    let response =
+        Api::current()
+            .authenticated()?
+            .assemble_difs(&options.org, project, &request)?;
+
+    let index_by_checksum: BTreeMap<_, _> = batch.map(|index| (hashes[index].0, index)).collect();
+    let mut batch_pending = Vec::new();
+    let mut chunks = Vec::new();
+    for (checksum, ref file_response) in response {
+        let index = *index_by_checksum
+            .get(&checksum)
+            .ok_or_else(|| format_err!("Server returned unexpected checksum"))?;
+        let dif_chunks = DifChunks::new(
+            difs[index].data(),
+            &hashes[index].1,
+            chunk_options.chunk_size,
+        );
+        if let Some(missing_chunks) = missing_chunks(file_response, dif_chunks) {
+            batch_pending.push(index);
+            chunks.extend(missing_chunks);
         }
     }
 
-    Ok((difs, chunks))
+    // The response is a map, so restore the original order of the DIFs.
+    batch_pending.sort_unstable();
+    pending.extend(batch_pending);
+
+    if chunks.is_empty() {
+        return Ok(false);
+    }
+    let progress_style = ProgressStyle::default_bar().template(&format!(
+        "{} Uploading missing debug information files...\
+         \n{{wide_bar}}  {{bytes}}/{{total_bytes}} ({{eta}})",
+        style(">").dim(),
+    ));
+    upload_chunks(&chunks, chunk_options, progress_style)?;
+    Ok(true)
 }
 
 /// Concurrently uploads chunks specified in `missing_info` in batches. The
@@ -1368,7 +1941,7 @@ fn upload_missing_chunks(
 
     upload_chunks(chunks, chunk_options, progress_style)?;
 
-    println!(
+    quiet_println!(
         "{} Uploaded {} missing debug information {}",
         style(">").dim(),
         style(difs.len().to_string()).yellow(),
@@ -1397,40 +1970,29 @@ fn render_detail(detail: &Option<String>, fallback: Option<&str>) {
 
     for line in string.lines() {
         if !line.is_empty() {
-            println!("        {}", style(line).dim());
+            quiet_println!("        {}", style(line).dim());
         }
     }
 }
 
-/// Polls the assemble endpoint until all DIFs have either completed or errored. Returns a list of
-/// `DebugInfoFile`s that have been created successfully and also prints a summary to the user.
-///
-/// This function assumes that all chunks have been uploaded successfully. If there are still
-/// missing chunks in the assemble response, this likely indicates a bug in the server.
-fn poll_dif_assemble(
-    difs: &[&ChunkedDifMatch<'_>],
+/// Polls the assemble endpoint for a single batch of DIFs until all of them have either completed
+/// or errored, or polling is given up.  Finished DIFs are counted on the shared progress bar.
+fn poll_assemble_batch(
+    request: &AssembleDifsRequest<'_>,
     options: &DifUpload,
-) -> Result<(Vec<DebugInfoFile>, bool)> {
-    let progress_style = ProgressStyle::default_bar().template(
-        "{prefix:.dim} Processing files...\
-         \n{wide_bar}  {pos}/{len}",
-    );
-
+    project: &str,
+    polling: &AssemblePolling,
+    assemble_start: Instant,
+    pb: &ProgressBar,
+) -> Result<AssembleDifsResponse> {
     let api = Api::current();
-    let pb = ProgressBar::new(difs.len());
-    pb.set_style(progress_style);
-    pb.set_prefix(">");
-
-    let assemble_start = Instant::now();
-
-    let request = difs
-        .iter()
-        .map(|d| d.to_assemble(options.pdbs_allowed))
-        .collect();
-    let response = loop {
-        let response =
-            api.authenticated()?
-                .assemble_difs(&options.org, &options.project, &request)?;
+    let mut attempts = 0;
+    let mut finished = 0;
+    loop {
+        let response = api
+            .authenticated()?
+            .assemble_difs(&options.org, project, request)?;
+        attempts += 1;
 
         let chunks_missing = response
             .values()
@@ -1447,11 +2009,11 @@ fn poll_dif_assemble(
         // that case, we return the potentially partial response from the server. This might
         // still contain a cached error.
         if !options.wait {
-            break response;
+            return Ok(response);
         }
 
-        if assemble_start.elapsed() > options.max_wait {
-            break response;
+        if assemble_start.elapsed() > options.max_wait || !polling.may_poll(attempts) {
+            return Ok(response);
         }
 
         let pending = response
@@ -1459,20 +2021,80 @@ fn poll_dif_assemble(
             .filter(|&(_, r)| r.state.is_pending())
             .count();
 
-        pb.set_position((difs.len() - pending) as u64);
+        let now_finished = request.len() - pending;
+        pb.inc(now_finished.saturating_sub(finished) as u64);
+        finished = finished.max(now_finished);
 
         if pending == 0 {
-            break response;
+            return Ok(response);
         }
 
-        thread::sleep(ASSEMBLE_POLL_INTERVAL);
-    };
+        thread::sleep(polling.interval);
+    }
+}
+
+/// Polls the assemble endpoint until all DIFs have either completed or errored. Returns a list of
+/// `DebugInfoFile`s that have been created successfully and also prints a summary to the user.
+///
+/// This function assumes that all chunks have been uploaded successfully. If there are still
+/// missing chunks in the assemble response, this likely indicates a bug in the server.
+fn poll_dif_assemble(
+    difs: &[&ChunkedDifMatch<'_>],
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+    project: &str,
+) -> Result<(Vec<DebugInfoFile>, bool)> {
+    let progress_style = ProgressStyle::default_bar().template(
+        "{prefix:.dim} Processing files...\
+         \n{wide_bar}  {pos}/{len}",
+    );
+
+    let pb = ProgressBar::new(difs.len());
+    pb.set_style(progress_style);
+    pb.set_prefix(">");
+
+    let assemble_start = Instant::now();
+
+    // Large uploads are polled in several batches at once, but at most as many as configured
+    // with `--max-concurrent-assembles`, so that the server is not flooded with requests.
+    let requests: Vec<AssembleDifsRequest<'_>> = difs
+        .chunks(ASSEMBLE_BATCH_SIZE)
+        .map(|batch| {
+            batch
+                .iter()
+                .map(|d| d.to_assemble(options.pdbs_allowed))
+                .collect()
+        })
+        .collect();
+    let config = Config::current();
+    let polling = AssemblePolling::from_config(&config);
+    let max_concurrent = config
+        .get_max_concurrent_assembles()?
+        .unwrap_or(chunk_options.concurrency.into());
+    info!(
+        "polling assemble requests in {} batches, at most {} at once",
+        requests.len(),
+        max_concurrent
+    );
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(max_concurrent as usize)
+        .build()?;
+    let responses = pool.install(|| {
+        requests
+            .par_iter()
+            .map(|request| {
+                poll_assemble_batch(request, options, project, &polling, assemble_start, &pb)
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+    let response: AssembleDifsResponse = responses.into_iter().flatten().collect();
 
     pb.finish_and_clear();
     if response.values().any(|r| r.state.is_pending()) {
-        println!("{} File upload complete:\n", style(">").dim());
+        quiet_println!("{} File upload complete:\n", style(">").dim());
     } else {
-        println!("{} File processing complete:\n", style(">").dim());
+        quiet_println!("{} File processing complete:\n", style(">").dim());
     }
 
     let (errors, mut successes): (Vec<_>, _) = response
@@ -1498,7 +2120,7 @@ fn poll_dif_assemble(
         if let Some(ref dif) = success.dif {
             // Files that have completed processing will contain a `dif` record
             // returned by the server. Use this to show detailed information.
-            println!(
+            quiet_println!(
                 "  {:>7} {} ({}; {}{})",
                 style("OK").green(),
                 style(&dif.id()).dim(),
@@ -1522,7 +2144,7 @@ fn poll_dif_assemble(
                 ParsedDif::Il2Cpp => String::from("il2cpp"),
             };
 
-            println!(
+            quiet_println!(
                 "  {:>8} {} ({}; {}{})",
                 style("UPLOADED").yellow(),
                 style(dif.debug_id.map(|id| id.to_string()).unwrap_or_default()).dim(),
@@ -1552,7 +2174,14 @@ fn poll_dif_assemble(
     }
     errored.sort_by_key(|x| x.0.file_name());
 
-    let has_errors = !errored.is_empty();
+    let mut has_errors = !errored.is_empty();
+    let is_partial = has_errors && !successes.is_empty();
+    let mut report = options.report.lock();
+    for &(checksum, _) in &successes {
+        if let Some(dif) = difs_by_checksum.get(&checksum) {
+            report.push(DifUploadReportFile::new(project, dif, "uploaded", None));
+        }
+    }
     for (dif, error) in errored {
         let fallback = match error.state {
             ChunkedFileState::Assembling => Some("The file is still processing and not ready yet"),
@@ -1560,8 +2189,19 @@ fn poll_dif_assemble(
             _ => Some("An unknown error occurred"),
         };
 
-        println!("  {:>7} {}", style("ERROR").red(), dif.file_name());
+        quiet_println!("  {:>7} {}", style("ERROR").red(), dif.file_name());
         render_detail(&error.detail, fallback);
+        let message = error.detail.clone().or(fallback.map(str::to_owned));
+        report.push(DifUploadReportFile::new(project, dif, "error", message));
+    }
+    drop(report);
+
+    if is_partial && !options.abort_on_partial_assemble {
+        warn!(
+            "Some debug information files did not process correctly. \
+             Pass --abort-on-partial-assemble to fail the upload in this case."
+        );
+        has_errors = false;
     }
 
     // Return only successful uploads
@@ -1571,17 +2211,248 @@ fn poll_dif_assemble(
     ))
 }
 
+/// Polls the DIFs that are not assembled yet, if there are any.
+fn finish_dif_assemble(
+    missing_difs: &[&ChunkedDifMatch<'_>],
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+    project: &str,
+) -> Result<(Vec<DebugInfoFile>, bool)> {
+    // Only if DIFs were missing, poll until assembling is complete
+    if !missing_difs.is_empty() {
+        return poll_dif_assemble(missing_difs, options, chunk_options, project);
+    }
+
+    quiet_println!(
+        "{} Nothing to upload, all files are on the server",
+        style(">").dim()
+    );
+    Ok(Default::default())
+}
+
+/// The report printed by `debug-files upload --json`.  It is a single document
+/// for all input paths and projects:
+///
+/// ```json
+/// {
+///   "files": [
+///     {
+///       "project": "wat-project",
+///       "path": "Sentry.Samples.Console.Basic.pdb",
+///       "debug_id": "0c1033f7-8ffa-4ba4-9a6e-e5c5b1a50a3e-1",
+///       "status": "uploaded"
+///     }
+///   ],
+///   "summary": {"files": 1, "uploaded": 1, "skipped": 0, "errors": 0}
+/// }
+/// ```
+///
+/// `debug_id` and `message` are omitted if the file has none.
+#[derive(Debug, Default, Serialize)]
+pub struct DifUploadReport {
+    /// The files of every project, sorted by project and path.
+    pub files: Vec<DifUploadReportFile>,
+    pub summary: DifUploadReportSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DifUploadReportFile {
+    pub project: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_id: Option<DebugId>,
+    /// `uploaded`, `skipped` if the file is already on the server, or `error`.
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip)]
+    checksum: Option<Digest>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DifUploadReportSummary {
+    pub files: usize,
+    pub uploaded: usize,
+    pub skipped: usize,
+    pub errors: usize,
+}
+
+impl DifUploadReportFile {
+    fn new(
+        project: &str,
+        dif: &ChunkedDifMatch<'_>,
+        status: &'static str,
+        message: Option<String>,
+    ) -> Self {
+        DifUploadReportFile {
+            project: project.to_owned(),
+            path: dif.path().to_owned(),
+            debug_id: dif.debug_id,
+            status,
+            message,
+            checksum: Some(dif.checksum),
+        }
+    }
+}
+
+impl DifUploadReport {
+    fn push(&mut self, file: DifUploadReportFile) {
+        self.summary.files += 1;
+        match file.status {
+            "uploaded" => self.summary.uploaded += 1,
+            "skipped" => self.summary.skipped += 1,
+            _ => self.summary.errors += 1,
+        }
+        self.files.push(file);
+    }
+
+    fn contains(&self, project: &str, checksum: Digest) -> bool {
+        self.files
+            .iter()
+            .any(|file| file.project == project && file.checksum == Some(checksum))
+    }
+}
+
+/// A project rejected the assemble request with a client error, for example
+/// because the token may not upload to it.
+#[derive(thiserror::Error, Debug)]
+#[error("{0:#}")]
+struct ProjectRejected(Error);
+
+/// Marks client errors of assemble requests as rejections by the project.
+/// Other errors, such as network and authentication errors, concern the whole
+/// upload and are returned unchanged.
+fn into_project_rejection(err: Error) -> Error {
+    let status = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ApiError>())
+        .and_then(ApiError::http_status);
+    match status {
+        Some(status) if (400..500).contains(&status) && status != 401 => {
+            ProjectRejected(err).into()
+        }
+        _ => err,
+    }
+}
+
+/// Uploads the missing chunks of hashed DIFs and assembles them in a project.
+/// Without `dedupe`, all chunks are uploaded.
+fn assemble_difs_in_project(
+    chunked: &[ChunkedDifMatch<'_>],
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+    project: &str,
+    dedupe: bool,
+) -> Result<(Vec<DebugInfoFile>, bool)> {
+    // Upload missing chunks to the server and remember incomplete difs
+    let missing_info = if dedupe {
+        try_assemble_difs(chunked, options, project).map_err(into_project_rejection)?
+    } else {
+        info!("Chunk deduplication disabled, uploading all chunks");
+        let chunks = chunked.iter().flat_map(|dif| dif.chunks()).collect();
+        (chunked.iter().collect(), chunks)
+    };
+    upload_missing_chunks(&missing_info, chunk_options)?;
+    finish_dif_assemble(&missing_info.0, options, chunk_options, project)
+        .map_err(into_project_rejection)
+}
+
+/// Assembles hashed DIFs in every project and prints the results per project.
+///
+/// Chunks are shared by all projects of the organization, so chunks that were
+/// uploaded for one project are not uploaded again for the others, even with
+/// `--no-dedupe`.  A project that rejects the upload does not stop the upload
+/// to the other projects, but fails the command.  Such projects are added to
+/// `rejected`.
+fn assemble_difs_in_projects(
+    chunked: &[ChunkedDifMatch<'_>],
+    options: &DifUpload,
+    chunk_options: &ChunkUploadOptions,
+    rejected: &mut Vec<String>,
+) -> Result<(Vec<DebugInfoFile>, bool)> {
+    let mut uploaded: Vec<DebugInfoFile> = vec![];
+    let mut has_errors = false;
+    for (index, project) in options.projects.iter().enumerate() {
+        quiet_println!("\n{}", style(format!("Project {project}")).bold());
+        let dedupe = options.dedupe || index > 0;
+        match assemble_difs_in_project(chunked, options, chunk_options, project, dedupe) {
+            Ok((files, project_errors)) => {
+                has_errors |= project_errors;
+                for file in files {
+                    if !uploaded.iter().any(|f| f.checksum == file.checksum) {
+                        uploaded.push(file);
+                    }
+                }
+            }
+            Err(err) => match err.downcast::<ProjectRejected>() {
+                Ok(err) => {
+                    quiet_println!("  {:>7} {err}", style("ERROR").red());
+                    rejected.push(project.clone());
+                }
+                Err(err) => return Err(err),
+            },
+        }
+    }
+
+    if rejected.len() == options.projects.len() {
+        bail!("All projects rejected the upload of debug information files");
+    }
+    if !rejected.is_empty() {
+        warn!(
+            "The upload of debug information files failed for {} of {} projects: {}",
+            rejected.len(),
+            options.projects.len(),
+            rejected.join(", ")
+        );
+        has_errors = true;
+    }
+    Ok((uploaded, has_errors))
+}
+
+/// Warns about objects whose debug information contains no inline functions,
+/// as their inline frames will not be symbolicated.  Fails instead if inline
+/// information is required.
+fn check_inline_info(difs: &[DifMatch<'_>], options: &DifUpload) -> Result<()> {
+    // Inlinees are only uploaded with the debug information, and reading them
+    // is expensive, so skip the check if nobody would see the result.
+    if !options.features.debug || !(options.require_inlines || log_enabled!(Level::Warn)) {
+        return Ok(());
+    }
+
+    let mut missing = 0;
+    for dif in difs {
+        let Some(object) = dif.object() else {
+            continue;
+        };
+        if supports_inline_info(object) && !has_inline_info(object) {
+            warn!(
+                "{} ({}) has no inline function information, inline frames will not be symbolicated",
+                dif.name,
+                object.debug_id()
+            );
+            missing += 1;
+        }
+    }
+
+    if missing > 0 && options.require_inlines {
+        bail!("{missing} debug information file(s) have no inline function information");
+    }
+    Ok(())
+}
+
 /// Uploads debug info files using the chunk-upload endpoint.
 fn upload_difs_chunked(
     options: &DifUpload,
     chunk_options: &ChunkUploadOptions,
+    rejected: &mut Vec<String>,
 ) -> Result<(Vec<DebugInfoFile>, bool)> {
     // Search for debug files in the file system and ZIPs
     let found = search_difs(options)?;
     if found.is_empty() {
-        println!("{} No debug information files found", style(">").dim());
+        quiet_println!("{} No debug information files found", style(">").dim());
         return Ok(Default::default());
     }
+    check_inline_info(&found, options)?;
 
     // Try to resolve BCSymbolMaps
     let symbol_map = options.symbol_map.as_deref();
@@ -1594,37 +2465,144 @@ fn upload_difs_chunked(
 
     // Resolve source code context if specified
     if options.include_sources {
-        let source_bundles = create_source_bundles(&processed, options.upload_il2cpp_mappings)?;
+        let source_bundles = create_source_bundles(
+            &processed,
+            options.upload_il2cpp_mappings,
+            options.source_root.as_deref(),
+        )?;
         processed.extend(source_bundles);
     }
 
-    // Calculate checksums and chunks
-    let chunked = prepare_difs(processed, |m| {
-        ChunkedDifMatch::from(m, chunk_options.chunk_size)
-    })?;
+    let chunked;
+    let result = match options.projects.as_slice() {
+        [project] if options.pipeline && options.dedupe => {
+            let pending;
+            (chunked, pending) = upload_difs_pipelined(processed, options, chunk_options, project)?;
+            let missing_difs: Vec<_> = pending.into_iter().map(|index| &chunked[index]).collect();
+            finish_dif_assemble(&missing_difs, options, chunk_options, project)?
+        }
+        projects => {
+            // Calculate checksums and chunks
+            chunked = prepare_difs(processed, |m| {
+                ChunkedDifMatch::from(m, chunk_options.chunk_size)
+            })?;
+
+            if let [project] = projects {
+                assemble_difs_in_project(&chunked, options, chunk_options, project, options.dedupe)?
+            } else {
+                assemble_difs_in_projects(&chunked, options, chunk_options, rejected)?
+            }
+        }
+    };
 
-    // Upload missing chunks to the server and remember incomplete difs
-    let missing_info = try_assemble_difs(&chunked, options)?;
-    upload_missing_chunks(&missing_info, chunk_options)?;
+    if options.prune_after {
+        prune_uploaded_difs(&chunked, options, rejected)?;
+    }
 
-    // Only if DIFs were missing, poll until assembling is complete
-    let (missing_difs, _) = missing_info;
-    if !missing_difs.is_empty() {
-        poll_dif_assemble(&missing_difs, options)
-    } else {
-        println!(
-            "{} Nothing to upload, all files are on the server",
-            style(">").dim()
-        );
+    // Files without a status from polling were already on the server.
+    let mut report = options.report.lock();
+    for project in &options.projects {
+        for dif in &chunked {
+            if report.contains(project, dif.checksum) {
+                continue;
+            }
+            let file = if rejected.contains(project) {
+                let message = "The project rejected the upload".to_owned();
+                DifUploadReportFile::new(project, dif, "error", Some(message))
+            } else {
+                DifUploadReportFile::new(project, dif, "skipped", None)
+            };
+            report.push(file);
+        }
+    }
+    report
+        .files
+        .sort_by(|a, b| (&a.project, &a.path).cmp(&(&b.project, &b.path)));
+
+    Ok(result)
+}
 
-        Ok((Default::default(), false))
+/// Deletes the local files of DIFs that the server reports as assembled.
+///
+/// A file is only deleted if every DIF read from it was assembled, and if it
+/// is a regular file within one of the search paths.  Files extracted from
+/// archives or generated during the upload are never deleted.
+fn prune_uploaded_difs(
+    difs: &[ChunkedDifMatch<'_>],
+    options: &DifUpload,
+    rejected: &[String],
+) -> Result<()> {
+    let request = difs
+        .iter()
+        .map(|d| d.to_assemble(options.pdbs_allowed))
+        .collect();
+    // A file is only assembled if it is assembled in every project that
+    // accepted the upload.
+    let mut assembled: BTreeMap<&Path, bool> = BTreeMap::new();
+    for project in options.projects.iter().filter(|p| !rejected.contains(p)) {
+        let response =
+            Api::current()
+                .authenticated()?
+                .assemble_difs(&options.org, project, &request)?;
+        for dif in difs {
+            if let Some(path) = dif.local_path.as_deref() {
+                let ok = response
+                    .get(&dif.checksum)
+                    .is_some_and(|r| r.state == ChunkedFileState::Ok);
+                *assembled.entry(path).or_insert(true) &= ok;
+            }
+        }
     }
+
+    let roots: Vec<_> = options
+        .paths
+        .iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .collect();
+    let mut pruned = 0;
+    let mut reclaimed = 0;
+    for (path, ok) in assembled {
+        if !ok {
+            debug!("Not pruning {}, it was not assembled", path.display());
+            continue;
+        }
+        let is_file = fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_file());
+        let in_roots = path
+            .canonicalize()
+            .is_ok_and(|path| roots.iter().any(|root| path.starts_with(root)));
+        if !is_file || !in_roots {
+            warn!(
+                "Not pruning {}, it is not a file within the search paths",
+                path.display()
+            );
+            continue;
+        }
+
+        let size = fs::metadata(path).map(|m| m.len()).unwrap_or_default();
+        fs::remove_file(path).with_context(|| format!("Failed to prune {}", path.display()))?;
+        debug!("Pruned {}", path.display());
+        pruned += 1;
+        reclaimed += size;
+    }
+
+    quiet_println!(
+        "{} Pruned {} uploaded {}, reclaimed {}",
+        style(">").dim(),
+        style(pruned).yellow(),
+        match pruned {
+            1 => "file",
+            _ => "files",
+        },
+        HumanBytes(reclaimed)
+    );
+    Ok(())
 }
 
 /// Returns debug files missing on the server.
 fn get_missing_difs<'data>(
     objects: Vec<HashedDifMatch<'data>>,
     options: &DifUpload,
+    project: &str,
 ) -> Result<Vec<HashedDifMatch<'data>>> {
     info!(
         "Checking for missing debug information files: {:#?}",
@@ -1634,11 +2612,8 @@ fn get_missing_difs<'data>(
     let api = Api::current();
     let missing_checksums = {
         let checksums = objects.iter().map(HashedDifMatch::checksum);
-        api.authenticated()?.find_missing_dif_checksums(
-            &options.org,
-            &options.project,
-            checksums,
-        )?
+        api.authenticated()?
+            .find_missing_dif_checksums(&options.org, project, checksums)?
     };
 
     let missing = objects
@@ -1660,7 +2635,7 @@ fn create_batch_archive(difs: &[HashedDifMatch<'_>]) -> Result<TempFile> {
         let mut zip = ZipWriter::new(tf.open()?);
 
         for symbol in difs {
-            zip.start_file(symbol.file_name(), FileOptions::default())?;
+            zip.start_file(symbol.file_name(), zip_file_options())?;
             copy_with_progress(&pb, &mut symbol.data(), &mut zip)?;
         }
     }
@@ -1673,26 +2648,27 @@ fn create_batch_archive(difs: &[HashedDifMatch<'_>]) -> Result<TempFile> {
 fn upload_in_batches(
     objects: &[HashedDifMatch<'_>],
     options: &DifUpload,
+    project: &str,
 ) -> Result<Vec<DebugInfoFile>> {
     let api = Api::current();
     let max_size = Config::current().get_max_dif_archive_size();
     let mut dsyms = Vec::new();
 
     for (i, (batch, _)) in objects.batches(max_size, MAX_CHUNKS).enumerate() {
-        println!("\n{}", style(format!("Batch {}", i + 1)).bold());
+        quiet_println!("\n{}", style(format!("Batch {}", i + 1)).bold());
 
-        println!(
+        quiet_println!(
             "{} Compressing {} debug symbol files",
             style(">").dim(),
             style(batch.len()).yellow()
         );
         let archive = create_batch_archive(batch)?;
 
-        println!("{} Uploading debug symbol files", style(">").dim());
+        quiet_println!("{} Uploading debug symbol files", style(">").dim());
         dsyms.extend(
             api.authenticated()?
                 .region_specific(&options.org)
-                .upload_dif_archive(&options.project, archive.path())?,
+                .upload_dif_archive(project, archive.path())?,
         );
     }
 
@@ -1700,13 +2676,14 @@ fn upload_in_batches(
 }
 
 /// Uploads debug info files using the legacy endpoint.
-fn upload_difs_batched(options: &DifUpload) -> Result<Vec<DebugInfoFile>> {
+fn upload_difs_batched(options: &DifUpload, project: &str) -> Result<Vec<DebugInfoFile>> {
     // Search for debug files in the file system and ZIPs
     let found = search_difs(options)?;
     if found.is_empty() {
-        println!("{} No debug information files found", style(">").dim());
+        quiet_println!("{} No debug information files found", style(">").dim());
         return Ok(Default::default());
     }
+    check_inline_info(&found, options)?;
 
     // Try to resolve BCSymbolMaps
     let symbol_map = options.symbol_map.as_deref();
@@ -1716,22 +2693,22 @@ fn upload_difs_batched(options: &DifUpload) -> Result<Vec<DebugInfoFile>> {
     let hashed = prepare_difs(processed, HashedDifMatch::from)?;
 
     // Check which files are missing on the server
-    let missing = get_missing_difs(hashed, options)?;
+    let missing = get_missing_difs(hashed, options, project)?;
     if missing.is_empty() {
-        println!(
+        quiet_println!(
             "{} Nothing to upload, all files are on the server",
             style(">").dim()
         );
-        println!("{} Nothing to upload", style(">").dim());
+        quiet_println!("{} Nothing to upload", style(">").dim());
         return Ok(Default::default());
     }
 
     // Upload missing DIFs in batches
-    let uploaded = upload_in_batches(&missing, options)?;
+    let uploaded = upload_in_batches(&missing, options, project)?;
     if !uploaded.is_empty() {
-        println!("{} File upload complete:\n", style(">").dim());
+        quiet_println!("{} File upload complete:\n", style(">").dim());
         for dif in &uploaded {
-            println!(
+            quiet_println!(
                 "  {} ({}; {})",
                 style(&dif.id()).dim(),
                 &dif.object_name,
@@ -1773,7 +2750,7 @@ pub enum DifFormat {
 /// ```
 /// use utils::dif_upload::DifUpload;
 ///
-/// DifUpload::new("org".into(), "project".into())
+/// DifUpload::new("org".into(), vec!["project".into()])
 ///     .search_path(".")
 ///     .upload()?;
 /// ```
@@ -1796,7 +2773,7 @@ pub enum DifFormat {
 #[derive(Debug, Default)]
 pub struct DifUpload {
     org: String,
-    project: String,
+    projects: Vec<String>,
     paths: Vec<PathBuf>,
     ids: BTreeSet<DebugId>,
     formats: BTreeSet<DifFormat>,
@@ -1804,21 +2781,34 @@ pub struct DifUpload {
     extensions: BTreeSet<OsString>,
     symbol_map: Option<PathBuf>,
     zips_allowed: bool,
+    exclude_dirs: Vec<glob::Pattern>,
     max_file_size: u64,
     max_wait: Duration,
     pdbs_allowed: bool,
     portablepdbs_allowed: bool,
     sources_allowed: bool,
     include_sources: bool,
+    source_root: Option<PathBuf>,
     bcsymbolmaps_allowed: bool,
     wait: bool,
+    dedupe: bool,
+    pipeline: bool,
+    require_inlines: bool,
+    abort_on_partial_assemble: bool,
+    prune_after: bool,
     upload_il2cpp_mappings: bool,
     il2cpp_mappings_allowed: bool,
+    on_conflict: ConflictPolicy,
+    rejected_projects: Vec<String>,
+    report: Mutex<DifUploadReport>,
 }
 
 impl DifUpload {
     /// Creates a new `DifUpload` with default parameters.
     ///
+    /// The files are uploaded to each of the given projects.  Their chunks are
+    /// only uploaded once and then assembled in every project.
+    ///
     /// To use it, also add paths using `DifUpload::search_path`. It will scan
     /// the paths and contained ZIPs for all supported object files and upload
     /// them.
@@ -1829,14 +2819,14 @@ impl DifUpload {
     /// ```
     /// use utils::dif_upload::DifUpload;
     ///
-    /// DifUpload::new("org", "project")
+    /// DifUpload::new("org", vec!["project"])
     ///     .search_path(".")
     ///     .upload()?;
     /// ```
-    pub fn new(org: String, project: String) -> Self {
+    pub fn new(org: String, projects: Vec<String>) -> Self {
         DifUpload {
             org,
-            project,
+            projects,
             paths: Vec::new(),
             ids: BTreeSet::new(),
             formats: BTreeSet::new(),
@@ -1844,16 +2834,26 @@ impl DifUpload {
             extensions: BTreeSet::new(),
             symbol_map: None,
             zips_allowed: true,
+            exclude_dirs: Vec::new(),
             max_file_size: DEFAULT_MAX_DIF_SIZE,
             max_wait: DEFAULT_MAX_WAIT,
             pdbs_allowed: false,
             portablepdbs_allowed: false,
             sources_allowed: false,
             include_sources: false,
+            source_root: None,
             bcsymbolmaps_allowed: false,
             wait: false,
+            dedupe: true,
+            pipeline: true,
+            require_inlines: false,
+            abort_on_partial_assemble: false,
+            prune_after: false,
             upload_il2cpp_mappings: false,
             il2cpp_mappings_allowed: false,
+            on_conflict: ConflictPolicy::default(),
+            rejected_projects: vec![],
+            report: Default::default(),
         }
     }
 
@@ -1931,6 +2931,29 @@ impl DifUpload {
         self
     }
 
+    /// Sets directory names or glob patterns that are not descended into
+    /// while searching for debug information files.
+    ///
+    /// Patterns are matched against the name of each directory, never against
+    /// the search path itself.
+    pub fn exclude_dirs<I>(&mut self, patterns: I) -> &mut Self
+    where
+        I: IntoIterator<Item = glob::Pattern>,
+    {
+        self.exclude_dirs = patterns.into_iter().collect();
+        self
+    }
+
+    /// Returns `true` if the directory entry should be pruned from the walk.
+    fn is_excluded_dir(&self, entry: &walkdir::DirEntry) -> bool {
+        entry.depth() > 0
+            && entry.file_type().is_dir()
+            && self
+                .exclude_dirs
+                .iter()
+                .any(|pattern| pattern.matches(&entry.file_name().to_string_lossy()))
+    }
+
     /// Set whether source files should be resolved during the scan process and
     /// uploaded as a separate archive.
     ///
@@ -1940,6 +2963,18 @@ impl DifUpload {
         self
     }
 
+    /// Set a directory from which to read the sources referenced by DWARF
+    /// debug information, see `write_dwarf_sources`.
+    ///
+    /// By default, sources are only read from their original paths.
+    pub fn source_root<P>(&mut self, path: P) -> &mut Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.source_root = Some(path.into());
+        self
+    }
+
     /// Set whether the upload should wait for the server to complete processing
     /// files or exit immediately after the upload.
     ///
@@ -1949,6 +2984,63 @@ impl DifUpload {
         self
     }
 
+    /// Set whether chunks the server already has are skipped.  Without
+    /// deduplication, all chunks are uploaded before assembling.
+    ///
+    /// Defaults to `true`.
+    pub fn dedupe(&mut self, dedupe: bool) -> &mut Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Set whether DIFs are hashed and uploaded at the same time.  Otherwise,
+    /// all DIFs are hashed before the first chunk is uploaded.
+    ///
+    /// Defaults to `true`.
+    pub fn pipeline(&mut self, pipeline: bool) -> &mut Self {
+        self.pipeline = pipeline;
+        self
+    }
+
+    /// Set whether the upload fails if an object with debug information has
+    /// no inline function information.  Otherwise, this only warns.
+    ///
+    /// Defaults to `false`.
+    pub fn require_inlines(&mut self, require_inlines: bool) -> &mut Self {
+        self.require_inlines = require_inlines;
+        self
+    }
+
+    /// Set whether the upload fails if some files were processed successfully
+    /// and others were not.  Otherwise, such partial failures are only
+    /// reported.  The upload always fails if no file was processed.
+    ///
+    /// Defaults to `false`.
+    pub fn abort_on_partial_assemble(&mut self, abort_on_partial_assemble: bool) -> &mut Self {
+        self.abort_on_partial_assemble = abort_on_partial_assemble;
+        self
+    }
+
+    /// Set whether the local files of DIFs are deleted once the server has
+    /// assembled them.  Only supported by the chunk upload protocol.
+    ///
+    /// Defaults to `false`.
+    pub fn prune_after(&mut self, prune_after: bool) -> &mut Self {
+        self.prune_after = prune_after;
+        self
+    }
+
+    /// Returns the projects that rejected the last upload.  The files were
+    /// still uploaded to the other projects.
+    pub fn rejected_projects(&self) -> &[String] {
+        &self.rejected_projects
+    }
+
+    /// Returns the status of every file of the last upload.
+    pub fn report(&mut self) -> &DifUploadReport {
+        self.report.get_mut()
+    }
+
     /// Sets the maximum length of time the upload should wait for the server to complete processing.
     ///
     /// Defaults to [`DEFAULT_MAX_WAIT`].
@@ -1965,12 +3057,20 @@ impl DifUpload {
         self
     }
 
+    /// Set how several files with the same debug id are handled.
+    ///
+    /// Defaults to [`ConflictPolicy::UploadBoth`].
+    pub fn on_conflict(&mut self, on_conflict: ConflictPolicy) -> &mut Self {
+        self.on_conflict = on_conflict;
+        self
+    }
+
     /// Performs the search for DIFs and uploads them.
     ///
     /// ```
     /// use utils::dif_upload::DifUpload;
     ///
-    /// DifUpload::new("org", "project")
+    /// DifUpload::new("org", vec!["project"])
     ///     .search_path(".")
     ///     .upload()?;
     /// ```
@@ -1979,12 +3079,13 @@ impl DifUpload {
     /// latter can be used to indicate a fail state from the upload.
     pub fn upload(&mut self) -> Result<(Vec<DebugInfoFile>, bool)> {
         if self.paths.is_empty() {
-            println!("{}: No paths were provided.", style("Warning").yellow());
+            quiet_println!("{}: No paths were provided.", style("Warning").yellow());
             return Ok(Default::default());
         }
 
         let api = Api::current();
-        if let Some(ref chunk_options) = api.authenticated()?.get_chunk_upload_options(&self.org)? {
+        let chunk_options = api.authenticated()?.get_chunk_upload_options(&self.org)?;
+        if let Some(ref chunk_options) = chunk_options {
             if chunk_options.max_file_size > 0 {
                 self.max_file_size = chunk_options.max_file_size;
             }
@@ -1999,15 +3100,36 @@ impl DifUpload {
             self.sources_allowed = chunk_options.supports(ChunkUploadCapability::Sources);
             self.bcsymbolmaps_allowed = chunk_options.supports(ChunkUploadCapability::BcSymbolmap);
             self.il2cpp_mappings_allowed = chunk_options.supports(ChunkUploadCapability::Il2Cpp);
+        }
 
-            if chunk_options.supports(ChunkUploadCapability::DebugFiles) {
-                self.validate_capabilities();
-                return upload_difs_chunked(self, chunk_options);
-            }
+        if use_chunked_upload(chunk_options.as_ref(), ChunkUploadCapability::DebugFiles)? {
+            self.validate_capabilities();
+            let mut rejected = vec![];
+            let result = upload_difs_chunked(self, chunk_options.as_ref().unwrap(), &mut rejected);
+            self.rejected_projects = rejected;
+            return result;
         }
 
         self.validate_capabilities();
-        Ok((upload_difs_batched(self)?, false))
+        let [project] = self.projects.as_slice() else {
+            bail!("The server does not support chunk uploads, which are required to upload to several projects at once.");
+        };
+        if self.prune_after {
+            warn!("The server does not support chunk uploads, so no files will be pruned.");
+        }
+        let uploaded = upload_difs_batched(self, project)?;
+        let report = self.report.get_mut();
+        for dif in &uploaded {
+            report.push(DifUploadReportFile {
+                project: project.clone(),
+                path: dif.object_name.clone(),
+                debug_id: Some(dif.id()),
+                status: "uploaded",
+                message: None,
+                checksum: None,
+            });
+        }
+        Ok((uploaded, false))
     }
 
     /// Validate that the server supports all requested capabilities.
@@ -2145,3 +3267,33 @@ impl DifUpload {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_symbol_maps_nested() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("Products/BCSymbolMaps");
+        fs::create_dir_all(&nested).unwrap();
+
+        let uuid = "C8374B6D-6E96-3F8F-A5D9-0F8C4E2D3A1B";
+        fs::write(nested.join(format!("{uuid}.bcsymbolmap")), b"").unwrap();
+        fs::write(nested.join("not-a-uuid.bcsymbolmap"), b"").unwrap();
+        fs::write(dir.path().join(format!("{uuid}.plist")), b"").unwrap();
+
+        let index = index_symbol_maps(dir.path()).unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(
+            index[&uuid.parse::<DebugId>().unwrap()],
+            nested.join(format!("{uuid}.bcsymbolmap"))
+        );
+    }
+
+    #[test]
+    fn test_source_bundle_path() {
+        assert_eq!(source_bundle_path("/build/src/main.c"), "build/src/main.c");
+        assert_eq!(source_bundle_path(r"C:\build\\main.c"), "C/build/main.c");
+    }
+}
@@ -0,0 +1,259 @@
+//! Inspection of stored envelopes before they are sent.
+//!
+//! Every envelope item declares the length of its payload in its header.
+//! Some envelope generators base64 encode binary payloads but declare the
+//! length of the decoded data, which breaks the framing of the envelope.
+//! Such items can be marked with `"encoding": "base64"` in their header and
+//! decoded before sending.
+use std::fmt;
+
+use anyhow::{bail, format_err, Context, Result};
+use log::info;
+use serde_json::{Map, Value};
+
+/// A single item of an envelope.
+pub struct EnvelopeItem {
+    header: Map<String, Value>,
+    payload: Vec<u8>,
+    /// The declared length if it does not match the payload.
+    mismatched_length: Option<u64>,
+}
+
+impl EnvelopeItem {
+    fn item_type(&self) -> &str {
+        self.header
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+    }
+
+    fn is_base64(&self) -> bool {
+        self.header.get("encoding").and_then(Value::as_str) == Some("base64")
+    }
+
+    fn decoded_payload(&self) -> Option<Vec<u8>> {
+        data_encoding::BASE64.decode(&self.payload).ok()
+    }
+}
+
+/// A problem with the framing of an envelope item.
+pub struct ItemProblem {
+    index: usize,
+    item_type: String,
+    message: String,
+}
+
+impl fmt::Display for ItemProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "item {} ({}): {}",
+            self.index + 1,
+            self.item_type,
+            self.message
+        )
+    }
+}
+
+/// An envelope split into its items without interpreting their payloads.
+pub struct StoredEnvelope {
+    header: Vec<u8>,
+    items: Vec<EnvelopeItem>,
+}
+
+impl StoredEnvelope {
+    pub fn parse(bytes: &[u8]) -> Result<StoredEnvelope> {
+        let (header, mut rest) = split_line(bytes);
+        if header.is_empty() {
+            bail!("missing envelope header");
+        }
+
+        let mut items = vec![];
+        while !rest.is_empty() {
+            let (item_header, remainder) = split_line(rest);
+            if item_header.is_empty() {
+                rest = remainder;
+                continue;
+            }
+            let header: Map<String, Value> = serde_json::from_slice(item_header)
+                .with_context(|| format!("invalid header of item {}", items.len() + 1))?;
+
+            let declared_length = header.get("length").and_then(Value::as_u64);
+            let fits = declared_length
+                .and_then(|length| usize::try_from(length).ok())
+                .filter(|&length| {
+                    length <= remainder.len() && matches!(remainder.get(length), None | Some(b'\n'))
+                });
+
+            // Items without a length, or with one that does not match the
+            // payload, are assumed to end at the next newline.
+            let (payload, remainder) = match fits {
+                Some(length) => (
+                    &remainder[..length],
+                    remainder.get(length + 1..).unwrap_or_default(),
+                ),
+                None => split_line(remainder),
+            };
+            items.push(EnvelopeItem {
+                header,
+                payload: payload.to_vec(),
+                mismatched_length: declared_length.filter(|_| fits.is_none()),
+            });
+            rest = remainder;
+        }
+
+        Ok(StoredEnvelope {
+            header: header.to_vec(),
+            items,
+        })
+    }
+
+    /// Checks the framing of all items.  `decode_base64` indicates whether
+    /// base64 encoded items will be decoded before sending.
+    pub fn validate(&self, decode_base64: bool) -> Vec<ItemProblem> {
+        let mut problems = vec![];
+        for (index, item) in self.items.iter().enumerate() {
+            let mut problem = |message| {
+                problems.push(ItemProblem {
+                    index,
+                    item_type: item.item_type().to_owned(),
+                    message,
+                })
+            };
+
+            if item.is_base64() {
+                let Some(decoded) = item.decoded_payload() else {
+                    problem("declares base64 encoding, but the payload is not valid base64".into());
+                    continue;
+                };
+                if let Some(length) = item.mismatched_length {
+                    if length != decoded.len() as u64 {
+                        problem(format!(
+                            "declares a length of {length} bytes, but the decoded payload is {} bytes long",
+                            decoded.len()
+                        ));
+                        continue;
+                    }
+                }
+                if !decode_base64 {
+                    problem(
+                        "declares base64 encoding; pass --decode-base64-items to decode it \
+                         before sending"
+                            .into(),
+                    );
+                }
+            } else if let Some(length) = item.mismatched_length {
+                let base64_length = item
+                    .decoded_payload()
+                    .map(|decoded| decoded.len() as u64)
+                    .filter(|&decoded| decoded == length);
+                problem(if base64_length.is_some() {
+                    format!(
+                        "declares a length of {length} bytes, but the payload is {} bytes long. \
+                         The payload appears to be base64 encoded: add \"encoding\":\"base64\" \
+                         to the item header and pass --decode-base64-items, or store the payload \
+                         unencoded",
+                        item.payload.len()
+                    )
+                } else {
+                    format!(
+                        "declares a length of {length} bytes, but the payload is {} bytes long",
+                        item.payload.len()
+                    )
+                });
+            }
+        }
+        problems
+    }
+
+    /// Decodes all items that declare base64 encoding.
+    pub fn decode_base64_items(&mut self) -> Result<()> {
+        for (index, item) in self.items.iter_mut().enumerate() {
+            if !item.is_base64() {
+                continue;
+            }
+            let decoded = item.decoded_payload().ok_or_else(|| {
+                format_err!(
+                    "item {} ({}) declares base64 encoding, but the payload is not valid base64",
+                    index + 1,
+                    item.item_type()
+                )
+            })?;
+            info!(
+                "Decoded base64 payload of item {} ({}) from {} to {} bytes",
+                index + 1,
+                item.item_type(),
+                item.payload.len(),
+                decoded.len()
+            );
+            item.header.remove("encoding");
+            item.header.insert("length".into(), decoded.len().into());
+            item.payload = decoded;
+            item.mismatched_length = None;
+        }
+        Ok(())
+    }
+
+    /// Serializes the envelope, with every item framed by its actual length.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header.clone();
+        for item in &self.items {
+            let mut header = item.header.clone();
+            header.insert("length".into(), item.payload.len().into());
+            bytes.push(b'\n');
+            bytes.extend(Value::Object(header).to_string().into_bytes());
+            bytes.push(b'\n');
+            bytes.extend(&item.payload);
+        }
+        bytes.push(b'\n');
+        bytes
+    }
+}
+
+/// Splits off the first line, without its newline.
+fn split_line(bytes: &[u8]) -> (&[u8], &[u8]) {
+    match bytes.iter().position(|&b| b == b'\n') {
+        Some(pos) => (&bytes[..pos], &bytes[pos + 1..]),
+        None => (bytes, &[]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_base64_length_mismatch() {
+        let envelope = StoredEnvelope::parse(
+            b"{}\n{\"type\":\"attachment\",\"length\":12}\nc29tZSBjb250ZW50\n",
+        )
+        .unwrap();
+        let problems = envelope.validate(false);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0]
+            .to_string()
+            .starts_with("item 1 (attachment): declares a length of 12 bytes, but the payload is 16 bytes long. The payload appears to be base64 encoded"));
+    }
+
+    #[test]
+    fn test_decode_base64_items() {
+        let mut envelope = StoredEnvelope::parse(
+            b"{}\n{\"type\":\"attachment\",\"length\":12,\"encoding\":\"base64\"}\nc29tZSBjb250ZW50\n",
+        )
+        .unwrap();
+        assert!(envelope.validate(true).is_empty());
+        envelope.decode_base64_items().unwrap();
+        assert_eq!(
+            envelope.to_bytes(),
+            b"{}\n{\"length\":12,\"type\":\"attachment\"}\nsome content\n"
+        );
+    }
+
+    #[test]
+    fn test_valid_envelope_roundtrip() {
+        let bytes = b"{}\n{\"length\":2,\"type\":\"event\"}\n{}\n";
+        let envelope = StoredEnvelope::parse(bytes).unwrap();
+        assert!(envelope.validate(false).is_empty());
+        assert_eq!(envelope.to_bytes(), bytes);
+    }
+}
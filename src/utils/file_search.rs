@@ -10,6 +10,7 @@ use ignore::types::TypesBuilder;
 use ignore::WalkBuilder;
 use log::{info, warn};
 
+use crate::utils::logging::quiet_println;
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 
 use super::fs::{decompress_gzip_content, is_gzip_compressed};
@@ -169,7 +170,7 @@ impl ReleaseFileSearch {
         }
 
         pb.finish_and_clear();
-        println!(
+        quiet_println!(
             "{} Found {} {}",
             style(">").dim(),
             style(collected.len()).yellow(),
@@ -1,15 +1,18 @@
 //! Searches, processes and uploads release files.
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
+use std::fs;
 use std::io::BufWriter;
-use std::path::PathBuf;
-use std::str;
+use std::path::{Path, PathBuf};
+use std::str::{self, FromStr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Result};
 use console::style;
-use log::info;
+use indicatif::HumanBytes;
+use itertools::Itertools;
+use log::{debug, info, warn};
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
@@ -22,10 +25,15 @@ use symbolic::debuginfo::sourcebundle::{
 use url::Url;
 
 use crate::api::NewRelease;
-use crate::api::{Api, ChunkUploadCapability, ChunkUploadOptions};
-use crate::constants::DEFAULT_MAX_WAIT;
-use crate::utils::chunks::{upload_chunks, Chunk, ASSEMBLE_POLL_INTERVAL};
+use crate::api::{Api, AssembleArtifactsResponse, ChunkUploadCapability, ChunkUploadOptions};
+use crate::config::Config;
+use crate::constants::{APP_NAME, DEFAULT_MAX_WAIT};
+use crate::utils::chunks::{
+    self, upload_chunks, use_chunked_upload, AssemblePolling, Chunk, UploadProtocol,
+};
 use crate::utils::fs::{get_sha1_checksum, get_sha1_checksums, TempFile};
+use crate::utils::logging::quiet_println;
+use crate::utils::metrics_summary;
 use crate::utils::progress::{ProgressBar, ProgressBarMode, ProgressStyle};
 
 /// Fallback concurrency for release file uploads.
@@ -41,12 +49,7 @@ pub fn initialize_legacy_release_upload(context: &UploadContext) -> Result<()> {
     // need to do anything here.  Artifact bundles will also only work
     // if a project is provided which is technically unnecessary for the
     // legacy upload though it will unlikely to be what users want.
-    if context.project.is_some()
-        && context.chunk_upload_options.map_or(false, |x| {
-            x.supports(ChunkUploadCapability::ArtifactBundles)
-                || x.supports(ChunkUploadCapability::ArtifactBundlesV2)
-        })
-    {
+    if uses_artifact_bundles(context) {
         return Ok(());
     }
 
@@ -65,21 +68,73 @@ pub fn initialize_legacy_release_upload(context: &UploadContext) -> Result<()> {
     }
 
     if let Some(version) = context.release {
-        let api = Api::current();
-        api.authenticated()?.new_release(
-            context.org,
-            &NewRelease {
-                version: version.to_string(),
-                projects: context.project.map(|x| x.to_string()).into_iter().collect(),
-                ..Default::default()
-            },
-        )?;
+        create_release(context, version)?;
     } else {
         bail!("This version of Sentry does not support artifact bundles. A release slug is required (provide with --release)");
     }
     Ok(())
 }
 
+/// Whether files are uploaded as artifact bundles, which do not need a release.
+pub fn uses_artifact_bundles(context: &UploadContext) -> bool {
+    context.project.is_some()
+        && context.chunk_upload_options.map_or(false, |x| {
+            x.supports(ChunkUploadCapability::ArtifactBundles)
+                || x.supports(ChunkUploadCapability::ArtifactBundlesV2)
+        })
+}
+
+fn create_release(context: &UploadContext, version: &str) -> Result<()> {
+    let api = Api::current();
+    api.authenticated()?.new_release(
+        context.org,
+        &NewRelease {
+            version: version.to_string(),
+            projects: context.project.map(|x| x.to_string()).into_iter().collect(),
+            ..Default::default()
+        },
+    )?;
+    Ok(())
+}
+
+/// Whether to upload with the legacy release file API although the server
+/// supports chunked uploads, because the files are smaller than
+/// `--chunked-threshold`.  For small uploads, the additional requests of
+/// chunked uploads take longer than uploading the files directly.
+fn below_chunked_threshold(context: &UploadContext, files: &SourceFiles) -> Result<bool> {
+    let Some(threshold) = context.chunked_threshold else {
+        return Ok(false);
+    };
+    if Config::current().get_upload_protocol() != UploadProtocol::Auto {
+        return Ok(false);
+    }
+    let Some(release) = context.release else {
+        info!("using chunked upload, legacy uploads below --chunked-threshold require a release");
+        return Ok(false);
+    };
+
+    let size: u64 = files.values().map(|file| file.contents.len() as u64).sum();
+    if size >= threshold {
+        info!(
+            "using chunked upload, the files ({}) are not below --chunked-threshold ({})",
+            HumanBytes(size),
+            HumanBytes(threshold)
+        );
+        return Ok(false);
+    }
+
+    info!(
+        "using legacy upload instead, the files ({}) are below --chunked-threshold ({})",
+        HumanBytes(size),
+        HumanBytes(threshold)
+    );
+    // The release was not created in advance for artifact bundles.
+    if uses_artifact_bundles(context) {
+        create_release(context, release)?;
+    }
+    Ok(true)
+}
+
 #[derive(Debug, Clone)]
 pub struct UploadContext<'a> {
     pub org: &'a str,
@@ -89,7 +144,11 @@ pub struct UploadContext<'a> {
     pub note: Option<&'a str>,
     pub wait: bool,
     pub max_wait: Duration,
+    /// Whether files and chunks that are already on the server are skipped.
     pub dedupe: bool,
+    pub prefetch_missing_chunks: bool,
+    /// Uploads smaller than this many bytes use the legacy release file API.
+    pub chunked_threshold: Option<u64>,
     pub chunk_upload_options: Option<&'a ChunkUploadOptions>,
 }
 
@@ -165,6 +224,47 @@ impl SourceFile {
     }
 }
 
+/// Controls what happens when a release file with the same name and dist
+/// already exists on the server.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum IfExists {
+    /// Leave the existing file in place and do not upload the local one.
+    Skip,
+    /// Replace the existing file with the local one.
+    Overwrite,
+    /// Abort the upload.
+    Fail,
+}
+
+impl IfExists {
+    pub fn all_names() -> &'static [&'static str] {
+        &["skip", "overwrite", "fail"]
+    }
+}
+
+impl FromStr for IfExists {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<IfExists> {
+        match s {
+            "skip" => Ok(IfExists::Skip),
+            "overwrite" => Ok(IfExists::Overwrite),
+            "fail" => Ok(IfExists::Fail),
+            _ => bail!("Invalid value for --if-exists: {}", s),
+        }
+    }
+}
+
+impl fmt::Display for IfExists {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            IfExists::Skip => write!(f, "skip"),
+            IfExists::Overwrite => write!(f, "overwrite"),
+            IfExists::Fail => write!(f, "fail"),
+        }
+    }
+}
+
 /// A map from URLs to source files.
 ///
 /// The keys correspond to the `url` field on the values.
@@ -173,6 +273,7 @@ pub type SourceFiles = BTreeMap<String, SourceFile>;
 pub struct FileUpload<'a> {
     context: &'a UploadContext<'a>,
     files: SourceFiles,
+    if_exists: Option<IfExists>,
 }
 
 impl<'a> FileUpload<'a> {
@@ -180,9 +281,18 @@ impl<'a> FileUpload<'a> {
         FileUpload {
             context,
             files: SourceFiles::new(),
+            if_exists: None,
         }
     }
 
+    /// Sets the behavior for files that already exist in the release.
+    ///
+    /// If unset, existing files are replaced.
+    pub fn if_exists(&mut self, if_exists: Option<IfExists>) -> &mut Self {
+        self.if_exists = if_exists;
+        self
+    }
+
     pub fn files(&mut self, files: &SourceFiles) -> &mut Self {
         for (k, v) in files {
             if !v.already_uploaded {
@@ -195,27 +305,66 @@ impl<'a> FileUpload<'a> {
     pub fn upload(&self) -> Result<()> {
         initialize_legacy_release_upload(self.context)?;
 
-        if let Some(chunk_options) = self.context.chunk_upload_options {
-            if chunk_options.supports(ChunkUploadCapability::ReleaseFiles) {
-                return upload_files_chunked(self.context, &self.files, chunk_options);
+        let chunk_options = self.context.chunk_upload_options;
+        let chunked = use_chunked_upload(chunk_options, ChunkUploadCapability::ReleaseFiles)?
+            && !below_chunked_threshold(self.context, &self.files)?;
+
+        // Artifact bundles are not stored as release files, so `if_exists`
+        // cannot be applied to them.
+        if chunked && uses_artifact_bundles(self.context) {
+            if self.if_exists.is_some() {
+                bail!(
+                    "--if-exists is not supported because the server stores the files \
+                     in artifact bundles instead of release files"
+                );
+            }
+            return upload_files_chunked(self.context, &self.files, chunk_options.unwrap());
+        }
+
+        let remaining;
+        let mut release_files = None;
+        let files = match self.if_exists {
+            Some(if_exists) => {
+                let existing = list_existing_release_files(self.context)?;
+                remaining =
+                    resolve_existing_files(self.context, &self.files, &existing, if_exists)?;
+                release_files = Some(existing);
+                &remaining
             }
+            None => &self.files,
+        };
+
+        if files.is_empty() {
+            quiet_println!(
+                "{} Nothing to upload, all files already exist in the release",
+                style(">").dim()
+            );
+            return Ok(());
+        }
+
+        if chunked {
+            return upload_files_chunked(self.context, files, chunk_options.unwrap());
         }
 
         // Do not permit uploads of more than 20k files if the server does not
         // support artifact bundles.  This is a temporary downside protection to
         // protect users from uploading more sources than we support.
-        if self.files.len() > 20_000 {
+        if files.len() > 20_000 {
             bail!(
                 "Too many sources: {} exceeds maximum allowed files per release",
-                &self.files.len()
+                &files.len()
             );
         }
 
+        let release_files = match release_files {
+            Some(release_files) => release_files,
+            None => list_existing_release_files(self.context)?,
+        };
         let concurrency = self
             .context
             .chunk_upload_options
             .map_or(DEFAULT_CONCURRENCY, |o| usize::from(o.concurrency));
-        upload_files_parallel(self.context, &self.files, concurrency)
+        upload_files_parallel(self.context, files, &release_files, concurrency)
     }
 
     pub fn build_jvm_bundle(&self, debug_id: Option<DebugId>) -> Result<TempFile> {
@@ -223,24 +372,160 @@ impl<'a> FileUpload<'a> {
     }
 }
 
+/// Normalizes the value of `--url-prefix` and warns about common mistakes,
+/// or fails if `strict` is set.
+///
+/// Trailing slashes are removed, since a slash is inserted before every file
+/// name.  The slashes of an empty URL authority stay intact apart from the
+/// last one, so `app:///` becomes `app://` as it always did.
+pub fn normalize_url_prefix(prefix: &str, strict: bool) -> Result<String> {
+    let normalized = strip_trailing_slashes(prefix);
+    if let Some((problem, suggestion)) = check_url_prefix(&normalized) {
+        let message = format!("URL prefix `{prefix}` {problem}, did you mean `{suggestion}`?");
+        if strict {
+            bail!(message);
+        }
+        warn!("{message} Files may not match the URLs in your stack traces.");
+    }
+    Ok(normalized)
+}
+
+fn strip_trailing_slashes(prefix: &str) -> String {
+    let trimmed = prefix.trim_end_matches('/');
+    if trimmed.ends_with(':') && has_scheme(trimmed) {
+        prefix.strip_suffix('/').unwrap_or(prefix).to_owned()
+    } else {
+        trimmed.to_owned()
+    }
+}
+
+/// Whether the prefix starts with a URL scheme.  Single letters are not
+/// considered schemes, since they are usually Windows drives.
+fn has_scheme(prefix: &str) -> bool {
+    let Some((scheme, _)) = prefix.split_once(':') else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    scheme.len() > 1
+        && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+}
+
+/// Checks a normalized URL prefix for forms that rarely match the URLs of
+/// stack frames, and returns the problem together with a corrected prefix.
+fn check_url_prefix(prefix: &str) -> Option<(&'static str, String)> {
+    // Report the first problem, but suggest a prefix without any of them.
+    let fixed = if prefix.trim() != prefix {
+        Some((
+            "has leading or trailing whitespace",
+            prefix.trim().to_owned(),
+        ))
+    } else if prefix.contains('\\') {
+        Some(("contains backslashes", prefix.replace('\\', "/")))
+    } else {
+        None
+    };
+    if let Some((problem, fixed)) = fixed {
+        let fixed = strip_trailing_slashes(&fixed);
+        let suggestion = check_url_prefix(&fixed).map_or(fixed, |(_, suggestion)| suggestion);
+        return Some((problem, suggestion));
+    }
+    if has_scheme(prefix) {
+        return None;
+    }
+
+    let path = if prefix == "~" {
+        ""
+    } else if let Some(path) = prefix.strip_prefix("~/") {
+        path
+    } else if let Some(path) = prefix.strip_prefix('~') {
+        return Some(("is missing the slash after `~`", format!("~/{path}")));
+    } else {
+        let path = prefix.trim_start_matches(['.', '/']);
+        let suggestion = if path.is_empty() {
+            "~".to_owned()
+        } else {
+            format!("~/{path}")
+        };
+        return Some(("does not start with `~/` or a URL scheme", suggestion));
+    };
+
+    if !path.is_empty() && path.split('/').any(str::is_empty) {
+        let path = path.split('/').filter(|s| !s.is_empty()).join("/");
+        return Some(("contains empty path segments", format!("~/{path}")));
+    }
+    None
+}
+
+/// Release files that already exist, keyed by dist and name.
+type ReleaseFiles = HashMap<(Option<String>, String), String>;
+
+/// Lists the files of the release, mapping them to their IDs.
+fn list_existing_release_files(context: &UploadContext) -> Result<ReleaseFiles> {
+    let api = Api::current();
+    Ok(api
+        .authenticated()?
+        .list_release_files(context.org, context.project, context.release()?)?
+        .into_iter()
+        .map(|artifact| ((artifact.dist, artifact.name), artifact.id))
+        .collect())
+}
+
+/// Checks which of the given files already exist in the release and applies
+/// the `if_exists` behavior to them.  Prints the action taken for every file
+/// and returns the files that still need to be uploaded.
+fn resolve_existing_files(
+    context: &UploadContext,
+    files: &SourceFiles,
+    existing: &ReleaseFiles,
+    if_exists: IfExists,
+) -> Result<SourceFiles> {
+    let release = context.release()?;
+    let dist = context.dist.map(str::to_owned);
+    let mut conflicts = vec![];
+    let mut rv = SourceFiles::new();
+    for (url, file) in files {
+        let exists = existing.contains_key(&(dist.clone(), url.clone()));
+        let (action, upload) = match (exists, if_exists) {
+            (false, _) => ("upload", true),
+            (true, IfExists::Skip) => ("skip", false),
+            (true, IfExists::Overwrite) => ("overwrite", true),
+            (true, IfExists::Fail) => {
+                conflicts.push(url.as_str());
+                ("fail", true)
+            }
+        };
+        quiet_println!("  {:<9} {}", style(action).dim(), url);
+        if upload {
+            rv.insert(url.clone(), file.clone());
+        }
+    }
+
+    if !conflicts.is_empty() {
+        bail!(
+            "{} {} in release {}: {}",
+            conflicts.len(),
+            match conflicts.len() {
+                1 => "file already exists",
+                _ => "files already exist",
+            },
+            release,
+            conflicts.join(", ")
+        );
+    }
+
+    Ok(rv)
+}
+
 fn upload_files_parallel(
     context: &UploadContext,
     files: &SourceFiles,
+    release_files: &ReleaseFiles,
     num_threads: usize,
 ) -> Result<()> {
-    let api = Api::current();
     let release = context.release()?;
 
-    // get a list of release files first so we know the file IDs of
-    // files that already exist.
-    let release_files: HashMap<_, _> = api
-        .authenticated()?
-        .list_release_files(context.org, context.project, release)?
-        .into_iter()
-        .map(|artifact| ((artifact.dist, artifact.name), artifact.id))
-        .collect();
-
-    println!(
+    quiet_println!(
         "{} Uploading source maps for release {}",
         style(">").dim(),
         style(release).cyan()
@@ -308,7 +593,7 @@ fn upload_files_parallel(
 
     pb.finish_and_clear();
 
-    print_upload_context_details(context);
+    print_upload_context_details(context, false);
 
     Ok(())
 }
@@ -338,7 +623,10 @@ fn poll_assemble(
     let use_artifact_bundle = (options.supports(ChunkUploadCapability::ArtifactBundles)
         || options.supports(ChunkUploadCapability::ArtifactBundlesV2))
         && context.project.is_some();
+    let polling = AssemblePolling::from_config(&Config::current());
+    let mut attempts = 0;
     let response = loop {
+        attempts += 1;
         // prefer standalone artifact bundle upload over legacy release based upload
         let response = if use_artifact_bundle {
             authenticated_api.assemble_artifact_bundle(
@@ -365,11 +653,11 @@ fn poll_assemble(
             break response;
         }
 
-        if assemble_start.elapsed() > max_wait {
+        if assemble_start.elapsed() > max_wait || !polling.may_poll(attempts) {
             break response;
         }
 
-        std::thread::sleep(ASSEMBLE_POLL_INTERVAL);
+        std::thread::sleep(polling.interval);
     };
 
     if response.state.is_err() {
@@ -380,19 +668,21 @@ fn poll_assemble(
     pb.finish_with_duration("Processing");
 
     if response.state.is_pending() {
-        if context.wait {
+        if context.wait && !polling.may_poll(attempts) {
+            bail!("Failed to process files after {attempts} assemble requests");
+        } else if context.wait {
             bail!("Failed to process files in {}s", max_wait.as_secs());
         } else {
-            println!(
+            quiet_println!(
                 "{} File upload complete (processing pending on server)",
                 style(">").dim()
             );
         }
     } else {
-        println!("{} File processing complete", style(">").dim());
+        quiet_println!("{} File processing complete", style(">").dim());
     }
 
-    print_upload_context_details(context);
+    print_upload_context_details(context, true);
 
     Ok(())
 }
@@ -427,26 +717,69 @@ fn upload_files_chunked(
         style(">").dim(),
     ));
 
+    // An upload is only resumed if an earlier upload of the same bundle was interrupted.
+    let state_path = upload_state_path(checksum);
+    let resuming = state_path.as_ref().is_some_and(|path| path.exists());
+
     // Filter out chunks that are already on the server. This only matters if the server supports
     // `ArtifactBundlesV2`, otherwise the `missing_chunks` field is meaningless.
-    if options.supports(ChunkUploadCapability::ArtifactBundlesV2) && context.project.is_some() {
-        let api = Api::current();
-        let response = api.authenticated()?.assemble_artifact_bundle(
-            context.org,
-            vec![context.project.unwrap().to_string()],
-            checksum,
-            &checksums,
-            context.release,
-            context.dist,
-        )?;
+    let mut prefetched = false;
+    if !context.dedupe {
+        info!("Chunk deduplication disabled, uploading all chunks");
+    } else if options.supports(ChunkUploadCapability::ArtifactBundlesV2)
+        && context.project.is_some()
+    {
+        let probe = || -> Result<AssembleArtifactsResponse> {
+            Ok(Api::current().authenticated()?.assemble_artifact_bundle(
+                context.org,
+                vec![context.project.unwrap().to_string()],
+                checksum,
+                &checksums,
+                context.release,
+                context.dist,
+            )?)
+        };
+
+        let response = if context.prefetch_missing_chunks {
+            let (response, prefetched_chunks) = probe_and_prefetch(&chunks, options, probe)?;
+            prefetched = !prefetched_chunks.is_empty();
+            chunks.retain(|Chunk((digest, _))| !prefetched_chunks.contains(digest));
+            response
+        } else {
+            probe()?
+        };
         chunks.retain(|Chunk((digest, _))| response.missing_chunks.contains(digest));
+
+        // Chunks left over from an earlier, interrupted upload are not sent again.
+        let missing = response.missing_chunks.len();
+        if !prefetched {
+            metrics_summary::record_deduped_chunks(checksums.len() - missing);
+        }
+        if resuming && missing > 0 && missing < checksums.len() {
+            quiet_println!(
+                "{} Resuming upload, {} of {} chunks are already on the server",
+                style(">").dim(),
+                checksums.len() - missing,
+                checksums.len()
+            );
+        } else if missing > 0 {
+            info!("Starting a fresh upload of {missing} chunks");
+        }
     };
 
-    if !chunks.is_empty() {
-        upload_chunks(&chunks, options, progress_style)?;
-        println!("{} Uploaded files to Sentry", style(">").dim());
+    if !chunks.is_empty() || prefetched {
+        if !chunks.is_empty() {
+            if let Some(ref path) = state_path {
+                save_upload_state(path);
+            }
+            upload_chunks(&chunks, options, progress_style)?;
+        }
+        if let Some(ref path) = state_path {
+            fs::remove_file(path).ok();
+        }
+        quiet_println!("{} Uploaded files to Sentry", style(">").dim());
     } else {
-        println!(
+        quiet_println!(
             "{} Nothing to upload, all files are on the server",
             style(">").dim()
         );
@@ -454,6 +787,71 @@ fn upload_files_chunked(
     poll_assemble(checksum, &checksums, context, options)
 }
 
+/// Returns the path of the file marking an unfinished upload of the bundle
+/// with the given checksum.
+fn upload_state_path(checksum: Digest) -> Option<PathBuf> {
+    let mut path = dirs::cache_dir()?;
+    path.push(APP_NAME);
+    path.push("uploads");
+    path.push(checksum.to_string());
+    Some(path)
+}
+
+/// Marks the upload as unfinished until all of its chunks are on the server.
+fn save_upload_state(path: &Path) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::File::create(path).map(|_| ()));
+    if let Err(err) = result {
+        info!("Could not save upload state to {}: {err}", path.display());
+    }
+}
+
+/// Uploads the first batch of chunks while `probe` asks the server which chunks
+/// are missing.  Returns the probe's response and the checksums of the chunks
+/// that were uploaded in the meantime, so that they are not sent again.
+fn probe_and_prefetch<P>(
+    chunks: &[Chunk<'_>],
+    options: &ChunkUploadOptions,
+    probe: P,
+) -> Result<(AssembleArtifactsResponse, HashSet<Digest>)>
+where
+    P: FnOnce() -> Result<AssembleArtifactsResponse>,
+{
+    let batch = chunks::first_batch(chunks, options);
+    let start = Instant::now();
+
+    let (response, upload) = std::thread::scope(|scope| {
+        let upload = scope.spawn(|| {
+            let upload_start = Instant::now();
+            chunks::upload_chunk_batch(batch, options).map(|()| upload_start.elapsed())
+        });
+        let probe_start = Instant::now();
+        let response = probe().map(|response| (response, probe_start.elapsed()));
+        let upload = upload
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+        (response, upload)
+    });
+    let (response, probe_duration) = response?;
+    let upload_duration = upload?;
+
+    let elapsed = start.elapsed();
+    debug!(
+        "prefetched {} chunks while probing for missing chunks in {:?} \
+         (probe {:?}, upload {:?}, saved {:?})",
+        batch.len(),
+        elapsed,
+        probe_duration,
+        upload_duration,
+        (probe_duration + upload_duration).saturating_sub(elapsed)
+    );
+
+    let prefetched = batch.iter().map(|Chunk((digest, _))| *digest).collect();
+    Ok((response, prefetched))
+}
+
 /// Creates a debug id from a map of source files by hashing each file's
 /// URL, contents, type, and headers.
 fn build_debug_id(files: &SourceFiles) -> DebugId {
@@ -539,7 +937,7 @@ fn build_artifact_bundle(
 
     pb.finish_with_duration("Bundling");
 
-    println!(
+    quiet_println!(
         "{} Bundled {} {} for upload",
         style(">").dim(),
         style(files.len()).yellow(),
@@ -549,7 +947,7 @@ fn build_artifact_bundle(
         }
     );
 
-    println!(
+    quiet_println!(
         "{} Bundle ID: {}",
         style(">").dim(),
         style(debug_id).yellow(),
@@ -581,28 +979,28 @@ fn url_to_bundle_path(url: &str) -> Result<String> {
     })
 }
 
-fn print_upload_context_details(context: &UploadContext) {
-    println!(
+fn print_upload_context_details(context: &UploadContext, chunked: bool) {
+    quiet_println!(
         "{} {}",
         style("> Organization:").dim(),
         style(context.org).yellow()
     );
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Project:").dim(),
         style(context.project.unwrap_or("None")).yellow()
     );
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Release:").dim(),
         style(context.release.unwrap_or("None")).yellow()
     );
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Dist:").dim(),
         style(context.dist.unwrap_or("None")).yellow()
     );
-    let upload_type = match context.chunk_upload_options {
+    let upload_type = match context.chunk_upload_options.filter(|_| chunked) {
         None => "single file",
         Some(opts)
             if opts.supports(ChunkUploadCapability::ArtifactBundles)
@@ -612,7 +1010,7 @@ fn print_upload_context_details(context: &UploadContext) {
         }
         _ => "release bundle",
     };
-    println!(
+    quiet_println!(
         "{} {}",
         style("> Upload type:").dim(),
         style(upload_type).yellow()
@@ -625,6 +1023,44 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_normalize_url_prefix() {
+        assert_eq!(normalize_url_prefix("~", true).unwrap(), "~");
+        assert_eq!(normalize_url_prefix("~/", true).unwrap(), "~");
+        assert_eq!(
+            normalize_url_prefix("~/static/js/", true).unwrap(),
+            "~/static/js"
+        );
+        assert_eq!(
+            normalize_url_prefix("~/static//", true).unwrap(),
+            "~/static"
+        );
+        assert_eq!(normalize_url_prefix("app:///", true).unwrap(), "app://");
+        assert_eq!(normalize_url_prefix("app://", true).unwrap(), "app:/");
+        assert_eq!(
+            normalize_url_prefix("https://example.com/assets/", true).unwrap(),
+            "https://example.com/assets"
+        );
+        assert_eq!(normalize_url_prefix("static/", false).unwrap(), "static");
+        assert!(normalize_url_prefix("static/", true).is_err());
+    }
+
+    #[test]
+    fn test_check_url_prefix() {
+        let suggestion = |prefix| check_url_prefix(prefix).map(|(_, suggestion)| suggestion);
+        assert_eq!(suggestion("~"), None);
+        assert_eq!(suggestion("~/static"), None);
+        assert_eq!(suggestion("app://"), None);
+        assert_eq!(suggestion("static/js").as_deref(), Some("~/static/js"));
+        assert_eq!(suggestion("/static").as_deref(), Some("~/static"));
+        assert_eq!(suggestion("./static").as_deref(), Some("~/static"));
+        assert_eq!(suggestion("").as_deref(), Some("~"));
+        assert_eq!(suggestion("~static").as_deref(), Some("~/static"));
+        assert_eq!(suggestion("~//static").as_deref(), Some("~/static"));
+        assert_eq!(suggestion(" ~/static").as_deref(), Some("~/static"));
+        assert_eq!(suggestion("~\\static\\").as_deref(), Some("~/static"));
+    }
+
     #[test]
     fn test_url_to_bundle_path() {
         assert_eq!(url_to_bundle_path("~/bar").unwrap(), "_/_/bar");
@@ -661,6 +1097,8 @@ mod tests {
             wait: false,
             max_wait: DEFAULT_MAX_WAIT,
             dedupe: true,
+            prefetch_missing_chunks: false,
+            chunked_threshold: None,
             chunk_upload_options: None,
         };
 
@@ -1,6 +1,56 @@
 use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use anyhow::Result;
 use chrono::Duration;
+use serde::Serialize;
+
+/// Whether `--no-newline` was passed.
+static NO_NEWLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_no_newline(no_newline: bool) {
+    NO_NEWLINE.store(no_newline, Ordering::Relaxed);
+}
+
+/// Whether `--output yaml` was passed.
+static YAML_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_yaml_output(yaml_output: bool) {
+    YAML_OUTPUT.store(yaml_output, Ordering::Relaxed);
+}
+
+fn finish_line() -> Result<()> {
+    let mut stdout = io::stdout();
+    if !NO_NEWLINE.load(Ordering::Relaxed) {
+        writeln!(stdout)?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Prints the single value a command results in, such as a release name.
+///
+/// The trailing newline is left out with `--no-newline`, so that shell
+/// scripts can capture the value as is.
+pub fn print_value<D: fmt::Display>(value: D) -> Result<()> {
+    print!("{value}");
+    finish_line()
+}
+
+/// Prints a single pretty-printed JSON document, or a YAML document with
+/// `--output yaml`, followed by a newline unless `--no-newline` was passed.
+///
+/// This is the only output that `--output` applies to, so commands with
+/// structured output should print it through this function.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    if YAML_OUTPUT.load(Ordering::Relaxed) {
+        print!("{}", serde_yaml::to_string(value)?.trim_end());
+    } else {
+        serde_json::to_writer_pretty(&mut io::stdout(), value)?;
+    }
+    finish_line()
+}
 
 /// Helper for formatting durations.
 pub struct HumanDuration(pub Duration);
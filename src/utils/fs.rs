@@ -3,16 +3,132 @@ use std::fs;
 use std::io;
 use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use flate2::read::GzDecoder;
-use log::error;
+use lazy_static::lazy_static;
+use log::{debug, error};
+use parking_lot::Mutex;
 use sha1_smol::{Digest, Sha1};
 use uuid::Uuid;
+use zip::write::FileOptions;
 
 pub trait SeekRead: Seek + Read {}
 impl<T: Seek + Read> SeekRead for T {}
 
+/// Whether temporary files should be preserved for debugging.
+static KEEP_TEMP: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    /// All temporary files and directories that are currently alive.  These are
+    /// removed by the signal handler if the process is interrupted.
+    static ref TEMP_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+/// Preserves all temporary files instead of deleting them.  Their locations
+/// are printed when they would otherwise have been removed.
+pub fn set_keep_temp(keep: bool) {
+    KEEP_TEMP.store(keep, Ordering::Relaxed);
+}
+
+fn track_temp_path(path: &Path) {
+    TEMP_PATHS.lock().push(path.to_owned());
+}
+
+/// Stops tracking the given temporary path and returns whether it should
+/// be deleted.
+fn release_temp_path(path: &Path) -> bool {
+    TEMP_PATHS.lock().retain(|p| p != path);
+    if KEEP_TEMP.load(Ordering::Relaxed) {
+        eprintln!("Keeping temporary file {}", path.display());
+        false
+    } else {
+        true
+    }
+}
+
+/// Removes all tracked temporary paths.  This is called from the signal
+/// handler, so it must never block on the lock.
+fn remove_tracked_temp_paths() {
+    if KEEP_TEMP.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(paths) = TEMP_PATHS.try_lock() {
+        for path in paths.iter() {
+            if path.is_dir() {
+                fs::remove_dir_all(path).ok();
+            } else {
+                fs::remove_file(path).ok();
+            }
+        }
+    }
+}
+
+/// Write end of the pipe used to forward signals to the cleanup thread.
+#[cfg(unix)]
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+/// Installs a handler for SIGINT and SIGTERM that removes all temporary files
+/// before terminating the process.
+///
+/// The signal handler itself only writes the signal number into a pipe, which
+/// is async-signal-safe. The actual cleanup runs on a regular thread.
+#[cfg(unix)]
+pub fn install_temp_cleanup_handler() {
+    extern "C" fn handle_signal(signal: libc::c_int) {
+        let fd = SIGNAL_PIPE.load(Ordering::Relaxed);
+        let byte = signal as u8;
+        unsafe {
+            libc::write(fd, &byte as *const u8 as *const libc::c_void, 1);
+        }
+    }
+
+    let mut fds = [0 as libc::c_int; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        debug!("Failed to create pipe for cleanup handler");
+        return;
+    }
+    let [read_fd, write_fd] = fds;
+    SIGNAL_PIPE.store(write_fd, Ordering::Relaxed);
+
+    let spawned = std::thread::Builder::new()
+        .name("temp-cleanup".into())
+        .spawn(move || {
+            let mut byte = 0u8;
+            let read = unsafe { libc::read(read_fd, &mut byte as *mut u8 as *mut libc::c_void, 1) };
+            if read != 1 {
+                return;
+            }
+            remove_tracked_temp_paths();
+            // Restore the default disposition and re-raise so that the exit
+            // status still reflects the signal.
+            let signal = libc::c_int::from(byte);
+            unsafe {
+                libc::signal(signal, libc::SIG_DFL);
+                libc::raise(signal);
+            }
+        });
+    if spawned.is_err() {
+        debug!("Failed to spawn cleanup thread");
+        return;
+    }
+
+    let handler = handle_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        if unsafe { libc::signal(signal, handler) } == libc::SIG_ERR {
+            debug!("Failed to install cleanup handler for signal {}", signal);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_temp_cleanup_handler() {}
+
 /// Helper for temporary dicts
 #[derive(Debug)]
 pub struct TempDir {
@@ -25,6 +141,7 @@ impl TempDir {
         let mut path = env::temp_dir();
         path.push(Uuid::new_v4().as_hyphenated().to_string());
         fs::create_dir(&path)?;
+        track_temp_path(&path);
         Ok(TempDir { path })
     }
 
@@ -36,7 +153,9 @@ impl TempDir {
 
 impl Drop for TempDir {
     fn drop(&mut self) {
-        let _ = fs::remove_dir_all(&self.path);
+        if release_temp_path(&self.path) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
     }
 }
 
@@ -53,6 +172,7 @@ impl TempFile {
         path.push(Uuid::new_v4().as_hyphenated().to_string());
 
         let tf = TempFile { path };
+        track_temp_path(&tf.path);
         tf.open()?;
         Ok(tf)
     }
@@ -63,6 +183,7 @@ impl TempFile {
         destination.push(Uuid::new_v4().as_hyphenated().to_string());
 
         fs::rename(&path, &destination)?;
+        track_temp_path(&destination);
         Ok(TempFile { path: destination })
     }
 
@@ -92,6 +213,9 @@ impl TempFile {
 impl Drop for TempFile {
     #[cfg(not(windows))]
     fn drop(&mut self) {
+        if !release_temp_path(&self.path) {
+            return;
+        }
         let result = fs::remove_file(&self.path);
         if let Err(e) = result {
             error!(
@@ -104,6 +228,9 @@ impl Drop for TempFile {
 
     #[cfg(windows)]
     fn drop(&mut self) {
+        if !release_temp_path(&self.path) {
+            return;
+        }
         // On Windows, we open the file handle to set "FILE_FLAG_DELETE_ON_CLOSE" so that it will be closed
         // when the last open handle to this file is gone.
         use std::os::windows::prelude::*;
@@ -123,6 +250,45 @@ impl Drop for TempFile {
     }
 }
 
+/// Guard for an output file that is still being written.
+///
+/// Unless `persist` is called, the file is removed when the guard is dropped
+/// or the process is interrupted, so that no half-written output remains.
+#[derive(Debug)]
+pub struct PartialFile {
+    path: PathBuf,
+}
+
+impl PartialFile {
+    /// Starts tracking the file at the given path.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_owned();
+        track_temp_path(&path);
+        PartialFile { path }
+    }
+
+    /// Returns the path to the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Marks the file as complete so that it is kept.
+    pub fn persist(self) -> PathBuf {
+        TEMP_PATHS.lock().retain(|p| p != &self.path);
+        let path = self.path.clone();
+        std::mem::forget(self);
+        path
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if release_temp_path(&self.path) {
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
 /// Checks if a path is writable.
 #[cfg(not(feature = "managed"))]
 pub fn is_writable<P: AsRef<Path>>(path: P) -> bool {
@@ -196,6 +362,13 @@ pub fn is_gzip_compressed(slice: &[u8]) -> bool {
     slice.starts_with(&GZIP_MAGIC)
 }
 
+/// Options for entries of ZIP archives.  The modification time is fixed to
+/// `1980-01-01T00:00:00`, like in source bundles, so that the same inputs
+/// always produce the same archive.
+pub fn zip_file_options() -> FileOptions {
+    FileOptions::default().last_modified_time(zip::DateTime::default())
+}
+
 /// Gets gzip decompressed contents.
 pub fn decompress_gzip_content(slice: &[u8]) -> Result<Vec<u8>> {
     let mut decoder = GzDecoder::new(slice);
@@ -214,6 +387,92 @@ pub fn path_as_url(path: &Path) -> String {
     path.display().to_string()
 }
 
+/// Whether `--no-lock` was passed.
+static NO_LOCK: AtomicBool = AtomicBool::new(false);
+/// The `--lock-timeout` in seconds.
+static LOCK_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_LOCK_TIMEOUT_SECS);
+
+/// How long to wait for the cache lock of another process by default.
+pub const DEFAULT_LOCK_TIMEOUT_SECS: u64 = 60;
+
+/// Configures the locking of cache directories.
+pub fn set_cache_locking(enabled: bool, timeout: Duration) {
+    NO_LOCK.store(!enabled, Ordering::Relaxed);
+    LOCK_TIMEOUT_SECS.store(timeout.as_secs(), Ordering::Relaxed);
+}
+
+/// An exclusive advisory lock on a cache directory, held until dropped.
+///
+/// The lock is taken on a `.lock` file in the directory, so that concurrent
+/// sentry-cli processes sharing the directory do not overwrite each other's
+/// state.
+pub struct CacheLock {
+    _file: Option<fs::File>,
+}
+
+impl CacheLock {
+    /// Locks the given cache directory, waiting up to `--lock-timeout` for
+    /// other processes to release it.
+    pub fn acquire(dir: &Path) -> Result<CacheLock> {
+        if NO_LOCK.load(Ordering::Relaxed) {
+            return Ok(CacheLock { _file: None });
+        }
+        let timeout = Duration::from_secs(LOCK_TIMEOUT_SECS.load(Ordering::Relaxed));
+        CacheLock::acquire_with_timeout(dir, timeout)
+    }
+
+    fn acquire_with_timeout(dir: &Path, timeout: Duration) -> Result<CacheLock> {
+        let path = dir.join(".lock");
+        let file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        let started = Instant::now();
+        while !try_lock_file(&file)? {
+            if started.elapsed() >= timeout {
+                bail!(
+                    "Timed out after {}s waiting for another sentry-cli process to release \
+                     the lock on {}.  Increase --lock-timeout to wait longer, or pass --no-lock \
+                     if the directory is not shared.",
+                    timeout.as_secs(),
+                    dir.display()
+                );
+            }
+            debug!("Waiting for the lock on {}", dir.display());
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        Ok(CacheLock { _file: Some(file) })
+    }
+}
+
+/// Tries to lock a file exclusively and returns whether it succeeded.  The
+/// lock is released when the file is closed.
+#[cfg(unix)]
+fn try_lock_file(file: &fs::File) -> Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+    let err = io::Error::last_os_error();
+    if err.kind() == io::ErrorKind::WouldBlock {
+        Ok(false)
+    } else {
+        Err(err.into())
+    }
+}
+
+#[cfg(not(unix))]
+fn try_lock_file(_file: &fs::File) -> Result<bool> {
+    debug!("Cache locking is not supported on this platform");
+    Ok(true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,6 +493,61 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn partial_file_goes_away_unless_persisted() -> io::Result<()> {
+        let dir = TempDir::create()?;
+
+        let dropped = PartialFile::new(dir.path().join("dropped"));
+        fs::write(dropped.path(), b"partial")?;
+        let dropped_path = dropped.path().to_owned();
+        drop(dropped);
+        assert!(!dropped_path.exists(), "Partial file didn't get deleted");
+
+        let persisted = PartialFile::new(dir.path().join("persisted"));
+        fs::write(persisted.path(), b"complete")?;
+        let persisted_path = persisted.persist();
+        assert!(persisted_path.exists(), "Persisted file got deleted");
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cache_lock_is_exclusive() -> Result<()> {
+        let dir = TempDir::create()?;
+
+        let lock = CacheLock::acquire_with_timeout(dir.path(), Duration::ZERO)?;
+        let err = CacheLock::acquire_with_timeout(dir.path(), Duration::ZERO)
+            .err()
+            .expect("Second lock should time out");
+        assert!(err.to_string().starts_with("Timed out"));
+
+        drop(lock);
+        CacheLock::acquire_with_timeout(dir.path(), Duration::ZERO)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn zip_entries_have_fixed_mtime() -> Result<()> {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        writer.start_file("file.txt", zip_file_options())?;
+        writer.write_all(b"contents")?;
+        let mut archive = zip::ZipArchive::new(writer.finish()?)?;
+
+        let mtime = archive.by_index(0)?.last_modified();
+        assert_eq!(
+            (mtime.year(), mtime.month(), mtime.day()),
+            (1980, 1, 1),
+            "entries should not carry the current time"
+        );
+        assert_eq!((mtime.hour(), mtime.minute(), mtime.second()), (0, 0, 0));
+
+        Ok(())
+    }
+
     #[test]
     fn tempfile_goes_away_with_longer_living_handle() -> io::Result<()> {
         let tempfile = TempFile::create()?;
@@ -4,6 +4,8 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 // Http statuses
+pub const HTTP_STATUS_429_TOO_MANY_REQUESTS: u32 = 429;
+pub const HTTP_STATUS_500_INTERNAL_SERVER_ERROR: u32 = 500;
 pub const HTTP_STATUS_502_BAD_GATEWAY: u32 = 502;
 pub const HTTP_STATUS_503_SERVICE_UNAVAILABLE: u32 = 503;
 pub const HTTP_STATUS_504_GATEWAY_TIMEOUT: u32 = 504;
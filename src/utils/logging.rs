@@ -23,8 +23,6 @@ pub fn set_quiet_mode(is_quiet: bool) {
     QUIET_MODE.store(is_quiet, Ordering::Relaxed);
 }
 
-// NOTE: Remove `allow`s after first use.
-#[allow(unused_macros)]
 macro_rules! quiet_println {
     ($($tt:tt)*) => {{
         if !crate::utils::logging::is_quiet_mode() {
@@ -32,7 +30,6 @@ macro_rules! quiet_println {
         }
     }};
 }
-#[allow(unused_imports)]
 pub(crate) use quiet_println;
 
 // NOTE: Remove `allow`s after first use.
@@ -112,7 +109,9 @@ impl log::Log for Logger {
             return;
         }
 
-        if let Some(pb) = get_progress_bar() {
+        // Hidden progress bars swallow printed lines, so only route through
+        // the progress bar if it is actually drawn.
+        if let Some(pb) = get_progress_bar().filter(|pb| !pb.is_hidden()) {
             pb.println(msg);
         } else {
             eprintln!("{msg}");
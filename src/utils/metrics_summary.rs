@@ -0,0 +1,36 @@
+//! Counters for the summary line printed after upload commands.
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static REQUESTS: AtomicU64 = AtomicU64::new(0);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+static UPLOADED_BYTES: AtomicU64 = AtomicU64::new(0);
+static DEDUPED_CHUNKS: AtomicU64 = AtomicU64::new(0);
+
+/// Records a request sent to the server, including every retry.
+pub fn record_request(uploaded_bytes: u64) {
+    REQUESTS.fetch_add(1, Ordering::Relaxed);
+    UPLOADED_BYTES.fetch_add(uploaded_bytes, Ordering::Relaxed);
+}
+
+/// Records that a request is sent again.
+pub fn record_retry() {
+    RETRIES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Records chunks that were not uploaded because the server already had them.
+pub fn record_deduped_chunks(count: usize) {
+    DEDUPED_CHUNKS.fetch_add(count as u64, Ordering::Relaxed);
+}
+
+/// Formats all counters as a single line of `key=value` pairs.
+pub fn format_metrics_summary(duration: Duration) -> String {
+    format!(
+        "sentry-cli metrics: requests={} retries={} uploaded_bytes={} deduped_chunks={} duration_ms={}",
+        REQUESTS.load(Ordering::Relaxed),
+        RETRIES.load(Ordering::Relaxed),
+        UPLOADED_BYTES.load(Ordering::Relaxed),
+        DEDUPED_CHUNKS.load(Ordering::Relaxed),
+        duration.as_millis()
+    )
+}
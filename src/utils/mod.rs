@@ -7,6 +7,7 @@ pub mod chunks;
 pub mod cordova;
 pub mod dif;
 pub mod dif_upload;
+pub mod envelopes;
 pub mod event;
 pub mod file_search;
 pub mod file_upload;
@@ -15,12 +16,16 @@ pub mod fs;
 pub mod http;
 pub mod logging;
 pub mod metrics;
+pub mod metrics_summary;
+pub mod path_list;
 pub mod progress;
 pub mod proguard_upload;
+pub mod rate_limit;
 pub mod releases;
 pub mod retry;
 pub mod sourcemaps;
 pub mod system;
+pub mod text_encoding;
 pub mod ui;
 pub mod update;
 pub mod value_parsers;
@@ -0,0 +1,233 @@
+//! Reads explicit lists of input paths for the upload commands.
+use std::fs;
+use std::io::{self, Read};
+use std::iter;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::ArgMatches;
+use console::style;
+use glob::{glob_with, MatchOptions};
+
+/// Returns the paths given with `--stdin-paths` or `--paths-from`, if any.
+///
+/// Entries are separated by newlines, or by NUL bytes if `--null` is set.
+/// Paths that do not exist are reported and cause an error unless
+/// `--allow-missing` is set, in which case they are skipped.  Directories are
+/// reported and skipped, except for `.dSYM` bundles, which are directories
+/// but contain a single debug information file.
+pub fn get_path_list(matches: &ArgMatches) -> Result<Option<Vec<PathBuf>>> {
+    let contents = if matches.get_flag("stdin_paths") {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .context("Failed to read paths from stdin")?;
+        contents
+    } else if let Some(path) = matches.get_one::<String>("paths_from") {
+        fs::read_to_string(path).with_context(|| format!("Failed to read paths from {path}"))?
+    } else {
+        return Ok(None);
+    };
+
+    let paths = parse_path_list(&contents, matches.get_flag("null"));
+    let (existing, missing): (Vec<_>, Vec<_>) = paths.into_iter().partition(|p| p.exists());
+
+    for path in &missing {
+        eprintln!(
+            "{} {}: {} does not exist",
+            style(">").dim(),
+            style("Warning").red(),
+            path.display()
+        );
+    }
+
+    if !missing.is_empty() && !matches.get_flag("allow_missing") {
+        bail!(
+            "{} listed {} not exist (use --allow-missing to skip them)",
+            missing.len(),
+            match missing.len() {
+                1 => "path does",
+                _ => "paths do",
+            }
+        );
+    }
+
+    Ok(Some(
+        existing
+            .into_iter()
+            .filter(|path| {
+                let is_file = path.is_file() || (path.is_dir() && is_dsym_bundle(path));
+                if !is_file {
+                    eprintln!(
+                        "{} {}: {} is not a file, skipping",
+                        style(">").dim(),
+                        style("Warning").red(),
+                        path.display()
+                    );
+                }
+                is_file
+            })
+            .collect(),
+    ))
+}
+
+/// Returns whether the path names a `.dSYM` bundle.
+fn is_dsym_bundle(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("dsym"))
+}
+
+/// Expands glob patterns in the given paths.
+///
+/// Patterns support `**` to match any number of directories and braces such as
+/// `{a,b}` to match alternatives.  Paths that exist or contain no glob
+/// characters are returned unchanged.  A pattern that matches nothing causes an
+/// error unless `allow_empty` is set, in which case it is reported and skipped.
+pub fn expand_glob_paths<I>(paths: I, allow_empty: bool) -> Result<Vec<PathBuf>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut expanded = vec![];
+    for path in paths {
+        let path = path.as_ref();
+        if !path.contains(['*', '?', '[', '{']) || Path::new(path).exists() {
+            expanded.push(PathBuf::from(path));
+            continue;
+        }
+
+        let mut matched = false;
+        for pattern in expand_braces(path) {
+            let entries = glob_with(&pattern, MatchOptions::new())
+                .with_context(|| format!("Invalid glob pattern `{path}`"))?;
+            for entry in entries.flatten() {
+                matched = true;
+                if !expanded.contains(&entry) {
+                    expanded.push(entry);
+                }
+            }
+        }
+
+        if !matched {
+            if !allow_empty {
+                bail!("{path} did not match any files (use --allow-empty to skip it)");
+            }
+            eprintln!(
+                "{} {}: {} did not match any files",
+                style(">").dim(),
+                style("Warning").red(),
+                path
+            );
+        }
+    }
+    Ok(expanded)
+}
+
+/// Expands the first group of alternatives in braces, and then the groups in
+/// the results.  Braces without a comma are kept as they are.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let mut search_from = 0;
+    while let Some(offset) = pattern[search_from..].find('{') {
+        let start = search_from + offset;
+        let mut depth = 0;
+        let mut commas = vec![];
+        let mut end = None;
+        for (index, c) in pattern[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(start + index);
+                        break;
+                    }
+                }
+                ',' if depth == 1 => commas.push(start + index),
+                _ => {}
+            }
+        }
+
+        let Some(end) = end.filter(|_| !commas.is_empty()) else {
+            search_from = start + 1;
+            continue;
+        };
+
+        let (prefix, suffix) = (&pattern[..start], &pattern[end + 1..]);
+        let mut alternatives = vec![];
+        let mut from = start + 1;
+        for to in commas.into_iter().chain(iter::once(end)) {
+            let alternative = format!("{prefix}{}{suffix}", &pattern[from..to]);
+            alternatives.extend(expand_braces(&alternative));
+            from = to + 1;
+        }
+        return alternatives;
+    }
+    vec![pattern.to_owned()]
+}
+
+/// Splits the contents of a path list into individual paths.
+fn parse_path_list(contents: &str, null_separated: bool) -> Vec<PathBuf> {
+    let separator = if null_separated { '\0' } else { '\n' };
+    contents
+        .split(separator)
+        .map(|line| {
+            if null_separated {
+                line
+            } else {
+                line.trim_end_matches('\r')
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_list_newlines() {
+        assert_eq!(
+            parse_path_list("a.js\r\nb/c.js\n\nd e.js\n", false),
+            vec![
+                PathBuf::from("a.js"),
+                PathBuf::from("b/c.js"),
+                PathBuf::from("d e.js")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_dsym_bundle() {
+        assert!(is_dsym_bundle(Path::new("build/App.app.dSYM")));
+        assert!(is_dsym_bundle(Path::new("build/App.dsym")));
+        assert!(!is_dsym_bundle(Path::new("build/App.app")));
+        assert!(!is_dsym_bundle(Path::new("build/dSYM")));
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(
+            expand_braces("build/{a,b{c,d}}/*.{dSYM,so}"),
+            vec![
+                "build/a/*.dSYM",
+                "build/a/*.so",
+                "build/bc/*.dSYM",
+                "build/bc/*.so",
+                "build/bd/*.dSYM",
+                "build/bd/*.so",
+            ]
+        );
+        assert_eq!(expand_braces("a{b}c{,d}"), vec!["a{b}c", "a{b}cd"]);
+        assert_eq!(expand_braces("a{b,c"), vec!["a{b,c"]);
+    }
+
+    #[test]
+    fn test_parse_path_list_null() {
+        assert_eq!(
+            parse_path_list("a.js\0with\nnewline.js\0", true),
+            vec![PathBuf::from("a.js"), PathBuf::from("with\nnewline.js")]
+        );
+    }
+}
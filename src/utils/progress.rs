@@ -2,12 +2,49 @@ use parking_lot::RwLock;
 use std::env;
 use std::ops::Deref;
 use std::sync::Arc;
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use console::style;
+use indicatif::HumanBytes;
+use lazy_static::lazy_static;
 
 use crate::utils::logging;
 
 pub use indicatif::ProgressStyle;
 
+/// The interval at which plain progress output prints status lines.
+const PLAIN_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Controls how the progress of long running operations is rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressOutput {
+    /// Use plain output if stdout is not a terminal, an animated bar otherwise.
+    Auto,
+    /// Render an animated progress bar.
+    Bar,
+    /// Print throttled one-line status updates.
+    Plain,
+}
+
+impl ProgressOutput {
+    fn is_plain(self) -> bool {
+        match self {
+            ProgressOutput::Auto => !console::user_attended(),
+            ProgressOutput::Bar => false,
+            ProgressOutput::Plain => true,
+        }
+    }
+}
+
+lazy_static! {
+    static ref PROGRESS_OUTPUT: RwLock<ProgressOutput> = RwLock::new(ProgressOutput::Auto);
+}
+
+pub fn set_progress_output(output: ProgressOutput) {
+    *PROGRESS_OUTPUT.write() = output;
+}
+
 pub fn is_progress_bar_visible() -> bool {
     env::var("SENTRY_NO_PROGRESS_BAR") != Ok("1".into())
 }
@@ -15,19 +52,65 @@ pub fn is_progress_bar_visible() -> bool {
 pub struct ProgressBar {
     inner: Arc<indicatif::ProgressBar>,
     start: Instant,
+    plain: bool,
 }
 
 impl ProgressBar {
     pub fn new(len: usize) -> Self {
-        if is_progress_bar_visible() {
-            indicatif::ProgressBar::new(len as u64).into()
-        } else {
+        Self::with_unit(len as u64, false)
+    }
+
+    /// Creates a progress bar that counts bytes.
+    pub fn new_bytes(len: u64) -> Self {
+        Self::with_unit(len, true)
+    }
+
+    fn with_unit(len: u64, bytes: bool) -> Self {
+        if !is_progress_bar_visible() {
             Self::hidden()
+        } else if PROGRESS_OUTPUT.read().is_plain() {
+            Self::plain(len, bytes)
+        } else {
+            indicatif::ProgressBar::new(len).into()
+        }
+    }
+
+    /// Creates a hidden progress bar that periodically prints its status on
+    /// a single line.
+    ///
+    /// Log output is not routed through the progress bar in this mode, so
+    /// interleaved log lines stay intact.
+    fn plain(len: u64, bytes: bool) -> Self {
+        let inner = Arc::new(indicatif::ProgressBar::hidden());
+        inner.set_length(len);
+
+        let weak = Arc::downgrade(&inner);
+        thread::spawn(move || {
+            let mut last_pos = 0;
+            loop {
+                thread::sleep(PLAIN_PROGRESS_INTERVAL);
+                let pb = match weak.upgrade() {
+                    Some(pb) if !pb.is_finished() => pb,
+                    _ => break,
+                };
+
+                let pos = pb.position();
+                if pos != last_pos {
+                    eprintln!("{}", format_plain_progress(pos, len, bytes));
+                    last_pos = pos;
+                }
+            }
+        });
+
+        ProgressBar {
+            inner,
+            start: Instant::now(),
+            plain: true,
         }
     }
 
     pub fn new_spinner() -> Self {
-        if is_progress_bar_visible() {
+        if is_progress_bar_visible() && !PROGRESS_OUTPUT.read().is_plain() {
             indicatif::ProgressBar::new_spinner().into()
         } else {
             Self::hidden()
@@ -42,6 +125,12 @@ impl ProgressBar {
         let dur = self.start.elapsed();
         // We could use `dur.as_secs_f64()`, but its unnecessarily precise (micros). Millis are enough for our purpose.
         let msg = format!("{} completed in {}s", op, dur.as_millis() as f64 / 1000.0);
+        if self.plain {
+            self.inner.finish();
+            eprintln!("{} {}", style(">").dim(), msg);
+            return;
+        }
+
         let progress_style = ProgressStyle::default_bar().template("{prefix:.dim} {msg}");
         self.inner.set_style(progress_style);
         self.inner.set_prefix(">");
@@ -62,10 +151,21 @@ impl From<indicatif::ProgressBar> for ProgressBar {
         ProgressBar {
             inner,
             start: Instant::now(),
+            plain: false,
         }
     }
 }
 
+/// Formats a single status line for plain progress output.
+fn format_plain_progress(pos: u64, len: u64, bytes: bool) -> String {
+    let percent = (pos * 100).checked_div(len).unwrap_or(0);
+    if bytes {
+        format!("[{}%] {}/{}", percent, HumanBytes(pos), HumanBytes(len))
+    } else {
+        format!("[{percent}%] {pos}/{len}")
+    }
+}
+
 impl Deref for ProgressBar {
     type Target = indicatif::ProgressBar;
 
@@ -105,3 +205,18 @@ impl ProgressBarMode {
         rv
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_plain_progress() {
+        assert_eq!(format_plain_progress(12, 27, false), "[44%] 12/27");
+        assert_eq!(format_plain_progress(0, 0, false), "[0%] 0/0");
+        assert_eq!(
+            format_plain_progress(512 * 1024, 2048 * 1024, true),
+            "[25%] 512.00KB/2.00MB"
+        );
+    }
+}
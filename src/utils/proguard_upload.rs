@@ -12,13 +12,11 @@ use indicatif::ProgressStyle;
 use sha1_smol::Digest;
 
 use super::chunks;
-use super::chunks::Chunk;
+use super::chunks::{AssemblePolling, Chunk};
 use super::fs::get_sha1_checksums;
 use crate::api::{Api, ChunkUploadOptions, ChunkedDifRequest, ChunkedFileState};
 use crate::commands::upload_proguard::MappingRef;
-
-/// How often to poll the server for the status of the assembled mappings.
-const ASSEMBLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+use crate::config::Config;
 
 /// How long to wait for the server to assemble the mappings before giving up.
 // 120 seconds was chosen somewhat arbitrarily, but in my testing, assembly
@@ -101,7 +99,11 @@ pub fn chunk_upload(
 
     let assemble_request = chunked_mappings.iter().map(to_assemble).collect();
     let start = Instant::now();
-    while Instant::now().duration_since(start) < ASSEMBLE_POLL_TIMEOUT {
+    let polling = AssemblePolling::from_config(&Config::current());
+    let mut attempts = 0;
+    while Instant::now().duration_since(start) < ASSEMBLE_POLL_TIMEOUT && polling.may_poll(attempts)
+    {
+        attempts += 1;
         let all_assembled = Api::current()
             .authenticated()?
             .assemble_difs(org, project, &assemble_request)?
@@ -122,7 +124,7 @@ pub fn chunk_upload(
             return Ok(());
         }
 
-        thread::sleep(ASSEMBLE_POLL_INTERVAL);
+        thread::sleep(polling.interval);
     }
 
     anyhow::bail!("Timed out waiting for server to assemble uploaded mappings.")
@@ -0,0 +1,60 @@
+//! Client-side throttling of requests, as configured with `--rate-limit`.
+use std::thread;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// A token bucket holding a single token, which spaces out requests evenly
+/// instead of allowing bursts.  It is shared by all threads of a command.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    /// The time at which the next token is available.
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter that allows the given number of requests per second.
+    pub fn new(per_second: f64) -> RateLimiter {
+        RateLimiter {
+            interval: Duration::from_secs_f64(1.0 / per_second),
+            next: Mutex::new(None),
+        }
+    }
+
+    /// Blocks until the next request may be sent.
+    pub fn wait(&self) {
+        let delay = self.reserve(Instant::now());
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+
+    /// Takes the next token and returns how long to wait after `now` until it
+    /// is available.  Tokens do not accumulate while no requests are sent.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut next = self.next.lock();
+        let at = next.map_or(now, |next| next.max(now));
+        *next = Some(at + self.interval);
+        at - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve() {
+        let limiter = RateLimiter::new(4.0);
+        let start = Instant::now();
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::from_millis(250));
+        assert_eq!(limiter.reserve(start), Duration::from_millis(500));
+
+        // After an idle period, only a single request is sent right away.
+        let later = start + Duration::from_secs(10);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::from_millis(250));
+    }
+}
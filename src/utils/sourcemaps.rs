@@ -11,6 +11,8 @@ use anyhow::{anyhow, bail, Context, Error, Result};
 use console::style;
 use indicatif::ProgressStyle;
 use log::{debug, info, warn};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
 use sentry::types::DebugId;
 use sha1_smol::Digest;
 use sourcemap::SourceMap;
@@ -20,16 +22,21 @@ use symbolic::debuginfo::js::{
 use symbolic::debuginfo::sourcebundle::SourceFileType;
 use url::Url;
 
-use crate::api::Api;
+use crate::api::{Api, ChunkUploadCapability};
 use crate::utils::file_search::ReleaseFileMatch;
 use crate::utils::file_upload::{
     initialize_legacy_release_upload, FileUpload, SourceFile, SourceFiles, UploadContext,
 };
-use crate::utils::logging::is_quiet_mode;
+use crate::utils::logging::{is_quiet_mode, quiet_println};
 use crate::utils::progress::ProgressBar;
 use crate::utils::sourcemaps::inject::InjectReport;
+use crate::utils::sourcemaps::source_paths::RewriteSourcesOptions;
+use crate::utils::sourcemaps::upload_report::UploadReport;
 
 pub mod inject;
+pub mod source_paths;
+pub mod upload_report;
+pub mod webpack;
 
 /// The string prefix denoting a data URL.
 ///
@@ -198,6 +205,7 @@ pub struct SourceMapProcessor {
     sources: SourceFiles,
     sourcemap_references: HashMap<String, Option<SourceMapReference>>,
     debug_ids: HashMap<String, DebugId>,
+    dist_pattern: Option<Regex>,
 }
 
 fn is_hermes_bytecode(slice: &[u8]) -> bool {
@@ -241,6 +249,36 @@ fn is_remote_sourcemap(url: &str) -> bool {
     is_remote_url(url) || is_url_path(url)
 }
 
+/// Returns why the artifact type of a file is a guess, if it is.  Files that
+/// are neither source maps nor RAM bundles are uploaded as minified sources,
+/// which only match at symbolication time if they are scripts.
+fn uncertain_artifact_type(source: &SourceFile) -> Option<&'static str> {
+    if source.ty != SourceFileType::MinifiedSource {
+        return None;
+    }
+    if url_matches_extension(&source.url, &["map"]) {
+        Some("file has a .map extension but is not a valid source map")
+    } else if str::from_utf8(&source.contents).is_err() {
+        Some("file is not a text file, so its artifact type could not be determined")
+    } else {
+        None
+    }
+}
+
+/// The name of an artifact type in reports, pluralized for `count`.
+fn artifact_type_name(ty: SourceFileType, count: usize) -> &'static str {
+    match (ty, count) {
+        (SourceFileType::Source, 1) => "source",
+        (SourceFileType::Source, _) => "sources",
+        (SourceFileType::MinifiedSource, 1) => "minified source",
+        (SourceFileType::MinifiedSource, _) => "minified sources",
+        (SourceFileType::SourceMap, 1) => "source map",
+        (SourceFileType::SourceMap, _) => "source maps",
+        (SourceFileType::IndexedRamBundle, 1) => "indexed RAM bundle",
+        (SourceFileType::IndexedRamBundle, _) => "indexed RAM bundles",
+    }
+}
+
 impl SourceMapProcessor {
     /// Creates a new sourcemap validator.
     pub fn new() -> SourceMapProcessor {
@@ -249,9 +287,17 @@ impl SourceMapProcessor {
             sources: SourceFiles::new(),
             sourcemap_references: HashMap::new(),
             debug_ids: HashMap::new(),
+            dist_pattern: None,
         }
     }
 
+    /// Derives the dist of every file from its file name, using the `dist`
+    /// capture group of the given pattern.  Files that do not match are
+    /// uploaded with the dist of the upload context.
+    pub fn dist_from_filename(&mut self, pattern: Regex) {
+        self.dist_pattern = Some(pattern);
+    }
+
     /// Adds a new file for processing.
     pub fn add(&mut self, url: &str, file: ReleaseFileMatch) -> Result<()> {
         self.pending_sources.insert((url.to_string(), file));
@@ -270,7 +316,7 @@ impl SourceMapProcessor {
         let pb = ProgressBar::new(self.pending_sources.len());
         pb.set_style(progress_style);
 
-        println!(
+        quiet_println!(
             "{} Analyzing {} sources",
             style(">").dim(),
             style(self.pending_sources.len()).yellow()
@@ -332,6 +378,12 @@ impl SourceMapProcessor {
                 messages: vec![],
                 already_uploaded: false,
             };
+            if let Some(reason) = uncertain_artifact_type(&source_file) {
+                source_file.warn(format!(
+                    "{reason}, uploading it as a {}",
+                    artifact_type_name(ty, 1)
+                ));
+            }
 
             if let Some(debug_id) = debug_id {
                 source_file.set_debug_id(debug_id.to_string());
@@ -342,6 +394,43 @@ impl SourceMapProcessor {
             pb.inc(1);
         }
         pb.finish_with_duration("Analyzing");
+        self.classify_original_sources();
+    }
+
+    /// Classifies scripts that one of the source maps lists as an original
+    /// source as sources rather than minified sources.  Scripts that reference
+    /// a source map or carry a debug id are minified sources themselves and
+    /// keep their type.
+    fn classify_original_sources(&mut self) {
+        let mut original_urls = HashSet::new();
+        for source in self.sources.values() {
+            if source.ty != SourceFileType::SourceMap {
+                continue;
+            }
+            let source_urls: Vec<_> = match sourcemap::decode_slice(&source.contents) {
+                Ok(sourcemap::DecodedMap::Regular(sm)) => sm.sources().map(str::to_owned).collect(),
+                Ok(sourcemap::DecodedMap::Hermes(smh)) => {
+                    smh.sources().map(str::to_owned).collect()
+                }
+                _ => continue,
+            };
+            for source_url in &source_urls {
+                if let Ok(url) = join_url(&source.url, source_url) {
+                    original_urls.insert(url);
+                }
+            }
+        }
+
+        for source in self.sources.values_mut() {
+            if source.ty == SourceFileType::MinifiedSource
+                && original_urls.contains(&source.url)
+                && !self.debug_ids.contains_key(&source.url)
+                && str::from_utf8(&source.contents)
+                    .is_ok_and(|contents| discover_sourcemaps_location(contents).is_none())
+            {
+                source.ty = SourceFileType::Source;
+            }
+        }
     }
 
     /// Collect references to sourcemaps in minified source files
@@ -460,6 +549,28 @@ impl SourceMapProcessor {
         }
     }
 
+    /// Prints how many files of each artifact type are uploaded.
+    fn print_artifact_type_summary(&self) {
+        let mut counts = BTreeMap::new();
+        for source in self.sources.values() {
+            if !source.already_uploaded {
+                *counts.entry(source.ty).or_insert(0) += 1;
+            }
+        }
+        if counts.is_empty() {
+            return;
+        }
+        quiet_println!(
+            "{} Artifact types: {}",
+            style(">").dim(),
+            counts
+                .into_iter()
+                .map(|(ty, count)| format!("{count} {}", artifact_type_name(ty, count)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
     /// Validates all sources within.
     pub fn validate_all(&mut self) -> Result<()> {
         self.flush_pending_sources();
@@ -467,7 +578,7 @@ impl SourceMapProcessor {
         let sources: Vec<&mut SourceFile> = self.sources.values_mut().collect();
         let mut failed = false;
 
-        println!("{} Validating sources", style(">").dim());
+        quiet_println!("{} Validating sources", style(">").dim());
 
         let progress_style = ProgressStyle::default_bar().template(&format!(
             "{} {{msg}}\n{{wide_bar}} {{pos}}/{{len}}",
@@ -505,6 +616,103 @@ impl SourceMapProcessor {
         bail!("Encountered problems when validating source maps.");
     }
 
+    /// Returns the URLs of all source maps that cannot map anything, together
+    /// with the reason.  These are usually produced by a broken build step.
+    pub fn find_empty_sourcemaps(&mut self) -> Vec<(String, &'static str)> {
+        self.flush_pending_sources();
+        let mut rv: Vec<_> = self
+            .sources
+            .values()
+            .filter(|source| source.ty == SourceFileType::SourceMap || source.url.ends_with(".map"))
+            .filter_map(|source| {
+                empty_sourcemap_reason(&source.contents).map(|reason| (source.url.clone(), reason))
+            })
+            .collect();
+        rv.sort();
+        rv
+    }
+
+    /// Extracts source maps embedded as data URLs into separate source map
+    /// files next to their minified sources.  With `strip`, the
+    /// `sourceMappingURL` of each minified source is rewritten to reference
+    /// the new file, which drops the inline map from the uploaded source.
+    pub fn externalize_inline_sourcemaps(&mut self, strip: bool) -> Result<()> {
+        self.flush_pending_sources();
+
+        let mut extracted = Vec::new();
+        for source in self.sources.values() {
+            if source.ty != SourceFileType::MinifiedSource {
+                continue;
+            }
+
+            let Some(location) = str::from_utf8(&source.contents)
+                .ok()
+                .and_then(discover_sourcemaps_location)
+            else {
+                continue;
+            };
+
+            if !location.starts_with("data:") {
+                continue;
+            }
+
+            let contents = decode_data_url(location).with_context(|| {
+                format!("Invalid embedded sourcemap in source file {}", source.url)
+            })?;
+            let sourcemap_url = format!("{}.map", source.url);
+            if self.sources.contains_key(&sourcemap_url) {
+                warn!(
+                    "Not extracting embedded sourcemap of {}, {} already exists",
+                    source.url, sourcemap_url
+                );
+                continue;
+            }
+
+            extracted.push((source.url.clone(), sourcemap_url, contents));
+        }
+
+        if !extracted.is_empty() {
+            quiet_println!(
+                "{} Extracting {} inline source maps",
+                style(">").dim(),
+                style(extracted.len()).yellow()
+            );
+        }
+
+        for (source_url, sourcemap_url, contents) in extracted {
+            let source = self.sources.get_mut(&source_url).unwrap();
+            if strip {
+                let basename = sourcemap_url.rsplit('/').next().unwrap_or(&sourcemap_url);
+                inject::replace_sourcemap_url(&mut source.contents, basename)?;
+            }
+
+            let mut path = source.path.clone().into_os_string();
+            path.push(".map");
+
+            let mut sourcemap_file = SourceFile {
+                url: sourcemap_url.clone(),
+                path: path.into(),
+                contents,
+                ty: SourceFileType::SourceMap,
+                headers: BTreeMap::new(),
+                messages: vec![],
+                already_uploaded: false,
+            };
+
+            if let Some(debug_id) = str::from_utf8(&sourcemap_file.contents)
+                .ok()
+                .and_then(discover_sourcemap_embedded_debug_id)
+            {
+                sourcemap_file.set_debug_id(debug_id.to_string());
+                self.debug_ids.insert(sourcemap_url.clone(), debug_id);
+            }
+
+            self.sources.insert(sourcemap_url, sourcemap_file);
+        }
+
+        Ok(())
+    }
+
     /// Unpacks the given RAM bundle into a list of module sources and their sourcemaps
     pub fn unpack_ram_bundle(
         &mut self,
@@ -648,7 +856,7 @@ impl SourceMapProcessor {
     pub fn rewrite(&mut self, prefixes: &[&str]) -> Result<()> {
         self.flush_pending_sources();
 
-        println!("{} Rewriting sources", style(">").dim());
+        quiet_println!("{} Rewriting sources", style(">").dim());
 
         self.unpack_indexed_ram_bundles()?;
 
@@ -689,12 +897,72 @@ impl SourceMapProcessor {
         Ok(())
     }
 
+    /// Rewrites the `sources` entries of all source maps.  Entries keep their
+    /// index, so `sourcesContent` stays aligned with them.
+    pub fn rewrite_sources(&mut self, options: &RewriteSourcesOptions) -> Result<()> {
+        const SAMPLE_SIZE: usize = 5;
+
+        self.flush_pending_sources();
+
+        let mut rewritten = 0;
+        for source in self.sources.values_mut() {
+            if source.ty != SourceFileType::SourceMap {
+                continue;
+            }
+            let mut map = sourcemap::decode_slice(&source.contents)?;
+            let sm: &mut SourceMap = match map {
+                sourcemap::DecodedMap::Regular(ref mut sm) => sm,
+                sourcemap::DecodedMap::Hermes(ref mut smh) => smh,
+                sourcemap::DecodedMap::Index(_) => {
+                    warn!(
+                        "Not rewriting sources of {}: indexed source maps are only \
+                        supported without --no-rewrite",
+                        source.url
+                    );
+                    continue;
+                }
+            };
+
+            // The source root is folded into the sources, since it would
+            // otherwise be prepended to the rewritten paths again.
+            let mut changed = sm.get_source_root().is_some();
+            let sources: Vec<String> = sm.sources().map(str::to_owned).collect();
+            sm.set_source_root(None::<String>);
+            for (idx, old) in sources.iter().enumerate() {
+                let new = options.rewrite(old);
+                if new != *old {
+                    if rewritten == 0 {
+                        quiet_println!("{} Rewritten source paths:", style(">").dim());
+                    }
+                    if rewritten < SAMPLE_SIZE {
+                        quiet_println!("    {old} -> {new}");
+                    } else {
+                        info!("Rewrote source path {old} to {new}");
+                    }
+                    rewritten += 1;
+                    changed = true;
+                }
+                sm.set_source(idx as u32, &new);
+            }
+
+            if changed {
+                let mut new_source: Vec<u8> = Vec::new();
+                map.to_writer(&mut new_source)?;
+                source.contents = new_source;
+            }
+        }
+        if rewritten > SAMPLE_SIZE {
+            quiet_println!("    ... and {} more", rewritten - SAMPLE_SIZE);
+        }
+        Ok(())
+    }
+
     /// Adds sourcemap references to all minified files
     pub fn add_sourcemap_references(&mut self) -> Result<()> {
         self.flush_pending_sources();
         self.collect_sourcemap_references();
 
-        println!("{} Adding source map references", style(">").dim());
+        quiet_println!("{} Adding source map references", style(">").dim());
         for source in self.sources.values_mut() {
             if source.ty != SourceFileType::MinifiedSource {
                 continue;
@@ -769,7 +1037,11 @@ impl SourceMapProcessor {
     /// Flags the collected sources whether they have already been uploaded before
     /// (based on their checksum), and returns the number of files that *do* need an upload.
     fn flag_uploaded_sources(&mut self, context: &UploadContext<'_>) -> usize {
-        let mut files_needing_upload = self.sources.len();
+        let mut files_needing_upload = self
+            .sources
+            .values()
+            .filter(|source| !source.already_uploaded)
+            .count();
 
         // TODO: this endpoint does not exist for non release based uploads
         if !context.dedupe {
@@ -804,7 +1076,7 @@ impl SourceMapProcessor {
                 .filter_map(|artifact| Digest::from_str(&artifact.sha1).ok())
                 .collect();
 
-            for source in self.sources.values_mut() {
+            for source in self.sources.values_mut().filter(|s| !s.already_uploaded) {
                 if let Ok(checksum) = source.checksum() {
                     if already_uploaded_checksums.contains(&checksum) {
                         source.already_uploaded = true;
@@ -816,6 +1088,82 @@ impl SourceMapProcessor {
         files_needing_upload
     }
 
+    /// Flags all sources that are unchanged since the given prior release as
+    /// already uploaded, so that only the delta is uploaded.
+    ///
+    /// Only files with debug ids uploaded as artifact bundles are reused, as
+    /// those are resolved by their debug id regardless of the release.
+    pub fn reuse_from_release(
+        &mut self,
+        context: &UploadContext<'_>,
+        prior_release: &str,
+    ) -> Result<()> {
+        self.flush_pending_sources();
+
+        let uses_artifact_bundles = context.project.is_some()
+            && context.chunk_upload_options.is_some_and(|options| {
+                options.supports(ChunkUploadCapability::ArtifactBundles)
+                    || options.supports(ChunkUploadCapability::ArtifactBundlesV2)
+            });
+        if !uses_artifact_bundles {
+            warn!(
+                "Files can only be reused from release {prior_release} with artifact bundle \
+                 uploads, uploading all files."
+            );
+            return Ok(());
+        }
+
+        let mut checksums = vec![];
+        for source in self.sources.values() {
+            if source.debug_id().is_some() {
+                checksums.push(source.checksum()?.to_string());
+            }
+        }
+        checksums.sort();
+
+        let prior_artifacts: HashSet<_> = if checksums.is_empty() {
+            HashSet::new()
+        } else {
+            Api::current()
+                .authenticated()?
+                .list_release_files_by_checksum(
+                    context.org,
+                    context.project,
+                    prior_release,
+                    &checksums,
+                )?
+                .into_iter()
+                .map(|artifact| (artifact.dist, artifact.name, artifact.sha1))
+                .collect()
+        };
+
+        let dist = context.dist.map(str::to_owned);
+        let mut reused = 0;
+        for source in self.sources.values_mut() {
+            if source.debug_id().is_none() {
+                continue;
+            }
+            let key = (
+                dist.clone(),
+                source.url.clone(),
+                source.checksum()?.to_string(),
+            );
+            if prior_artifacts.contains(&key) {
+                source.already_uploaded = true;
+                reused += 1;
+            }
+        }
+
+        quiet_println!(
+            "{} Release delta since {}: {} reused, {} new",
+            style(">").dim(),
+            style(prior_release).cyan(),
+            style(reused).yellow(),
+            style(self.sources.len() - reused).yellow()
+        );
+        Ok(())
+    }
+
     /// Uploads all files, and on success, returns the number of files that were
     /// uploaded, wrapped in Ok()
     pub fn upload(&mut self, context: &UploadContext<'_>) -> Result<usize> {
@@ -857,16 +1205,71 @@ impl SourceMapProcessor {
 
         let files_needing_upload = self.flag_uploaded_sources(context);
         if files_needing_upload > 0 {
-            let mut uploader = FileUpload::new(context);
-            uploader.files(&self.sources);
-            uploader.upload()?;
+            if self.dist_pattern.is_some() {
+                for (dist, files) in self.sources_by_dist(context.dist) {
+                    let context = UploadContext {
+                        dist: dist.as_deref(),
+                        ..context.clone()
+                    };
+                    FileUpload::new(&context).files(&files).upload()?;
+                }
+            } else {
+                FileUpload::new(context).files(&self.sources).upload()?;
+            }
+            self.print_artifact_type_summary();
             self.dump_log("Source Map Upload Report");
         } else {
-            println!("{} Nothing to upload", style(">").dim());
+            quiet_println!("{} Nothing to upload", style(">").dim());
         }
         Ok(files_needing_upload)
     }
 
+    /// Returns the status of every file after an upload, for `--json`.
+    pub fn upload_report(&self) -> UploadReport<'_> {
+        UploadReport::new(self.sources.values())
+    }
+
+    /// Groups the sources by the dist derived from their file names, falling
+    /// back to `default_dist` for files that do not match the pattern.
+    fn sources_by_dist(&self, default_dist: Option<&str>) -> BTreeMap<Option<String>, SourceFiles> {
+        const SAMPLE_SIZE: usize = 5;
+
+        let pattern = self.dist_pattern.as_ref().unwrap();
+        let mut groups: BTreeMap<Option<String>, SourceFiles> = BTreeMap::new();
+        let mut derived = 0;
+        for (url, source) in &self.sources {
+            let filename = url.rsplit('/').next().unwrap_or(url);
+            let dist = match pattern.captures(filename).and_then(|c| c.name("dist")) {
+                Some(dist) => {
+                    if derived == 0 {
+                        quiet_println!("{} Dists derived from file names:", style(">").dim());
+                    }
+                    if derived < SAMPLE_SIZE {
+                        quiet_println!("    {} -> {}", url, dist.as_str());
+                    } else {
+                        info!("Derived dist {} for {}", dist.as_str(), url);
+                    }
+                    derived += 1;
+                    Some(dist.as_str().to_owned())
+                }
+                None => {
+                    if default_dist.is_none() && !source.already_uploaded {
+                        warn!("No dist for {url}: its file name does not match the dist pattern");
+                    }
+                    default_dist.map(str::to_owned)
+                }
+            };
+            groups
+                .entry(dist)
+                .or_default()
+                .insert(url.clone(), source.clone());
+        }
+        if derived > SAMPLE_SIZE {
+            quiet_println!("    ... and {} more", derived - SAMPLE_SIZE);
+        }
+        groups
+    }
+
     /// Upload all files in "strict" mode. Strict mode differs from a normal upload
     /// only when there are no files to upload. In strict mode, having no files to
     /// upload results in an error, whereas such an upload is successful in normal
@@ -891,14 +1294,25 @@ impl SourceMapProcessor {
     ///
     /// If `dry_run` is false, this will modify the source and sourcemap files on disk!
     ///
+    /// If `ignore_errors` is true, files that cannot be processed are skipped
+    /// and recorded in the report instead of aborting the injection.
+    ///
     /// The `js_extensions` is a list of file extensions that should be considered
     /// for JavaScript files.
-    pub fn inject_debug_ids(&mut self, dry_run: bool, js_extensions: &[&str]) -> Result<()> {
+    pub fn inject_debug_ids(
+        &mut self,
+        dry_run: bool,
+        ignore_errors: bool,
+        js_extensions: &[&str],
+    ) -> Result<InjectReport> {
         self.flush_pending_sources();
         self.collect_sourcemap_references();
-        println!("{} Injecting debug ids", style(">").dim());
+        quiet_println!("{} Injecting debug ids", style(">").dim());
 
-        let mut report = InjectReport::default();
+        let mut report = InjectReport {
+            dry_run,
+            ..Default::default()
+        };
 
         let mut sourcemaps = self
             .sources
@@ -924,161 +1338,23 @@ impl SourceMapProcessor {
                 continue;
             }
 
-            // Modify the source file and the sourcemap.
-            // There are several cases to consider according to whether we have a sourcemap for the source file and
-            // whether it's embedded or external.
-            let debug_id = match sourcemap_url {
-                None => {
-                    // Case 1: We have no sourcemap for the source file. Hash the file contents for the debug id.
-                    let source_file = self.sources.get_mut(source_url).unwrap();
-                    let debug_id = inject::debug_id_from_bytes_hashed(&source_file.contents);
-
-                    // If we don't have a sourcemap, it's not safe to inject the code snippet at the beginning,
-                    // because that would throw off all the mappings. Instead, inject the snippet at the very end.
-                    // This isn't ideal, but it's the best we can do in this case.
-                    inject::fixup_js_file_end(&mut source_file.contents, debug_id)
-                        .context(format!("Failed to process {}", source_file.path.display()))?;
-                    debug_id
-                }
-                Some(sourcemap) => {
-                    if let Some(encoded) = sourcemap.url.strip_prefix(DATA_PREAMBLE) {
-                        // Case 2: The source file has an embedded sourcemap.
-
-                        let Ok(mut decoded) = data_encoding::BASE64.decode(encoded.as_bytes())
-                        else {
-                            bail!("Invalid embedded sourcemap in source file {source_url}");
-                        };
-
-                        let mut sourcemap = SourceMap::from_slice(&decoded).with_context(|| {
-                            format!("Invalid embedded sourcemap in source file {source_url}")
-                        })?;
-
-                        let debug_id = sourcemap
-                            .get_debug_id()
-                            .unwrap_or_else(|| inject::debug_id_from_bytes_hashed(&decoded));
-
-                        let source_file = self.sources.get_mut(source_url).unwrap();
-                        let adjustment_map =
-                            inject::fixup_js_file(&mut source_file.contents, debug_id).context(
-                                format!("Failed to process {}", source_file.path.display()),
-                            )?;
-
-                        sourcemap.adjust_mappings(&adjustment_map);
-                        sourcemap.set_debug_id(Some(debug_id));
-
-                        decoded.clear();
-                        sourcemap.to_writer(&mut decoded)?;
-
-                        let encoded = data_encoding::BASE64.encode(&decoded);
-                        let new_sourcemap_url = format!("{DATA_PREAMBLE}{encoded}");
-
-                        inject::replace_sourcemap_url(
-                            &mut source_file.contents,
-                            &new_sourcemap_url,
-                        )?;
-                        *sourcemap_url = Some(SourceMapReference::from_url(new_sourcemap_url));
-
-                        debug_id
-                    } else {
-                        // Handle external sourcemaps
-
-                        let normalized =
-                            inject::normalize_sourcemap_url(source_url, &sourcemap.url);
-                        let matches = inject::find_matching_paths(&sourcemaps, &normalized);
-
-                        let sourcemap_url = match &matches[..] {
-                            [] => normalized,
-                            [x] => x.to_string(),
-                            _ => {
-                                warn!("Ambiguous matches for sourcemap path {normalized}:");
-                                for path in matches {
-                                    warn!("{path}");
-                                }
-                                normalized
-                            }
-                        };
-
-                        if self.sources.contains_key(&sourcemap_url) {
-                            // Case 3: We have an external sourcemap for the source file.
-
-                            // We need to do a bit of a dance here because we can't mutably
-                            // borrow the source file and the sourcemap at the same time.
-                            let (mut sourcemap, debug_id, debug_id_fresh) = {
-                                let sourcemap_file = &self.sources[&sourcemap_url];
-
-                                let sm = SourceMap::from_slice(&sourcemap_file.contents).context(
-                                    format!("Invalid sourcemap at {}", sourcemap_file.url),
-                                )?;
-
-                                match sm.get_debug_id() {
-                                    Some(debug_id) => (sm, debug_id, false),
-                                    None => {
-                                        let debug_id = inject::debug_id_from_bytes_hashed(
-                                            &sourcemap_file.contents,
-                                        );
-                                        (sm, debug_id, true)
-                                    }
-                                }
-                            };
-
-                            let source_file = self.sources.get_mut(source_url).unwrap();
-                            let adjustment_map =
-                                inject::fixup_js_file(&mut source_file.contents, debug_id)
-                                    .context(format!(
-                                        "Failed to process {}",
-                                        source_file.path.display()
-                                    ))?;
-
-                            sourcemap.adjust_mappings(&adjustment_map);
-                            sourcemap.set_debug_id(Some(debug_id));
-
-                            let sourcemap_file = self.sources.get_mut(&sourcemap_url).unwrap();
-                            sourcemap_file.contents.clear();
-                            sourcemap.to_writer(&mut sourcemap_file.contents)?;
-
-                            sourcemap_file.set_debug_id(debug_id.to_string());
-
-                            if !dry_run {
-                                let mut file = std::fs::File::create(&sourcemap_file.path)?;
-                                file.write_all(&sourcemap_file.contents).context(format!(
-                                    "Failed to write sourcemap file {}",
-                                    sourcemap_file.path.display()
-                                ))?;
-                            }
-
-                            if debug_id_fresh {
-                                report
-                                    .sourcemaps
-                                    .push((sourcemap_file.path.clone(), debug_id));
-                            } else {
-                                report
-                                    .skipped_sourcemaps
-                                    .push((sourcemap_file.path.clone(), debug_id));
-                            }
-
-                            debug_id
-                        } else {
-                            // Case 4: We have a URL for the external sourcemap, but we can't find it.
-                            // This is substantially the same as case 1.
-                            debug!("Sourcemap file {} not found", sourcemap_url);
-                            // source map cannot be found, fall back to hashing the contents.
-                            let source_file = self.sources.get_mut(source_url).unwrap();
-                            let debug_id =
-                                inject::debug_id_from_bytes_hashed(&source_file.contents);
-
-                            // If we don't have a sourcemap, it's not safe to inject the code snippet at the beginning,
-                            // because that would throw off all the mappings. Instead, inject the snippet at the very end.
-                            // This isn't ideal, but it's the best we can do in this case.
-                            inject::fixup_js_file_end(&mut source_file.contents, debug_id)
-                                .context(format!(
-                                    "Failed to process {}",
-                                    source_file.path.display()
-                                ))?;
-
-                            debug_id
-                        }
-                    }
+            let result = inject_debug_id(
+                &mut self.sources,
+                &sourcemaps,
+                source_url,
+                sourcemap_url,
+                dry_run,
+                &mut report,
+            );
+            let debug_id = match result {
+                Ok(debug_id) => debug_id,
+                Err(err) if ignore_errors => {
+                    let path = self.sources[source_url].path.clone();
+                    warn!("Skipping {}: {err:#}", path.display());
+                    report.failed.push((path, format!("{err:#}")));
+                    continue;
                 }
+                Err(err) => return Err(err),
             };
 
             // Finally, some housekeeping.
@@ -1098,13 +1374,176 @@ impl SourceMapProcessor {
             report.injected.push((source_file.path.clone(), debug_id));
         }
 
-        if !report.is_empty() {
-            println!("{report}");
-        } else {
-            println!("> Nothing to inject")
+        Ok(report)
+    }
+}
+
+/// Injects a debug id into a single source file and its sourcemap, if any.
+/// Returns the injected debug id.
+fn inject_debug_id(
+    sources: &mut SourceFiles,
+    sourcemaps: &[String],
+    source_url: &str,
+    sourcemap_url: &mut Option<SourceMapReference>,
+    dry_run: bool,
+    report: &mut InjectReport,
+) -> Result<DebugId> {
+    // Modify the source file and the sourcemap.
+    // There are several cases to consider according to whether we have a sourcemap for the source file and
+    // whether it's embedded or external.
+    Ok(match sourcemap_url {
+        None => {
+            // Case 1: We have no sourcemap for the source file. Hash the file contents for the debug id.
+            let source_file = sources.get_mut(source_url).unwrap();
+            let debug_id = inject::debug_id_from_bytes_hashed(&source_file.contents);
+
+            // If we don't have a sourcemap, it's not safe to inject the code snippet at the beginning,
+            // because that would throw off all the mappings. Instead, inject the snippet at the very end.
+            // This isn't ideal, but it's the best we can do in this case.
+            inject::fixup_js_file_end(&mut source_file.contents, debug_id)
+                .context(format!("Failed to process {}", source_file.path.display()))?;
+            debug_id
         }
+        Some(sourcemap) => {
+            if let Some(encoded) = sourcemap.url.strip_prefix(DATA_PREAMBLE) {
+                // Case 2: The source file has an embedded sourcemap.
 
-        Ok(())
+                let Ok(mut decoded) = data_encoding::BASE64.decode(encoded.as_bytes()) else {
+                    bail!("Invalid embedded sourcemap in source file {source_url}");
+                };
+
+                let mut sourcemap = SourceMap::from_slice(&decoded).with_context(|| {
+                    format!("Invalid embedded sourcemap in source file {source_url}")
+                })?;
+
+                let debug_id = sourcemap
+                    .get_debug_id()
+                    .unwrap_or_else(|| inject::debug_id_from_bytes_hashed(&decoded));
+
+                let source_file = sources.get_mut(source_url).unwrap();
+                let adjustment_map = inject::fixup_js_file(&mut source_file.contents, debug_id)
+                    .context(format!("Failed to process {}", source_file.path.display()))?;
+
+                sourcemap.adjust_mappings(&adjustment_map);
+                sourcemap.set_debug_id(Some(debug_id));
+
+                decoded.clear();
+                sourcemap.to_writer(&mut decoded)?;
+
+                let encoded = data_encoding::BASE64.encode(&decoded);
+                let new_sourcemap_url = format!("{DATA_PREAMBLE}{encoded}");
+
+                inject::replace_sourcemap_url(&mut source_file.contents, &new_sourcemap_url)?;
+                *sourcemap_url = Some(SourceMapReference::from_url(new_sourcemap_url));
+
+                debug_id
+            } else {
+                // Handle external sourcemaps
+
+                let normalized = inject::normalize_sourcemap_url(source_url, &sourcemap.url);
+                let matches = inject::find_matching_paths(sourcemaps, &normalized);
+
+                let sourcemap_url = match &matches[..] {
+                    [] => normalized,
+                    [x] => x.to_string(),
+                    _ => {
+                        warn!("Ambiguous matches for sourcemap path {normalized}:");
+                        for path in matches {
+                            warn!("{path}");
+                        }
+                        normalized
+                    }
+                };
+
+                if sources.contains_key(&sourcemap_url) {
+                    // Case 3: We have an external sourcemap for the source file.
+
+                    // We need to do a bit of a dance here because we can't mutably
+                    // borrow the source file and the sourcemap at the same time.
+                    let (mut sourcemap, debug_id, debug_id_fresh) = {
+                        let sourcemap_file = &sources[&sourcemap_url];
+
+                        let sm = SourceMap::from_slice(&sourcemap_file.contents)
+                            .context(format!("Invalid sourcemap at {}", sourcemap_file.url))?;
+
+                        match sm.get_debug_id() {
+                            Some(debug_id) => (sm, debug_id, false),
+                            None => {
+                                let debug_id =
+                                    inject::debug_id_from_bytes_hashed(&sourcemap_file.contents);
+                                (sm, debug_id, true)
+                            }
+                        }
+                    };
+
+                    let source_file = sources.get_mut(source_url).unwrap();
+                    let adjustment_map = inject::fixup_js_file(&mut source_file.contents, debug_id)
+                        .context(format!("Failed to process {}", source_file.path.display()))?;
+
+                    sourcemap.adjust_mappings(&adjustment_map);
+                    sourcemap.set_debug_id(Some(debug_id));
+
+                    let sourcemap_file = sources.get_mut(&sourcemap_url).unwrap();
+                    sourcemap_file.contents.clear();
+                    sourcemap.to_writer(&mut sourcemap_file.contents)?;
+
+                    sourcemap_file.set_debug_id(debug_id.to_string());
+
+                    if !dry_run {
+                        let mut file = std::fs::File::create(&sourcemap_file.path)?;
+                        file.write_all(&sourcemap_file.contents).context(format!(
+                            "Failed to write sourcemap file {}",
+                            sourcemap_file.path.display()
+                        ))?;
+                    }
+
+                    if debug_id_fresh {
+                        report
+                            .sourcemaps
+                            .push((sourcemap_file.path.clone(), debug_id));
+                    } else {
+                        report
+                            .skipped_sourcemaps
+                            .push((sourcemap_file.path.clone(), debug_id));
+                    }
+
+                    debug_id
+                } else {
+                    // Case 4: We have a URL for the external sourcemap, but we can't find it.
+                    // This is substantially the same as case 1.
+                    debug!("Sourcemap file {} not found", sourcemap_url);
+                    // source map cannot be found, fall back to hashing the contents.
+                    let source_file = sources.get_mut(source_url).unwrap();
+                    let debug_id = inject::debug_id_from_bytes_hashed(&source_file.contents);
+
+                    // If we don't have a sourcemap, it's not safe to inject the code snippet at the beginning,
+                    // because that would throw off all the mappings. Instead, inject the snippet at the very end.
+                    // This isn't ideal, but it's the best we can do in this case.
+                    inject::fixup_js_file_end(&mut source_file.contents, debug_id)
+                        .context(format!("Failed to process {}", source_file.path.display()))?;
+
+                    debug_id
+                }
+            }
+        }
+    })
+}
+
+/// Decodes the payload of a `data:` URL.  Both base64 and percent-encoded
+/// payloads are supported.
+fn decode_data_url(url: &str) -> Result<Vec<u8>> {
+    let Some((header, data)) = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+    else {
+        bail!("malformed data URL");
+    };
+
+    let data: Vec<u8> = percent_decode_str(data).collect();
+    if header.split(';').any(|param| param == "base64") {
+        Ok(data_encoding::BASE64.decode(&data)?)
+    } else {
+        Ok(data)
     }
 }
 
@@ -1158,6 +1597,38 @@ fn validate_sourcemap(source_urls: &HashSet<String>, source: &mut SourceFile) ->
     Ok(())
 }
 
+/// Checks whether a source map is empty, has no mappings or no sources.
+fn empty_sourcemap_reason(contents: &[u8]) -> Option<&'static str> {
+    if contents.iter().all(u8::is_ascii_whitespace) {
+        return Some("empty file");
+    }
+
+    let Ok(serde_json::Value::Object(map)) = serde_json::from_slice(contents) else {
+        return None;
+    };
+
+    // Indexed sourcemaps carry their mappings in sections.
+    if map.contains_key("sections") {
+        return None;
+    }
+
+    if map
+        .get("mappings")
+        .and_then(|mappings| mappings.as_str())
+        .map_or(true, str::is_empty)
+    {
+        Some("no mappings")
+    } else if map
+        .get("sources")
+        .and_then(|sources| sources.as_array())
+        .map_or(true, Vec::is_empty)
+    {
+        Some("no sources")
+    } else {
+        None
+    }
+}
+
 impl Default for SourceMapProcessor {
     fn default() -> Self {
         SourceMapProcessor::new()
@@ -1168,6 +1639,42 @@ impl Default for SourceMapProcessor {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_empty_sourcemap_reason() {
+        assert_eq!(empty_sourcemap_reason(b""), Some("empty file"));
+        assert_eq!(empty_sourcemap_reason(b" \n"), Some("empty file"));
+        assert_eq!(empty_sourcemap_reason(b"{}"), Some("no mappings"));
+        assert_eq!(
+            empty_sourcemap_reason(br#"{"version":3,"sources":["a.js"],"mappings":""}"#),
+            Some("no mappings")
+        );
+        assert_eq!(
+            empty_sourcemap_reason(br#"{"version":3,"sources":[],"mappings":"AAAA"}"#),
+            Some("no sources")
+        );
+        assert_eq!(
+            empty_sourcemap_reason(br#"{"version":3,"sources":["a.js"],"mappings":"AAAA"}"#),
+            None
+        );
+        assert_eq!(
+            empty_sourcemap_reason(br#"{"version":3,"sections":[]}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_data_url() {
+        assert_eq!(
+            decode_data_url("data:application/json;base64,eyJ2ZXJzaW9uIjozfQ==").unwrap(),
+            br#"{"version":3}"#
+        );
+        assert_eq!(
+            decode_data_url("data:application/json;charset=utf-8,%7B%22version%22%3A3%7D").unwrap(),
+            br#"{"version":3}"#
+        );
+        assert!(decode_data_url("data:application/json;base64").is_err());
+    }
+
     #[test]
     fn test_split_url() {
         assert_eq!(split_url("/foo.js"), (Some(""), "foo", Some("js")));
@@ -5,13 +5,14 @@ use symbolic::common::{clean_path, join_path};
 
 use std::fmt;
 use std::io::{BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use lazy_static::lazy_static;
 
 use magic_string::{GenerateDecodedMapOptions, MagicString};
 use sentry::types::DebugId;
+use serde::Serialize;
 use sourcemap::SourceMap;
 
 const CODE_SNIPPET_TEMPLATE: &str = r#"!function(){try{var e="undefined"!=typeof window?window:"undefined"!=typeof global?global:"undefined"!=typeof self?self:{},n=(new e.Error).stack;n&&(e._sentryDebugIds=e._sentryDebugIds||{},e._sentryDebugIds[n]="__SENTRY_DEBUG_ID__")}catch(e){}}();"#;
@@ -47,10 +48,26 @@ fn print_section_title(f: &mut fmt::Formatter<'_>, title: &str) -> fmt::Result {
 
 #[derive(Debug, Clone, Default)]
 pub struct InjectReport {
+    pub dry_run: bool,
     pub injected: Vec<(PathBuf, DebugId)>,
     pub previously_injected: Vec<(PathBuf, DebugId)>,
     pub sourcemaps: Vec<(PathBuf, DebugId)>,
     pub skipped_sourcemaps: Vec<(PathBuf, DebugId)>,
+    /// Source files that could not be processed, with the error.
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// A single file of an [`InjectReport`], as emitted by `--json`.
+#[derive(Debug, Serialize)]
+pub struct InjectReportEntry<'a> {
+    pub path: &'a Path,
+    /// `inject` if the file gains a debug id, `skip` if it already has one,
+    /// `error` if it could not be processed.
+    pub action: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_id: Option<DebugId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<&'a str>,
 }
 
 impl InjectReport {
@@ -59,21 +76,51 @@ impl InjectReport {
             && self.previously_injected.is_empty()
             && self.sourcemaps.is_empty()
             && self.skipped_sourcemaps.is_empty()
+            && self.failed.is_empty()
+    }
+
+    /// Returns all files of the report, sorted by path.
+    pub fn entries(&self) -> Vec<InjectReportEntry<'_>> {
+        let injected = self.injected.iter().chain(&self.sourcemaps);
+        let skipped = self
+            .previously_injected
+            .iter()
+            .chain(&self.skipped_sourcemaps);
+        injected
+            .map(|entry| ("inject", entry))
+            .chain(skipped.map(|entry| ("skip", entry)))
+            .map(|(action, (path, debug_id))| InjectReportEntry {
+                path,
+                action,
+                debug_id: Some(*debug_id),
+                error: None,
+            })
+            .chain(self.failed.iter().map(|(path, error)| InjectReportEntry {
+                path,
+                action: "error",
+                debug_id: None,
+                error: Some(error),
+            }))
+            .sorted_by_key(|entry| entry.path)
+            .collect()
     }
 }
 
 impl fmt::Display for InjectReport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(
-            f,
-            "\n{}",
-            style("Source Map Debug ID Injection Report").dim().bold()
-        )?;
+        let (title, modified) = if self.dry_run {
+            ("Source Map Debug ID Injection Report (dry run)", "would be")
+        } else {
+            ("Source Map Debug ID Injection Report", "have been")
+        };
+        writeln!(f, "\n{}", style(title).dim().bold())?;
 
         if !self.injected.is_empty() {
             print_section_with_debugid(
                 f,
-                "Modified: The following source files have been modified to have debug ids",
+                &format!(
+                    "Modified: The following source files {modified} modified to have debug ids"
+                ),
                 &self.injected,
             )?;
         }
@@ -81,7 +128,9 @@ impl fmt::Display for InjectReport {
         if !self.sourcemaps.is_empty() {
             print_section_with_debugid(
                 f,
-                "Modified: The following sourcemap files have been modified to have debug ids",
+                &format!(
+                    "Modified: The following sourcemap files {modified} modified to have debug ids"
+                ),
                 &self.sourcemaps,
             )?;
         }
@@ -102,6 +151,16 @@ impl fmt::Display for InjectReport {
             )?;
         }
 
+        if !self.failed.is_empty() {
+            print_section_title(
+                f,
+                "Skipped: The following source files could not be processed",
+            )?;
+            for (path, error) in self.failed.iter().sorted_by_key(|x| &x.0) {
+                writeln!(f, "    {} ({error})", path.display())?;
+            }
+        }
+
         Ok(())
     }
 }
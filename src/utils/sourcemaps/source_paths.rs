@@ -0,0 +1,116 @@
+//! Normalization of the `sources` entries of source maps.
+//!
+//! Bundlers often emit source paths such as `webpack://app/./src/foo.js`,
+//! which are rewritten to `app/src/foo.js` for display in Sentry.
+
+/// Scheme prefixes that bundlers put in front of source paths.
+const KNOWN_SCHEMES: &[&str] = &["webpack://", "webpack-internal://"];
+
+/// The transformations applied to the `sources` of a source map.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RewriteSourcesOptions {
+    /// Strips known bundler schemes such as `webpack://`.
+    pub strip_schemes: bool,
+    /// Resolves `.` and `..` segments and removes duplicate slashes.
+    pub normalize_paths: bool,
+}
+
+impl RewriteSourcesOptions {
+    /// Returns the rewritten source path.
+    pub fn rewrite(&self, source: &str) -> String {
+        let mut source = source;
+        if self.strip_schemes {
+            if let Some(rest) = KNOWN_SCHEMES
+                .iter()
+                .find_map(|scheme| source.strip_prefix(scheme))
+            {
+                source = rest.trim_start_matches('/');
+            }
+        }
+        if self.normalize_paths {
+            normalize_path(source)
+        } else {
+            source.to_owned()
+        }
+    }
+}
+
+/// Resolves `.` and `..` segments of a path.  For URLs, only the path after
+/// the host is normalized.  Leading `..` segments of relative paths are kept.
+fn normalize_path(source: &str) -> String {
+    let (prefix, path) = match source.find("://") {
+        Some(idx) => match source[idx + 3..].find('/') {
+            Some(host_len) => source.split_at(idx + 3 + host_len),
+            None => return source.to_owned(),
+        },
+        None => ("", source),
+    };
+    let absolute = path.starts_with('/');
+
+    let mut segments: Vec<&str> = vec![];
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => match segments.last() {
+                Some(&last) if last != ".." => {
+                    segments.pop();
+                }
+                _ if !absolute => segments.push(".."),
+                _ => {}
+            },
+            segment => segments.push(segment),
+        }
+    }
+
+    let root = if absolute { "/" } else { "" };
+    format!("{prefix}{root}{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite() {
+        let options = RewriteSourcesOptions {
+            strip_schemes: true,
+            normalize_paths: true,
+        };
+        assert_eq!(
+            options.rewrite("webpack://app/./src/foo.js"),
+            "app/src/foo.js"
+        );
+        assert_eq!(options.rewrite("webpack:///./src/foo.js"), "src/foo.js");
+        assert_eq!(
+            options.rewrite("webpack-internal:///./src/a/../b.js"),
+            "src/b.js"
+        );
+        assert_eq!(
+            options.rewrite("../node_modules/x.js"),
+            "../node_modules/x.js"
+        );
+        assert_eq!(options.rewrite("/src/../../foo.js"), "/foo.js");
+        assert_eq!(
+            options.rewrite("https://example.com/a/./b/../c.js"),
+            "https://example.com/a/c.js"
+        );
+
+        let strip_only = RewriteSourcesOptions {
+            strip_schemes: true,
+            normalize_paths: false,
+        };
+        assert_eq!(
+            strip_only.rewrite("webpack://app/./src/foo.js"),
+            "app/./src/foo.js"
+        );
+
+        let normalize_only = RewriteSourcesOptions {
+            strip_schemes: false,
+            normalize_paths: true,
+        };
+        assert_eq!(
+            normalize_only.rewrite("webpack://app/./src/foo.js"),
+            "webpack://app/src/foo.js"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! The report printed by `sourcemaps upload --json`.
+//!
+//! The report is a single document for all input paths:
+//!
+//! ```json
+//! {
+//!   "files": [
+//!     {
+//!       "url": "~/bundle.min.js",
+//!       "path": "dist/bundle.min.js",
+//!       "type": "minified_source",
+//!       "status": "uploaded",
+//!       "debug_id": "2297b93d-928d-421e-8910-127c786382dd",
+//!       "messages": [{"level": "warning", "message": "..."}]
+//!     }
+//!   ],
+//!   "summary": {"files": 1, "uploaded": 1, "skipped": 0, "warnings": 1, "errors": 0}
+//! }
+//! ```
+//!
+//! `debug_id` and `messages` are omitted if the file has none.
+use std::path::Path;
+
+use serde::Serialize;
+use symbolic::debuginfo::sourcebundle::SourceFileType;
+
+use crate::utils::file_upload::{LogLevel, SourceFile};
+
+#[derive(Debug, Serialize)]
+pub struct UploadReport<'a> {
+    /// All files, sorted by URL.
+    pub files: Vec<UploadReportFile<'a>>,
+    pub summary: UploadReportSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadReportFile<'a> {
+    pub url: &'a str,
+    pub path: &'a Path,
+    /// `source`, `minified_source`, `source_map` or `indexed_ram_bundle`.
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    /// `uploaded`, or `skipped` if the file is already on the server.
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<UploadReportMessage<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadReportMessage<'a> {
+    /// `warning` or `error`.
+    pub level: &'static str,
+    pub message: &'a str,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct UploadReportSummary {
+    pub files: usize,
+    pub uploaded: usize,
+    pub skipped: usize,
+    pub warnings: usize,
+    pub errors: usize,
+}
+
+fn type_name(ty: SourceFileType) -> &'static str {
+    match ty {
+        SourceFileType::Source => "source",
+        SourceFileType::MinifiedSource => "minified_source",
+        SourceFileType::SourceMap => "source_map",
+        SourceFileType::IndexedRamBundle => "indexed_ram_bundle",
+    }
+}
+
+impl<'a> UploadReport<'a> {
+    /// Creates the report for the given files.
+    pub fn new<I>(sources: I) -> Self
+    where
+        I: IntoIterator<Item = &'a SourceFile>,
+    {
+        let mut summary = UploadReportSummary::default();
+        let mut files: Vec<_> = sources
+            .into_iter()
+            .map(|source| {
+                summary.files += 1;
+                let status = if source.already_uploaded {
+                    summary.skipped += 1;
+                    "skipped"
+                } else {
+                    summary.uploaded += 1;
+                    "uploaded"
+                };
+                let messages = source
+                    .messages
+                    .iter()
+                    .map(|(level, message)| {
+                        let level = match level {
+                            LogLevel::Warning => {
+                                summary.warnings += 1;
+                                "warning"
+                            }
+                            LogLevel::Error => {
+                                summary.errors += 1;
+                                "error"
+                            }
+                        };
+                        UploadReportMessage { level, message }
+                    })
+                    .collect();
+                UploadReportFile {
+                    url: &source.url,
+                    path: &source.path,
+                    ty: type_name(source.ty),
+                    status,
+                    debug_id: source.debug_id().map(String::as_str),
+                    messages,
+                }
+            })
+            .collect();
+        files.sort_by_key(|file| file.url);
+        UploadReport { files, summary }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn source_file(url: &str, ty: SourceFileType, already_uploaded: bool) -> SourceFile {
+        SourceFile {
+            url: url.to_owned(),
+            path: PathBuf::from(url.trim_start_matches("~/")),
+            contents: vec![],
+            ty,
+            headers: BTreeMap::new(),
+            messages: vec![],
+            already_uploaded,
+        }
+    }
+
+    #[test]
+    fn test_upload_report() {
+        let mut source = source_file("~/b.js", SourceFileType::MinifiedSource, false);
+        source.set_debug_id("2297b93d-928d-421e-8910-127c786382dd".to_owned());
+        source.warn("no sourcemap ref".to_owned());
+        let sourcemap = source_file("~/a.js.map", SourceFileType::SourceMap, true);
+
+        let report = UploadReport::new([&source, &sourcemap]);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "files": [
+                    {
+                        "url": "~/a.js.map",
+                        "path": "a.js.map",
+                        "type": "source_map",
+                        "status": "skipped"
+                    },
+                    {
+                        "url": "~/b.js",
+                        "path": "b.js",
+                        "type": "minified_source",
+                        "status": "uploaded",
+                        "debug_id": "2297b93d-928d-421e-8910-127c786382dd",
+                        "messages": [{"level": "warning", "message": "no sourcemap ref"}]
+                    }
+                ],
+                "summary": {"files": 2, "uploaded": 1, "skipped": 1, "warnings": 1, "errors": 0}
+            })
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Support for webpack stats files, as written by `webpack --json`.
+//!
+//! The stats list every emitted asset together with the `publicPath` the
+//! assets are served from, which determines both the files to upload and
+//! the URLs they are loaded from at runtime.
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    public_path: Option<String>,
+    output_path: Option<PathBuf>,
+    #[serde(default)]
+    assets: Vec<Asset>,
+    #[serde(default)]
+    chunks: Vec<Chunk>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    #[serde(default)]
+    related: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Chunk {
+    #[serde(default)]
+    files: Vec<String>,
+    #[serde(default)]
+    auxiliary_files: Vec<String>,
+}
+
+/// The assets of a webpack build.
+pub struct WebpackManifest {
+    public_path: Option<String>,
+    output_path: PathBuf,
+    assets: BTreeSet<String>,
+}
+
+impl WebpackManifest {
+    /// Reads a stats file.  Assets are looked up in the `outputPath` of the
+    /// build, or next to the stats file if that directory does not exist.
+    pub fn from_path(path: &Path) -> Result<WebpackManifest> {
+        let contents = fs::read(path)
+            .with_context(|| format!("Failed to read webpack manifest {}", path.display()))?;
+        let stats: Stats = serde_json::from_slice(&contents)
+            .with_context(|| format!("Invalid webpack manifest {}", path.display()))?;
+
+        let output_path = stats
+            .output_path
+            .filter(|output_path| output_path.is_dir())
+            .unwrap_or_else(|| path.parent().unwrap_or(Path::new("")).to_owned());
+
+        let mut assets = BTreeSet::new();
+        let mut pending = stats.assets;
+        while let Some(asset) = pending.pop() {
+            assets.insert(asset.name);
+            pending.extend(asset.related);
+        }
+        for chunk in stats.chunks {
+            assets.extend(chunk.files);
+            assets.extend(chunk.auxiliary_files);
+        }
+
+        Ok(WebpackManifest {
+            public_path: stats.public_path,
+            output_path,
+            assets,
+        })
+    }
+
+    /// The URL prefix the assets are served from, derived from `publicPath`.
+    /// Paths without a host are relative to the root of the site (`~/`).
+    pub fn url_prefix(&self) -> String {
+        let public_path = match self.public_path.as_deref() {
+            None | Some("auto") => "",
+            Some(public_path) => public_path.trim_end_matches('/'),
+        };
+        if public_path.contains("://") {
+            public_path.to_owned()
+        } else {
+            format!("~/{}", public_path.trim_start_matches('/'))
+                .trim_end_matches('/')
+                .to_owned()
+        }
+    }
+
+    /// The names of all assets, relative to the output path.
+    pub fn assets(&self) -> impl Iterator<Item = &str> {
+        self.assets.iter().map(String::as_str)
+    }
+
+    /// The directory holding the assets.
+    pub fn output_path(&self) -> &Path {
+        &self.output_path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(public_path: Option<&str>) -> WebpackManifest {
+        WebpackManifest {
+            public_path: public_path.map(str::to_owned),
+            output_path: PathBuf::new(),
+            assets: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_url_prefix() {
+        assert_eq!(manifest(None).url_prefix(), "~");
+        assert_eq!(manifest(Some("auto")).url_prefix(), "~");
+        assert_eq!(manifest(Some("/")).url_prefix(), "~");
+        assert_eq!(manifest(Some("/static/js/")).url_prefix(), "~/static/js");
+        assert_eq!(manifest(Some("assets/")).url_prefix(), "~/assets");
+        assert_eq!(
+            manifest(Some("https://cdn.example.com/app/")).url_prefix(),
+            "https://cdn.example.com/app"
+        );
+    }
+}
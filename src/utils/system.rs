@@ -2,15 +2,25 @@ use std::borrow::Cow;
 use std::env;
 #[cfg(target_os = "macos")]
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use anyhow::{Error, Result};
+use anyhow::{bail, Error, Result};
 use console::style;
 use dotenv::Result as DotenvResult;
 use lazy_static::lazy_static;
 use regex::{Captures, Regex};
+use serde_json::json;
 
+use crate::api::ApiError;
 use crate::config::Config;
 
+/// Whether `--error-format json` was passed.
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_json_errors(json_errors: bool) {
+    JSON_ERRORS.store(json_errors, Ordering::Relaxed);
+}
+
 /// Propagate an exit status outwarts.
 /// We only use this function in the macOS binary.
 #[cfg(target_os = "macos")]
@@ -70,15 +80,70 @@ pub fn expand_vars<F: Fn(&str) -> String>(s: &str, f: F) -> Cow<'_, str> {
     })
 }
 
+/// Expands `${VAR}` and `${VAR:-default}` references to environment
+/// variables in a command line argument.  Like in the shell, the default is
+/// also used for empty variables.  Unset variables without a default are an
+/// error.
+pub fn expand_env_in_arg(arg: &str) -> Result<String> {
+    lazy_static! {
+        static ref ENV_VAR_RE: Regex =
+            Regex::new(r"\$\{(?P<name>[a-zA-Z_][a-zA-Z0-9_]*)(?::-(?P<default>[^}]*))?\}").unwrap();
+    }
+    let mut expanded = String::with_capacity(arg.len());
+    let mut last_end = 0;
+    for caps in ENV_VAR_RE.captures_iter(arg) {
+        let range = caps.get(0).unwrap().range();
+        let name = &caps["name"];
+        let value = match (env::var(name), caps.name("default")) {
+            (Ok(value), Some(default)) if value.is_empty() => default.as_str().to_owned(),
+            (Ok(value), _) => value,
+            (Err(_), Some(default)) => default.as_str().to_owned(),
+            (Err(_), None) => bail!(
+                "Environment variable {name} referenced in argument `{arg}` is not set. \
+                 Use ${{{name}:-default}} to provide a default."
+            ),
+        };
+        expanded.push_str(&arg[last_end..range.start]);
+        expanded.push_str(&value);
+        last_end = range.end;
+    }
+    expanded.push_str(&arg[last_end..]);
+    Ok(expanded)
+}
+
 /// Helper that renders an error to stderr.
 pub fn print_error(err: &Error) {
     if let Some(clap_err) = err.downcast_ref::<clap::Error>() {
         clap_err.exit();
     }
 
+    let api_err = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ApiError>());
+
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        let details = json!({
+            "error": err.to_string(),
+            "causes": err.chain().skip(1).map(ToString::to_string).collect::<Vec<_>>(),
+            "http_status": api_err.and_then(ApiError::http_status),
+            "request_id": api_err.and_then(ApiError::request_id),
+            "server_response": api_err.and_then(ApiError::server_response),
+        });
+        eprintln!("{details}");
+        return;
+    }
+
     // Debug style for error includes cause chain and backtrace (if available).
     eprintln!("{} {:?}", style("error:").red(), err);
 
+    if let Some(request_id) = api_err.and_then(ApiError::request_id) {
+        eprintln!();
+        eprintln!(
+            "Request ID: {} (include this when contacting Sentry support)",
+            style(request_id).yellow()
+        );
+    }
+
     if Config::current_opt().map_or(true, |config| {
         config.get_log_level() < log::LevelFilter::Info
     }) {
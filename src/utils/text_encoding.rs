@@ -0,0 +1,113 @@
+//! Transcoding of text inputs, such as ProGuard mappings and source files,
+//! to UTF-8, and normalization of their line endings.
+use std::borrow::Cow;
+
+use encoding::all::{UTF_16BE, UTF_16LE};
+use encoding::label::encoding_from_whatwg_label;
+use encoding::{DecoderTrap, EncodingRef};
+use log::warn;
+
+/// Parses an encoding label such as `latin1` or `utf-16`, used as a
+/// value_parser for Clap arguments.
+pub fn parse_encoding(label: &str) -> Result<EncodingRef, String> {
+    encoding_from_whatwg_label(label).ok_or_else(|| format!("unknown encoding `{label}`"))
+}
+
+/// Returns the contents of the file `name` transcoded to UTF-8.
+///
+/// UTF-16 marked with a byte order mark is always detected.  Otherwise the
+/// given encoding is used, or UTF-8 if there is none.  Characters that cannot
+/// be decoded are replaced, which is reported with a warning for UTF-8.
+pub fn transcode_to_utf8<'a>(
+    data: &'a [u8],
+    encoding: Option<EncodingRef>,
+    name: &str,
+) -> Cow<'a, [u8]> {
+    let (encoding, data) = match data {
+        [0xff, 0xfe, rest @ ..] => (Some(UTF_16LE as EncodingRef), rest),
+        [0xfe, 0xff, rest @ ..] => (Some(UTF_16BE as EncodingRef), rest),
+        _ => (encoding.filter(|encoding| encoding.name() != "utf-8"), data),
+    };
+
+    let Some(encoding) = encoding else {
+        return match String::from_utf8_lossy(data) {
+            Cow::Borrowed(_) => Cow::Borrowed(data),
+            Cow::Owned(lossy) => {
+                warn!(
+                    "{name} is not valid UTF-8, invalid characters were replaced. \
+                     Use --input-encoding to specify its encoding."
+                );
+                Cow::Owned(lossy.into_bytes())
+            }
+        };
+    };
+
+    // Decoding with `DecoderTrap::Replace` never fails.
+    let decoded = encoding
+        .decode(data, DecoderTrap::Replace)
+        .unwrap_or_default();
+    if decoded.as_bytes() == data {
+        Cow::Borrowed(data)
+    } else {
+        Cow::Owned(decoded.into_bytes())
+    }
+}
+
+/// Converts CRLF and CR line endings to LF and strips trailing whitespace
+/// from every line.  Returns `None` if the data is already normalized.
+pub fn normalize_line_endings(data: &[u8]) -> Option<Vec<u8>> {
+    let mut normalized = Vec::with_capacity(data.len());
+    for (index, line) in data.split(|&b| b == b'\n').enumerate() {
+        if index > 0 {
+            normalized.push(b'\n');
+        }
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        for (index, part) in line.split(|&b| b == b'\r').enumerate() {
+            if index > 0 {
+                normalized.push(b'\n');
+            }
+            normalized.extend_from_slice(part.trim_ascii_end());
+        }
+    }
+    (normalized != data).then_some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcode_to_utf8() {
+        let latin1 = parse_encoding("latin1").unwrap();
+        assert_eq!(
+            transcode_to_utf8(b"caf\xe9", Some(latin1), "a.txt"),
+            "café".as_bytes()
+        );
+        assert!(matches!(
+            transcode_to_utf8(b"cafe", Some(latin1), "a.txt"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            transcode_to_utf8(b"\xff\xfec\x00a\x00f\x00\xe9\x00", None, "a.txt"),
+            "café".as_bytes()
+        );
+        assert_eq!(
+            transcode_to_utf8(b"\xfe\xff\x00c\x00a\x00f\x00\xe9", Some(latin1), "a.txt"),
+            "café".as_bytes()
+        );
+        assert_eq!(
+            transcode_to_utf8(b"caf\xe9", None, "a.txt"),
+            "caf\u{fffd}".as_bytes()
+        );
+        assert!(parse_encoding("no-such-encoding").is_err());
+    }
+
+    #[test]
+    fn test_normalize_line_endings() {
+        assert_eq!(
+            normalize_line_endings(b"a -> b:\r\n    1:1:void c() -> d  \r\ne\rf\n").unwrap(),
+            b"a -> b:\n    1:1:void c() -> d\ne\nf\n"
+        );
+        assert_eq!(normalize_line_endings(b"a -> b:\n    c -> d\n"), None);
+    }
+}
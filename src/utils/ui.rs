@@ -1,8 +1,46 @@
 use std::io;
 use std::io::{Read, Write};
 
+use anyhow::{bail, Result};
+use console::user_attended;
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+
 use crate::utils::progress::{ProgressBar, ProgressStyle};
 
+/// Controls how destructive operations are confirmed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfirmationMode {
+    /// Prompt if the session is attended, refuse to continue otherwise.
+    Auto,
+    /// Never prompt and assume the operation is confirmed.
+    AssumeYes,
+    /// Always prompt, even if the session is not attended.
+    Interactive,
+}
+
+lazy_static! {
+    static ref CONFIRMATION_MODE: RwLock<ConfirmationMode> = RwLock::new(ConfirmationMode::Auto);
+}
+
+pub fn set_confirmation_mode(mode: ConfirmationMode) {
+    *CONFIRMATION_MODE.write() = mode;
+}
+
+/// Asks the user to confirm a destructive operation according to the
+/// configured `ConfirmationMode`.
+pub fn confirm_destructive(message: &str) -> Result<bool> {
+    match *CONFIRMATION_MODE.read() {
+        ConfirmationMode::AssumeYes => Ok(true),
+        ConfirmationMode::Interactive => Ok(prompt_to_continue(message)?),
+        ConfirmationMode::Auto if user_attended() => Ok(prompt_to_continue(message)?),
+        ConfirmationMode::Auto => bail!(
+            "This operation requires confirmation, but the session is not interactive. \
+             Pass --yes to confirm or --interactive to prompt anyway."
+        ),
+    }
+}
+
 /// Prints a message and loops until yes or no is entered.
 pub fn prompt_to_continue(message: &str) -> io::Result<bool> {
     loop {
@@ -10,7 +48,13 @@ pub fn prompt_to_continue(message: &str) -> io::Result<bool> {
         io::stdout().flush()?;
 
         let mut buf = String::new();
-        io::stdin().read_line(&mut buf)?;
+        if io::stdin().read_line(&mut buf)? == 0 {
+            println!();
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no input available to answer the prompt",
+            ));
+        }
         let input = buf.trim();
 
         if input == "y" {
@@ -67,7 +111,7 @@ where
 
 /// Creates a progress bar for byte stuff
 pub fn make_byte_progress_bar(length: u64) -> ProgressBar {
-    let pb = ProgressBar::new(length as usize);
+    let pb = ProgressBar::new_bytes(length);
     pb.set_style(
         ProgressStyle::default_bar().template("{wide_bar}  {bytes}/{total_bytes} ({eta})"),
     );
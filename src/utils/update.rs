@@ -19,6 +19,7 @@ use serde::{Deserialize, Serialize};
 use crate::api::{Api, SentryCliRelease};
 use crate::config::Config;
 use crate::constants::{APP_NAME, VERSION};
+use crate::utils::fs::CacheLock;
 #[cfg(not(feature = "managed"))]
 use crate::utils::fs::{is_writable, set_executable_mode};
 #[cfg(not(feature = "managed"))]
@@ -213,12 +214,18 @@ fn update_nagger_impl() -> Result<()> {
 
     path.push(APP_NAME);
     fs::create_dir_all(path.clone())?;
+    let dir = path.clone();
     path.push("updatecheck");
 
-    let mut check: LastUpdateCheck = if let Ok(f) = fs::File::open(&path) {
-        serde_json::from_reader(io::BufReader::new(f))?
-    } else {
-        Default::default()
+    // The cache is only locked while the file is accessed, not during the
+    // request for the latest release.
+    let mut check: LastUpdateCheck = {
+        let _lock = CacheLock::acquire(&dir)?;
+        if let Ok(f) = fs::File::open(&path) {
+            serde_json::from_reader(io::BufReader::new(f))?
+        } else {
+            Default::default()
+        }
     };
 
     if check.should_run_check() {
@@ -226,6 +233,7 @@ fn update_nagger_impl() -> Result<()> {
         let ui = get_latest_sentrycli_release()?;
         if ui.have_version_info() {
             check.update_for_info(&ui);
+            let _lock = CacheLock::acquire(&dir)?;
             let mut f = fs::File::create(&path)?;
             serde_json::to_writer_pretty(&mut f, &check)?;
             f.write_all(b"\n")?;
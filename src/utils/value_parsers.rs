@@ -1,5 +1,6 @@
 use crate::utils::auth_token::AuthToken;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+use regex::Regex;
 use std::convert::Infallible;
 
 /// Parse key:value pair from string, used as a value_parser for Clap arguments
@@ -9,6 +10,30 @@ pub fn kv_parser(s: &str) -> Result<(String, String)> {
         .ok_or_else(|| anyhow!("`{s}` is missing a `:`"))
 }
 
+/// Parse a regular expression with a named `dist` capture group
+pub fn dist_pattern_parser(s: &str) -> Result<Regex> {
+    let pattern = Regex::new(s)?;
+    if !pattern.capture_names().any(|name| name == Some("dist")) {
+        bail!("`{s}` has no capture group named `dist`, e.g. `(?P<dist>[^.]+)`");
+    }
+    Ok(pattern)
+}
+
+/// The lowest accepted request rate, one request every 1000 seconds.
+const MIN_RATE_LIMIT: f64 = 0.001;
+
+/// Parse a request rate such as `5` or `5/sec`, in requests per second
+pub fn rate_limit_parser(s: &str) -> Result<f64> {
+    let number = s
+        .strip_suffix("/sec")
+        .or_else(|| s.strip_suffix("/s"))
+        .unwrap_or(s);
+    match number.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate >= MIN_RATE_LIMIT => Ok(rate),
+        _ => bail!("`{s}` is not a number of requests per second of at least {MIN_RATE_LIMIT}"),
+    }
+}
+
 /// Parse an AuthToken, and warn if the format is unrecognized
 pub fn auth_token_parser(s: &str) -> Result<AuthToken, Infallible> {
     let token = AuthToken::from(s);
@@ -0,0 +1,5 @@
+static volatile int greeted;
+
+static void greet(int count) {
+    greeted += count;
+}
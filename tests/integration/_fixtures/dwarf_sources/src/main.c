@@ -0,0 +1,8 @@
+#include "greeting.h"
+#include "missing.h"
+
+void _start(void) {
+    for (;;) {
+        greet(count());
+    }
+}
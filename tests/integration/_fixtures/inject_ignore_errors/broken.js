@@ -0,0 +1,2 @@
+console.log("broken");
+//# sourceMappingURL=data:application/json;base64,not-base64!
@@ -0,0 +1,2 @@
+console.log("android");
+//# sourceMappingURL=app.android.js.map
@@ -0,0 +1,2 @@
+console.log("ios");
+//# sourceMappingURL=app.ios.js.map
@@ -0,0 +1,2 @@
+function hello(){}
+//# sourceMappingURL=data:application/json;base64,eyJ2ZXJzaW9uIjozLCJmaWxlIjoiYXBwLm1pbi5qcyIsInNvdXJjZXMiOlsiYXBwLmpzIl0sIm5hbWVzIjpbImhlbGxvIl0sIm1hcHBpbmdzIjoiQUFBQSxTQUFTQSJ9
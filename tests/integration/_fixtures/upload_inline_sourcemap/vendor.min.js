@@ -0,0 +1,2 @@
+var vendor=1;
+//# sourceMappingURL=data:application/json;charset=utf-8,%7B%22version%22%3A3%2C%22file%22%3A%22vendor.min.js%22%2C%22sources%22%3A%5B%22vendor.js%22%5D%2C%22names%22%3A%5B%5D%2C%22mappings%22%3A%22AAAA%22%7D
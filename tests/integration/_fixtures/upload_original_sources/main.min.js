@@ -0,0 +1,2 @@
+function b(){}b();
+//# sourceMappingURL=main.min.js.map
@@ -0,0 +1,2 @@
+function a(){}a();
+//# sourceMappingURL=bundle.js.map
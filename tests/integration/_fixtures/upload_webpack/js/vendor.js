@@ -0,0 +1,2 @@
+console.log("vendor");
+//# sourceMappingURL=vendor.js.map
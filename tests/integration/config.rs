@@ -0,0 +1,74 @@
+use crate::integration::TestManager;
+
+#[test]
+fn command_config_get_help() {
+    TestManager::new().register_trycmd_test("config/config-get-help.trycmd");
+}
+
+#[test]
+fn command_config_get() {
+    TestManager::new().register_trycmd_test("config/config-get.trycmd");
+}
+
+#[test]
+fn command_config_get_explain() {
+    TestManager::new().register_trycmd_test("config/config-get-explain.trycmd");
+}
+
+#[test]
+fn command_config_get_url_explain() {
+    TestManager::new()
+        .register_trycmd_test("config/config-get-url-explain.trycmd")
+        .with_server_var()
+        .expect("Failed to set server variable");
+}
+
+#[test]
+fn command_config_get_invalid_key() {
+    TestManager::new().register_trycmd_test("config/config-get-invalid-key.trycmd");
+}
+
+#[test]
+fn command_config_dump_help() {
+    TestManager::new().register_trycmd_test("config/config-dump-help.trycmd");
+}
+
+#[test]
+fn command_config_dump() {
+    TestManager::new()
+        .register_trycmd_test("config/config-dump.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_config_dump_include_secrets() {
+    TestManager::new()
+        .register_trycmd_test("config/config-dump-include-secrets.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_config_get_no_newline() {
+    TestManager::new().register_trycmd_test("config/config-get-no-newline.trycmd");
+}
+
+#[test]
+fn command_config_get_force_foreground_color() {
+    TestManager::new().register_trycmd_test("config/config-get-force-foreground-color.trycmd");
+}
+
+#[test]
+fn command_config_get_print_env() {
+    TestManager::new()
+        .register_trycmd_test("config/config-get-print-env.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn print_env_before_resolving_numeric_project() {
+    // No server is listening, so resolving the numeric project id fails.
+    TestManager::new()
+        .register_trycmd_test("config/print-env-numeric-project.trycmd")
+        .with_default_token()
+        .env("SENTRY_PROJECT", "42");
+}
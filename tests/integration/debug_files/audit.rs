@@ -0,0 +1,52 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+/// Mocks a server that only has the `fibonacci` binary of the
+/// `chunk_upload_multiple_files` fixture.
+fn mock_difs(manager: TestManager) -> TestManager {
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=8c3dd266-abf0-3c95-9163-6a0686a35453",
+            )
+            .with_response_file("debug_files/get-difs-by-debug-id.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=0f072295-8909-3c3a-a68c-b66b52c036bb",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=9ed13ad4-567b-3c31-b5a5-668c0b0be421",
+            )
+            .with_response_body("[]"),
+        )
+}
+
+#[test]
+fn command_debug_files_audit() {
+    mock_difs(TestManager::new())
+        .register_trycmd_test("debug_files/audit/debug_files-audit.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_audit_report_only() {
+    mock_difs(TestManager::new())
+        .register_trycmd_test("debug_files/audit/debug_files-audit-report-only.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_audit_json() {
+    mock_difs(TestManager::new())
+        .register_trycmd_test("debug_files/audit/debug_files-audit-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -1,5 +1,6 @@
 use crate::integration::TestManager;
 
+mod audit;
 mod bundle_jvm;
 mod upload;
 
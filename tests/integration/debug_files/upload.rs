@@ -1,5 +1,7 @@
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::LazyLock;
+use std::sync::{LazyLock, Mutex};
 use std::{fs, str};
 
 use regex::bytes::Regex;
@@ -35,6 +37,24 @@ fn command_debug_files_upload() {
         .with_default_token();
 }
 
+#[test]
+fn command_debug_files_upload_paths_from_dsym() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body("{}"),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-paths-from-dsym.trycmd")
+        .with_default_token();
+}
+
 #[test]
 fn command_debug_files_upload_pdb() {
     TestManager::new()
@@ -61,6 +81,52 @@ fn command_debug_files_upload_pdb() {
         .with_default_token();
 }
 
+#[test]
+fn command_debug_files_upload_metrics_summary() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "5f81d6becc51980870acc9f6636ab53d26160763": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            ),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-metrics-summary.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_retry_idempotent_post() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_status(500)
+            .expect(2),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-retry-idempotent-post.trycmd")
+        .with_default_token()
+        .env("SENTRY_HTTP_MAX_RETRIES", "1")
+        .assert_mock_endpoints();
+}
+
 #[test]
 fn command_debug_files_upload_pdb_embedded_sources() {
     TestManager::new()
@@ -134,10 +200,6 @@ fn command_debug_files_upload_mixed_embedded_sources() {
                 }"#,
             ),
         )
-        // TODO this isn't tested properly at the moment, because `indicatif` ProgressBar (at least at the current version)
-        //      swallows debug logs printed while the progress bar is active and the session is not attended.
-        //      See how it's supposed to look like `debug_files-bundle_sources-mixed-embedded-sources.trycmd` and try it out
-        //      after an update of `indicatif` to the latest version (currently it's blocked by some other issues).
         .register_trycmd_test("debug_files/upload/debug_files-upload-mixed-embedded-sources.trycmd")
         .with_default_token();
 }
@@ -159,6 +221,127 @@ fn command_debug_files_upload_no_upload() {
         .register_trycmd_test("debug_files/upload/debug_files-upload-no-upload.trycmd");
 }
 
+#[test]
+fn command_debug_files_upload_legacy_protocol() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/unknown/?checksums=4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8",
+            )
+            .with_response_body(r#"{"missing": ["4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8"]}"#),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/files/dsyms/")
+                .with_response_body("[]"),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-legacy-protocol.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_chunked_protocol_unsupported() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(
+                    r#"{
+                        "url": "organizations/wat-org/chunk-upload/",
+                        "chunkSize": 8388608,
+                        "chunksPerRequest": 64,
+                        "maxRequestSize": 33554432,
+                        "concurrency": 8,
+                        "hashAlgorithm": "sha1",
+                        "accept": ["release_files"]
+                    }"#,
+                ),
+        )
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-chunked-protocol-unsupported.trycmd",
+        )
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_exclude_dir() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-exclude-dir.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_prune_after() {
+    let testcase_cwd =
+        Path::new("tests/integration/_cases/debug_files/upload/debug_files-upload-prune-after.in/");
+    if testcase_cwd.exists() {
+        fs::remove_dir_all(testcase_cwd).unwrap();
+    }
+    fs::create_dir_all(testcase_cwd).unwrap();
+    fs::copy(
+        "tests/integration/_fixtures/elf-Linux-ARMv7-ls",
+        testcase_cwd.join("elf-Linux-ARMv7-ls"),
+    )
+    .unwrap();
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_file("debug_files/post-difs-assemble.json")
+            .expect(2),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-prune-after.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_on_conflict_prefer_first() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_file("debug_files/post-difs-assemble.json"),
+        )
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-on-conflict-prefer-first.trycmd",
+        )
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_on_conflict_error() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-on-conflict-error.trycmd")
+        .with_default_token();
+}
+
 #[test]
 /// This test ensures that the correct initial call to the debug files assemble endpoint is made.
 /// The mock assemble endpoint returns a 200 response simulating the case where all chunks
@@ -192,6 +375,42 @@ fn ensure_correct_assemble_call() {
         .run_and_assert(AssertCommand::Success);
 }
 
+#[test]
+/// This test verifies that with `--no-dedupe`, chunks are uploaded even though the
+/// server reports that it already has them, and that the files are assembled afterwards.
+fn chunk_upload_no_dedupe() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_body(
+                r#"{
+                "21b76b717dbbd8c89e42d92b29667ac87aa3c124": {
+                    "state": "ok",
+                    "missingChunks": []
+                }
+            }"#,
+            )
+            .expect(1),
+        )
+        .assert_cmd(
+            "debug-files upload --no-dedupe --include-sources tests/integration/_fixtures/SrcGenSampleApp.pdb"
+                .split(' '),
+        )
+        .with_default_token()
+        .run_and_assert(AssertCommand::Success);
+}
+
 #[test]
 /// This test simulates a full chunk upload (with only one chunk).
 /// It verifies that the Sentry CLI makes the expected API calls to the chunk upload endpoint
@@ -287,17 +506,14 @@ fn ensure_correct_chunk_upload() {
 }
 
 #[test]
-/// This test verifies a correct chunk upload of multiple debug files.
-fn chunk_upload_multiple_files() {
-    let expected_chunk_body = fs::read(
-        "tests/integration/_expected_requests/debug_files/upload/chunk_upload_multiple_files.bin",
-    )
-    .expect("expected chunk body file should be present");
-    // This is the boundary used in the expected request file.
-    // It was randomly generated when the expected request was recorded.
-    let boundary_of_expected_request = "------------------------b26LKrHFvpOPfwMoDhYNY8";
-
+/// This test verifies that `--multipart-boundary-charset simple` produces a
+/// purely alphanumeric boundary and otherwise the same chunk upload body.
+fn chunk_upload_simple_multipart_boundary() {
     let is_first_assemble_call = AtomicBool::new(true);
+    let expected_chunk_body =
+        fs::read("tests/integration/_expected_requests/debug_files/upload/chunk_upload.bin")
+            .expect("expected chunk body file should be present");
+
     TestManager::new()
         .mock_endpoint(
             MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
@@ -308,23 +524,19 @@ fn chunk_upload_multiple_files() {
                 .with_response_fn(move |request| {
                     let boundary = chunk_upload::boundary_from_request(request)
                         .expect("content-type header should be a valid multipart/form-data header");
+                    assert!(
+                        boundary.chars().all(|c| c.is_ascii_alphanumeric()),
+                        "boundary {boundary:?} should only contain letters and digits"
+                    );
 
-                    let body = request.body().expect("body should be readable");
-
-                    let chunks = chunk_upload::split_chunk_body(body, boundary)
-                        .expect("body should be a valid multipart/form-data body");
-
-                    let expected_chunks = chunk_upload::split_chunk_body(
-                        &expected_chunk_body,
-                        boundary_of_expected_request,
-                    )
-                    .expect("expected chunk body is a valid multipart/form-data body");
+                    let mut expected_body = format!("--{boundary}").into_bytes();
+                    expected_body.extend_from_slice(&expected_chunk_body);
+                    expected_body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
 
-                    // Using assert! because in case of failure, the output with assert_eq!
-                    // is too long to be useful.
+                    let body = request.body().expect("body should be readable");
                     assert!(
-                        chunks == expected_chunks,
-                        "Uploaded chunks differ from the expected chunks"
+                        *body == expected_body,
+                        "Uploaded body differs from the expected body"
                     );
 
                     vec![]
@@ -335,34 +547,70 @@ fn chunk_upload_multiple_files() {
                 "POST",
                 "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
             )
-            .with_header_matcher("content-type", "application/json")
             .with_response_fn(move |_| {
                 if is_first_assemble_call.swap(false, Ordering::Relaxed) {
                     r#"{
-                        "6e217f035ed538d4d6c14129baad5cb52e680e74": {
-                            "state": "not_found",
-                            "missingChunks": ["6e217f035ed538d4d6c14129baad5cb52e680e74"]
-                        },
-                        "500848b7815119669a292f2ae1f44af11d7aa2d3": {
-                            "state": "not_found",
-                            "missingChunks": ["500848b7815119669a292f2ae1f44af11d7aa2d3"]
-                        },
-                        "fc27d95861d56fe16a2b66150e31652b76e8c678": {
+                        "21b76b717dbbd8c89e42d92b29667ac87aa3c124": {
                             "state": "not_found",
-                            "missingChunks": ["fc27d95861d56fe16a2b66150e31652b76e8c678"]
+                            "missingChunks": ["21b76b717dbbd8c89e42d92b29667ac87aa3c124"]
                         }
                     }"#
                 } else {
                     r#"{
-                        "6e217f035ed538d4d6c14129baad5cb52e680e74": {
-                            "state": "created",
-                            "missingChunks": []
-                        },
-                        "500848b7815119669a292f2ae1f44af11d7aa2d3": {
+                        "21b76b717dbbd8c89e42d92b29667ac87aa3c124": {
                             "state": "created",
                             "missingChunks": []
-                        },
-                        "fc27d95861d56fe16a2b66150e31652b76e8c678": {
+                        }
+                    }"#
+                }
+                .into()
+            })
+            .expect(2),
+        )
+        .assert_cmd(
+            "--multipart-boundary-charset simple debug-files upload --include-sources \
+             tests/integration/_fixtures/SrcGenSampleApp.pdb"
+                .split_whitespace(),
+        )
+        .with_default_token()
+        .run_and_assert(AssertCommand::Success);
+}
+
+#[test]
+/// This test verifies that `--dump-request-bodies` writes the exact chunk upload
+/// and assemble bodies, along with metadata with redacted credentials.
+fn chunk_upload_dump_request_bodies() {
+    let is_first_assemble_call = AtomicBool::new(true);
+    let expected_chunk_body =
+        fs::read("tests/integration/_expected_requests/debug_files/upload/chunk_upload.bin")
+            .expect("expected chunk body file should be present");
+    let dump_dir = tempfile::tempdir().expect("temp dir should be created");
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(move |_| {
+                if is_first_assemble_call.swap(false, Ordering::Relaxed) {
+                    r#"{
+                        "21b76b717dbbd8c89e42d92b29667ac87aa3c124": {
+                            "state": "not_found",
+                            "missingChunks": ["21b76b717dbbd8c89e42d92b29667ac87aa3c124"]
+                        }
+                    }"#
+                } else {
+                    r#"{
+                        "21b76b717dbbd8c89e42d92b29667ac87aa3c124": {
                             "state": "created",
                             "missingChunks": []
                         }
@@ -372,7 +620,202 @@ fn chunk_upload_multiple_files() {
             })
             .expect(2),
         )
-        .assert_cmd(vec![
+        .assert_cmd([
+            "--dump-request-bodies",
+            dump_dir.path().to_str().unwrap(),
+            "debug-files",
+            "upload",
+            "--include-sources",
+            "tests/integration/_fixtures/SrcGenSampleApp.pdb",
+        ])
+        .with_default_token()
+        .run_and_assert(AssertCommand::Success);
+
+    let read_dump =
+        |name: &str| fs::read(dump_dir.path().join(name)).expect("dumped file should be present");
+
+    // The GET request has no body, so only its metadata is dumped.
+    assert!(!dump_dir.path().join("0001.bin").exists());
+    let metadata = String::from_utf8(read_dump("0001.meta.json")).unwrap();
+    assert!(metadata.contains("\"method\": \"GET\""));
+    assert!(metadata.contains("Authorization: [redacted]"));
+    assert!(!metadata.contains("Bearer"));
+
+    assert_eq!(
+        read_dump("0002.json"),
+        br#"{"21b76b717dbbd8c89e42d92b29667ac87aa3c124":{"name":"SrcGenSampleApp.pdb","debug_id":"c02651ae-cd6f-492d-bc33-0b83111e7106-8d8e7c60","chunks":["21b76b717dbbd8c89e42d92b29667ac87aa3c124"]}}"#
+    );
+
+    let chunk_upload = read_dump("0003.bin");
+    let boundary_end = chunk_upload
+        .windows(2)
+        .position(|window| window == b"\r\n")
+        .expect("boundary line should be present");
+    let boundary = str::from_utf8(&chunk_upload[2..boundary_end]).unwrap();
+    let mut expected_body = format!("--{boundary}").into_bytes();
+    expected_body.extend_from_slice(&expected_chunk_body);
+    expected_body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    assert!(
+        chunk_upload == expected_body,
+        "Dumped chunk upload body differs from the expected body"
+    );
+
+    assert!(dump_dir.path().join("0004.json").exists());
+}
+
+#[test]
+/// This test verifies a correct chunk upload of multiple debug files.
+fn chunk_upload_multiple_files() {
+    let expected_chunk_body = fs::read(
+        "tests/integration/_expected_requests/debug_files/upload/chunk_upload_multiple_files.bin",
+    )
+    .expect("expected chunk body file should be present");
+    // This is the boundary used in the expected request file.
+    // It was randomly generated when the expected request was recorded.
+    let boundary_of_expected_request = "------------------------b26LKrHFvpOPfwMoDhYNY8";
+
+    let is_first_assemble_call = AtomicBool::new(true);
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_fn(move |request| {
+                    let boundary = chunk_upload::boundary_from_request(request)
+                        .expect("content-type header should be a valid multipart/form-data header");
+
+                    let body = request.body().expect("body should be readable");
+
+                    let chunks = chunk_upload::split_chunk_body(body, boundary)
+                        .expect("body should be a valid multipart/form-data body");
+
+                    let expected_chunks = chunk_upload::split_chunk_body(
+                        &expected_chunk_body,
+                        boundary_of_expected_request,
+                    )
+                    .expect("expected chunk body is a valid multipart/form-data body");
+
+                    // Using assert! because in case of failure, the output with assert_eq!
+                    // is too long to be useful.
+                    assert!(
+                        chunks == expected_chunks,
+                        "Uploaded chunks differ from the expected chunks"
+                    );
+
+                    vec![]
+                }),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_header_matcher("content-type", "application/json")
+            .with_response_fn(move |_| {
+                if is_first_assemble_call.swap(false, Ordering::Relaxed) {
+                    r#"{
+                        "6e217f035ed538d4d6c14129baad5cb52e680e74": {
+                            "state": "not_found",
+                            "missingChunks": ["6e217f035ed538d4d6c14129baad5cb52e680e74"]
+                        },
+                        "500848b7815119669a292f2ae1f44af11d7aa2d3": {
+                            "state": "not_found",
+                            "missingChunks": ["500848b7815119669a292f2ae1f44af11d7aa2d3"]
+                        },
+                        "fc27d95861d56fe16a2b66150e31652b76e8c678": {
+                            "state": "not_found",
+                            "missingChunks": ["fc27d95861d56fe16a2b66150e31652b76e8c678"]
+                        }
+                    }"#
+                } else {
+                    r#"{
+                        "6e217f035ed538d4d6c14129baad5cb52e680e74": {
+                            "state": "created",
+                            "missingChunks": []
+                        },
+                        "500848b7815119669a292f2ae1f44af11d7aa2d3": {
+                            "state": "created",
+                            "missingChunks": []
+                        },
+                        "fc27d95861d56fe16a2b66150e31652b76e8c678": {
+                            "state": "created",
+                            "missingChunks": []
+                        }
+                    }"#
+                }
+                .into()
+            })
+            .expect(2),
+        )
+        .assert_cmd(vec![
+            "debug-files",
+            "upload",
+            "tests/integration/_fixtures/debug_files/upload/chunk_upload_multiple_files",
+        ])
+        .with_default_token()
+        .run_and_assert(AssertCommand::Success);
+}
+
+#[test]
+/// This test verifies that `--max-chunk-batch` lowers the number of chunks per request.
+fn chunk_upload_max_chunk_batch() {
+    let is_first_assemble_call = AtomicBool::new(true);
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect(3),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(move |_| {
+                if is_first_assemble_call.swap(false, Ordering::Relaxed) {
+                    r#"{
+                        "6e217f035ed538d4d6c14129baad5cb52e680e74": {
+                            "state": "not_found",
+                            "missingChunks": ["6e217f035ed538d4d6c14129baad5cb52e680e74"]
+                        },
+                        "500848b7815119669a292f2ae1f44af11d7aa2d3": {
+                            "state": "not_found",
+                            "missingChunks": ["500848b7815119669a292f2ae1f44af11d7aa2d3"]
+                        },
+                        "fc27d95861d56fe16a2b66150e31652b76e8c678": {
+                            "state": "not_found",
+                            "missingChunks": ["fc27d95861d56fe16a2b66150e31652b76e8c678"]
+                        }
+                    }"#
+                } else {
+                    r#"{
+                        "6e217f035ed538d4d6c14129baad5cb52e680e74": {
+                            "state": "created",
+                            "missingChunks": []
+                        },
+                        "500848b7815119669a292f2ae1f44af11d7aa2d3": {
+                            "state": "created",
+                            "missingChunks": []
+                        },
+                        "fc27d95861d56fe16a2b66150e31652b76e8c678": {
+                            "state": "created",
+                            "missingChunks": []
+                        }
+                    }"#
+                }
+                .into()
+            })
+            .expect(2),
+        )
+        .assert_cmd(vec![
+            "--max-chunk-batch",
+            "1",
             "debug-files",
             "upload",
             "tests/integration/_fixtures/debug_files/upload/chunk_upload_multiple_files",
@@ -380,3 +823,519 @@ fn chunk_upload_multiple_files() {
         .with_default_token()
         .run_and_assert(AssertCommand::Success);
 }
+
+/// Chunk upload options with small requests and no concurrency, so that each
+/// pipelined batch only holds a few of the test files.
+const SMALL_BATCH_CHUNK_UPLOAD: &str = r#"{
+    "url": "organizations/wat-org/chunk-upload/",
+    "chunkSize": 8388608,
+    "chunksPerRequest": 64,
+    "maxFileSize": 2147483648,
+    "maxRequestSize": 500000,
+    "concurrency": 1,
+    "hashAlgorithm": "sha1",
+    "accept": ["debug_files"]
+}"#;
+
+/// Responds to assemble requests like a server that has none of the files initially and
+/// assembles each file once it is requested again.
+fn assemble_on_second_request() -> impl Fn(&mockito::Request) -> Vec<u8> + Send + Sync {
+    let requested = Mutex::new(HashSet::new());
+    move |request| {
+        let body: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(request.body().expect("body should be readable"))
+                .expect("assemble request should be a JSON object");
+        let mut requested = requested.lock().unwrap();
+        let response: serde_json::Map<_, _> = body
+            .into_iter()
+            .map(|(checksum, file)| {
+                let state = if requested.insert(checksum.clone()) {
+                    serde_json::json!({ "state": "not_found", "missingChunks": file["chunks"] })
+                } else {
+                    serde_json::json!({ "state": "created", "missingChunks": [] })
+                };
+                (checksum, state)
+            })
+            .collect();
+        serde_json::to_vec(&response).unwrap()
+    }
+}
+
+#[test]
+/// This test verifies that files are uploaded in batches while they are hashed. The first
+/// two files make up the first batch, the last file is uploaded in a second batch, and all
+/// files are assembled in a final request.
+fn chunk_upload_pipelined_batches() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(SMALL_BATCH_CHUNK_UPLOAD),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(3),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_on_second_request())
+            .expect(3),
+        )
+        .assert_cmd(vec![
+            "debug-files",
+            "upload",
+            "tests/integration/_fixtures/debug_files/upload/chunk_upload_multiple_files",
+        ])
+        .with_default_token()
+        .run_and_assert(AssertCommand::Success);
+}
+
+#[test]
+/// This test verifies that with `--no-pipeline`, all files are hashed first and checked
+/// in a single assemble request before uploading.
+fn chunk_upload_no_pipeline() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(SMALL_BATCH_CHUNK_UPLOAD),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(3),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_on_second_request())
+            .expect(2),
+        )
+        .assert_cmd(vec![
+            "debug-files",
+            "upload",
+            "--no-pipeline",
+            "tests/integration/_fixtures/debug_files/upload/chunk_upload_multiple_files",
+        ])
+        .with_default_token()
+        .run_and_assert(AssertCommand::Success);
+}
+
+#[test]
+fn command_debug_files_upload_require_inlines() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-require-inlines.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+/// Responds to assemble requests like `assemble_on_second_request`, except that the file
+/// with the lowest checksum fails to process.
+fn assemble_with_one_error() -> impl Fn(&mockito::Request) -> Vec<u8> + Send + Sync {
+    let requested = Mutex::new(HashSet::new());
+    move |request| {
+        let body: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(request.body().expect("body should be readable"))
+                .expect("assemble request should be a JSON object");
+        let failing = body.keys().min().cloned();
+        let mut requested = requested.lock().unwrap();
+        let response: serde_json::Map<_, _> = body
+            .into_iter()
+            .map(|(checksum, file)| {
+                let state = if requested.insert(checksum.clone()) {
+                    serde_json::json!({ "state": "not_found", "missingChunks": file["chunks"] })
+                } else if Some(&checksum) == failing.as_ref() {
+                    serde_json::json!({ "state": "error", "detail": "Invalid debug file", "missingChunks": [] })
+                } else {
+                    serde_json::json!({ "state": "ok", "missingChunks": [] })
+                };
+                (checksum, state)
+            })
+            .collect();
+        serde_json::to_vec(&response).unwrap()
+    }
+}
+
+#[test]
+fn command_debug_files_upload_partial_assemble() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(SMALL_BATCH_CHUNK_UPLOAD),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_with_one_error()),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-partial-assemble.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_json() {
+    // The server already has the file with the highest checksum.
+    let assemble_with_one_error = assemble_with_one_error();
+    let assemble = move |request: &mockito::Request| {
+        let body: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(request.body().expect("body should be readable"))
+                .expect("assemble request should be a JSON object");
+        let existing = body.keys().max().cloned();
+        let mut response: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&assemble_with_one_error(request)).unwrap();
+        if let Some(existing) = existing {
+            response.insert(
+                existing,
+                serde_json::json!({ "state": "ok", "missingChunks": [] }),
+            );
+        }
+        serde_json::to_vec(&response).unwrap()
+    };
+
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(SMALL_BATCH_CHUNK_UPLOAD),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-json.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_abort_on_partial_assemble() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(SMALL_BATCH_CHUNK_UPLOAD),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_with_one_error()),
+        )
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-abort-on-partial-assemble.trycmd",
+        )
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_glob() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(SMALL_BATCH_CHUNK_UPLOAD),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_on_second_request()),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-glob.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_glob_no_match() {
+    TestManager::new()
+        .register_trycmd_test("debug_files/upload/debug_files-upload-glob-no-match.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_debug_files_upload_glob_allow_empty() {
+    TestManager::new()
+        .register_trycmd_test("debug_files/upload/debug_files-upload-glob-allow-empty.trycmd")
+        .with_default_token();
+}
+
+/// Responds to assemble requests for `elf-Linux-ARMv7-ls` like a server that does not have
+/// the file initially and has processed it once it is requested again.
+fn assemble_elf_on_second_request() -> impl Fn(&mockito::Request) -> Vec<u8> + Send + Sync {
+    let is_first_assemble_call = AtomicBool::new(true);
+    move |_| {
+        if is_first_assemble_call.swap(false, Ordering::Relaxed) {
+            r#"{
+                "4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {
+                    "state": "not_found",
+                    "missingChunks": ["4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8"]
+                }
+            }"#
+            .into()
+        } else {
+            fs::read("tests/integration/_responses/debug_files/post-difs-assemble.json")
+                .expect("assemble response should be present")
+        }
+    }
+}
+
+#[test]
+fn command_debug_files_upload_verify_upload() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_elf_on_second_request())
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=307a5402-9480-8ec2-25f1-a4adc744a991",
+            )
+            .with_response_file("debug_files/get-dsyms-elf.json")
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-verify-upload.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_verify_upload_missing() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_elf_on_second_request())
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/files/dsyms/?debug_id=307a5402-9480-8ec2-25f1-a4adc744a991",
+            )
+            .with_response_body("[]")
+            .expect(1),
+        )
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-verify-upload-missing.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_assemble_max_attempts() {
+    let is_first_assemble_call = AtomicBool::new(true);
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(move |_| {
+                let state = if is_first_assemble_call.swap(false, Ordering::Relaxed) {
+                    "not_found"
+                } else {
+                    "assembling"
+                };
+                format!(
+                    r#"{{
+                        "4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8": {{
+                            "state": "{state}",
+                            "missingChunks": ["4111bebacb6ccdd7e52784a16ca1b75f9c1d54b8"]
+                        }}
+                    }}"#
+                )
+                .into()
+            })
+            .expect(3),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-assemble-max-attempts.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_dwarf_include_sources() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_on_second_request())
+            .expect(2),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-dwarf-include-sources.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_max_concurrent_assembles() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(MockEndpointBuilder::new(
+            "POST",
+            "/api/0/organizations/wat-org/chunk-upload/",
+        ))
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_elf_on_second_request())
+            .expect(2),
+        )
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-max-concurrent-assembles.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_multiple_projects() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_response_fn(assemble_elf_on_second_request())
+            .expect(2),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/rejected-project/files/difs/assemble/",
+            )
+            .with_status(403)
+            .with_response_body(
+                r#"{"detail": "You do not have permission to perform this action."}"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/other-project/files/difs/assemble/",
+            )
+            .with_response_file("debug_files/post-difs-assemble.json")
+            .expect(1),
+        )
+        .register_trycmd_test("debug_files/upload/debug_files-upload-multiple-projects.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_debug_files_upload_multiple_projects_auth_error() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_file("debug_files/get-chunk-upload.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/files/difs/assemble/",
+            )
+            .with_status(401)
+            .with_response_body(r#"{"detail": "Invalid token"}"#)
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/other-project/files/difs/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test(
+            "debug_files/upload/debug_files-upload-multiple-projects-auth-error.trycmd",
+        )
+        .with_default_token()
+        .assert_mock_endpoints();
+}
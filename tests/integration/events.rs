@@ -12,3 +12,30 @@ fn command_events() {
         .register_trycmd_test("events/*.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_events_list_project_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_response_file("projects/get-projects.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/setcommits/events/?cursor=")
+                .with_response_body("[]"),
+        )
+        .register_trycmd_test("events/project_id/events-list-project-id.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_events_list_unknown_project_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_response_file("projects/get-projects.json"),
+        )
+        .register_trycmd_test("events/project_id/events-list-unknown-project-id.trycmd")
+        .with_default_token();
+}
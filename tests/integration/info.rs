@@ -36,6 +36,26 @@ fn command_info_basic() {
         .expect("Failed to set server variable");
 }
 
+#[test]
+fn command_info_api_version() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/")
+                .with_header_matcher("Accept", "application/json; version=1")
+                .with_response_file("info/get-info.json"),
+        )
+        .register_trycmd_test("info/info-api-version.trycmd")
+        .with_default_token()
+        .with_server_var()
+        .expect("Failed to set server variable")
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_info_invalid_proxy_url() {
+    TestManager::new().register_trycmd_test("info/info-invalid-proxy-url.trycmd");
+}
+
 #[test]
 fn command_info_no_defaults() {
     TestManager::new()
@@ -73,3 +93,28 @@ fn command_info_json_without_defaults() {
         .with_server_var()
         .expect("Failed to set server variable");
 }
+
+#[test]
+fn command_info_list_regions() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/users/me/regions/")
+                .with_response_file("info/get-regions.json")
+                .expect(2),
+        )
+        .register_trycmd_test("info/info-list-regions.trycmd")
+        .register_trycmd_test("info/info-list-regions-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_info_list_regions_self_hosted() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("GET", "/api/0/users/me/regions/").with_status(404))
+        .register_trycmd_test("info/info-list-regions-self-hosted.trycmd")
+        .with_default_token()
+        .with_server_var()
+        .expect("Failed to set server variable")
+        .assert_mock_endpoints();
+}
@@ -0,0 +1,94 @@
+use mockito::Matcher;
+use serde_json::json;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_issues_assign_help() {
+    TestManager::new().register_trycmd_test("issues/issues-assign-help.trycmd");
+}
+
+#[test]
+fn assign_issue_to_user() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_response_file("issues/get-issue.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/members/?cursor=")
+                .with_response_file("issues/get-organization-members.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_matcher(Matcher::PartialJson(json!({ "assignedTo": "user:1" })))
+                .with_response_file("issues/get-issue.json"),
+        )
+        .register_trycmd_test("issues/issues-assign-user.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn assign_issue_to_team() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_response_file("issues/get-issue.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/teams/?cursor=")
+                .with_response_file("issues/get-organization-teams.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_matcher(Matcher::PartialJson(json!({ "assignedTo": "team:7" })))
+                .with_response_file("issues/get-issue.json"),
+        )
+        .register_trycmd_test("issues/issues-assign-team.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn assign_issue_to_unknown_user() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_response_file("issues/get-issue.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/members/?cursor=")
+                .with_response_file("issues/get-organization-members.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/issues/4242424243/")
+                .expect(0),
+        )
+        .register_trycmd_test("issues/issues-assign-unknown-user.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn assign_issue_forbidden() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_response_file("issues/get-issue.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/members/?cursor=")
+                .with_response_file("issues/get-organization-members.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_status(403)
+                .with_response_body(
+                    r#"{"detail": "You do not have permission to perform this action."}"#,
+                ),
+        )
+        .register_trycmd_test("issues/issues-assign-forbidden.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -0,0 +1,43 @@
+use mockito::Matcher;
+use serde_json::json;
+
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_issues_comment_help() {
+    TestManager::new().register_trycmd_test("issues/issues-comment-help.trycmd");
+}
+
+#[test]
+fn comment_on_issue() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_response_file("issues/get-issue.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/issues/4242424243/comments/",
+            )
+            .with_status(201)
+            .with_matcher(Matcher::PartialJson(json!({ "text": "Investigating" })))
+            .with_response_body(r#"{"id": "9001", "type": "note"}"#),
+        )
+        .register_trycmd_test("issues/issues-comment.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn comment_on_missing_issue() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/issues/1/")
+                .with_status(404)
+                .with_response_body(r#"{"detail": "The requested resource does not exist"}"#),
+        )
+        .register_trycmd_test("issues/issues-comment-not-found.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
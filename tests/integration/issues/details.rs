@@ -0,0 +1,37 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_issues_details_help() {
+    TestManager::new().register_trycmd_test("issues/issues-details-help.trycmd");
+}
+
+#[test]
+fn display_issue_by_short_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/shortids/SEN-CLI-L/")
+                .with_response_file("issues/get-issue-short-id.json"),
+        )
+        .register_trycmd_test("issues/issues-details.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn display_issue_as_json_with_latest_event() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/issues/4242424243/")
+                .with_response_file("issues/get-issue.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/issues/4242424243/events/latest/",
+            )
+            .with_response_file("issues/get-issue-latest-event.json"),
+        )
+        .register_trycmd_test("issues/issues-details-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
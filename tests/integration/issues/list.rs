@@ -46,3 +46,48 @@ fn display_resolved_issues() {
         .register_trycmd_test("issues/issues-display-with-query.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn display_issues_with_sort_and_stats_period() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/issues/?query=&sort=freq&statsPeriod=24h&cursor=",
+            )
+            .with_response_file("issues/get-issues-stats.json")
+            .expect(2),
+        )
+        .register_trycmd_test("issues/issues-list-sort-stats-period.trycmd")
+        .register_trycmd_test("issues/issues-list-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn rate_limited_issues_are_retried() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/issues/?query=&cursor=",
+            )
+            .with_status(429)
+            .with_response_header("Retry-After", "0")
+            .with_response_body(
+                r#"{"detail": "You are attempting to use this endpoint too frequently."}"#,
+            )
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/issues/?query=&cursor=",
+            )
+            .with_response_file("issues/get-issues.json")
+            .expect(1),
+        )
+        .register_trycmd_test("issues/issues-list-rate-limit.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
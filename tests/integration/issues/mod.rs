@@ -1,5 +1,8 @@
 use crate::integration::TestManager;
 
+mod assign;
+mod comment;
+mod details;
 mod list;
 
 #[test]
@@ -1,4 +1,5 @@
 mod bash_hook;
+mod config;
 mod debug_files;
 mod deploys;
 mod events;
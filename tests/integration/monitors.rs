@@ -33,3 +33,23 @@ fn command_monitors_run_server_error() {
     #[cfg(windows)]
     manager.register_trycmd_test("monitors/server_error/monitors-run-server-error-win.trycmd");
 }
+
+#[cfg(not(windows))]
+#[test]
+fn command_monitors_run_keep_alive_checkins() {
+    // The in-progress, final and at least one keep-alive check-in.
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect_at_least(3))
+        .register_trycmd_test("monitors/keep_alive/monitors-run-keep-alive-checkins.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_monitors_run_heartbeat_jitter_too_large() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/").expect(0))
+        .register_trycmd_test("monitors/keep_alive/monitors-run-heartbeat-jitter-too-large.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -1,3 +1,5 @@
+use mockito::Matcher;
+
 use crate::integration::{MockEndpointBuilder, TestManager};
 
 #[test]
@@ -11,3 +13,116 @@ fn command_projects_list() {
         .register_trycmd_test("projects/*.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_projects_list_html_response() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_response_file("projects/sso-login.html"),
+        )
+        .register_trycmd_test("projects/html_response/*.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_projects_list_html_error_status() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_status(502)
+                .with_response_file("projects/sso-login.html"),
+        )
+        .register_trycmd_test("projects/html_error_status/*.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_projects_list_unsupported_api_version() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_status(406),
+        )
+        .register_trycmd_test("projects/api_version/projects-list-unsupported.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_projects_list_unsupported_api_version_detail() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_status(406)
+                .with_response_body(r#"{"detail": "API version 0 requires the Accept header"}"#),
+        )
+        .register_trycmd_test("projects/api_version/projects-list-unsupported-detail.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_projects_list_batch_size() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/projects/?cursor=&per_page=100",
+            )
+            .with_response_file("projects/get-projects.json"),
+        )
+        .register_trycmd_test("projects/batch_size/*.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_projects_list_follows_redirects() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_status(302)
+                .with_response_header("Location", "/api/0/organizations/new-org/projects/?cursor="),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/new-org/projects/?cursor=")
+                .with_header_matcher("Authorization", Matcher::Regex("^Bearer ".into()))
+                .with_response_file("projects/get-projects.json"),
+        )
+        .register_trycmd_test("projects/redirects/projects-list-redirect.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_projects_list_cross_origin_redirect_drops_credentials() {
+    let manager = TestManager::new();
+    let target = format!(
+        "{}/api/0/organizations/new-org/projects/?cursor=",
+        manager.server_url().replace("127.0.0.1", "localhost"),
+    );
+
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_status(302)
+                .with_response_header("Location", target),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/new-org/projects/?cursor=")
+                .with_header_matcher("Authorization", Matcher::Missing)
+                .with_response_file("projects/get-projects.json"),
+        )
+        .register_trycmd_test("projects/redirects/projects-list-cross-origin-redirect.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_projects_list_too_many_redirects() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/projects/?cursor=")
+                .with_status(307)
+                .with_response_header("Location", "/api/0/organizations/wat-org/projects/?cursor=")
+                .expect(3),
+        )
+        .register_trycmd_test("projects/redirects/projects-list-too-many-redirects.trycmd")
+        .with_default_token();
+}
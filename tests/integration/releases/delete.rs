@@ -56,3 +56,37 @@ fn doesnt_allow_to_delete_active_releases() {
         .register_trycmd_test("releases/releases-delete-active.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn prints_request_id_of_failed_requests() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_status(400)
+            .with_response_header("X-Sentry-Request-Id", "f1d2d2f924e986ac86fdf7b36c94bcdf")
+            .with_response_file("releases/delete-active-release.json")
+            .expect(2),
+        )
+        .register_trycmd_test("releases/releases-delete-request-id.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_releases_delete_requires_confirmation() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_status(204)
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-delete-no-confirmation.trycmd")
+        .register_trycmd_test("releases/releases-delete-interactive-no-input.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
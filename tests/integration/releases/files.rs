@@ -0,0 +1,247 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_releases_files_upload_if_exists_skip() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_status(404),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body(
+                r#"[{
+                    "id": "1337",
+                    "name": "bundle.min.js.map",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "38ed853073df85147960ea3a5bced6170ec389b0",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }]"#,
+            ),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-files-upload-if-exists-skip.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_files_upload_if_exists_overwrite() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_status(404),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body(
+                r#"[{
+                    "id": "1337",
+                    "name": "bundle.min.js.map",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "38ed853073df85147960ea3a5bced6170ec389b0",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }]"#,
+            ),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "DELETE",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/1337/",
+            )
+            .with_status(204),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/",
+            )
+            .with_status(201)
+            .with_response_body(
+                r#"{
+                    "id": "1338",
+                    "name": "bundle.min.js.map",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "38ed853073df85147960ea3a5bced6170ec389b0",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }"#,
+            ),
+        )
+        .register_trycmd_test("releases/releases-files-upload-if-exists-overwrite.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_releases_files_upload_if_exists_artifact_bundles() {
+    let manager = TestManager::new();
+    let chunk_upload_response = format!(
+        r#"{{
+            "url": "{}/api/0/organizations/wat-org/chunk-upload/",
+            "chunkSize": 8388608,
+            "chunksPerRequest": 64,
+            "maxRequestSize": 33554432,
+            "concurrency": 8,
+            "hashAlgorithm": "sha1",
+            "accept": ["release_files", "artifact_bundles_v2"]
+        }}"#,
+        manager.server_url(),
+    );
+
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(chunk_upload_response),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/artifactbundle/assemble/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-files-upload-if-exists-artifact-bundles.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn command_releases_files_upload_large_file() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let state_dir = cache_dir.path().join("sentrycli").join("uploads");
+
+    let manager = TestManager::new();
+    let chunk_upload_response = format!(
+        r#"{{
+            "url": "{}/api/0/organizations/wat-org/chunk-upload/",
+            "chunkSize": 512,
+            "chunksPerRequest": 64,
+            "maxRequestSize": 33554432,
+            "concurrency": 8,
+            "hashAlgorithm": "sha1",
+            "accept": ["release_files"]
+        }}"#,
+        manager.server_url(),
+    );
+
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(chunk_upload_response),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body("[]")
+                .expect_at_least(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/organizations/wat-org/releases/wat-release/assemble/",
+            )
+            .with_response_body(r#"{"state": "created", "missingChunks": []}"#)
+            .expect_at_least(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/",
+            )
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-files-upload-large-file.trycmd")
+        .with_default_token()
+        .env("XDG_CACHE_HOME", cache_dir.path().to_str().unwrap())
+        .assert_mock_endpoints();
+
+    assert!(std::fs::read_dir(&state_dir).unwrap().next().is_none());
+}
+
+#[test]
+fn command_releases_files_upload_large_file_exists() {
+    let manager = TestManager::new();
+    let chunk_upload_response = format!(
+        r#"{{
+            "url": "{}/api/0/organizations/wat-org/chunk-upload/",
+            "chunkSize": 512,
+            "chunksPerRequest": 64,
+            "maxRequestSize": 33554432,
+            "concurrency": 8,
+            "hashAlgorithm": "sha1",
+            "accept": ["release_files"]
+        }}"#,
+        manager.server_url(),
+    );
+
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(chunk_upload_response),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body(
+                r#"[{
+                    "id": "1337",
+                    "name": "~/large-asset.txt",
+                    "headers": {},
+                    "size": 3400,
+                    "sha1": "38ed853073df85147960ea3a5bced6170ec389b0",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }]"#,
+            ),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/organizations/wat-org/chunk-upload/")
+                .expect(0),
+        )
+        .register_trycmd_test("releases/releases-files-upload-large-file-exists.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
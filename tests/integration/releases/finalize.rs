@@ -48,3 +48,62 @@ fn release_with_custom_dates() {
         .register_trycmd_test("releases/releases-finalize-dates.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn verify_before_finalize_fails_for_empty_release() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json")
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-finalize-verify-empty.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn verify_before_finalize_requires_artifacts() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_file("sourcemaps/get-artifacts.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/",
+            )
+            .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json"),
+        )
+        .register_trycmd_test("releases/releases-finalize-verify-require-artifacts.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -65,3 +65,52 @@ fn displays_releases_in_raw_mode_with_delimiter() {
         .register_trycmd_test("releases/releases-list-raw-delimiter.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn sorts_and_filters_releases() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_response_file("releases/get-releases-dates.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/?sort=date",
+            )
+            .with_response_file("releases/get-releases-dates.json"),
+        )
+        .register_trycmd_test("releases/releases-list-sort-version.trycmd")
+        .register_trycmd_test("releases/releases-list-sort-date-since.trycmd")
+        .register_trycmd_test("releases/releases-list-json-max-rows.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn fetches_pages_until_since() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/?sort=date",
+            )
+            .with_response_header(
+                "Link",
+                r#"<?cursor=page2>; rel="next"; results="true"; cursor="page2""#,
+            )
+            .with_response_file("releases/get-releases-page1.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/?sort=date&cursor=page2",
+            )
+            .with_response_header(
+                "Link",
+                r#"<?cursor=page3>; rel="next"; results="true"; cursor="page3""#,
+            )
+            .with_response_file("releases/get-releases-page2.json"),
+        )
+        .register_trycmd_test("releases/releases-list-since-pages.trycmd")
+        .with_default_token();
+}
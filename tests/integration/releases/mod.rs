@@ -1,10 +1,12 @@
 use crate::integration::TestManager;
 
 mod delete;
+mod files;
 mod finalize;
 mod info;
 mod list;
 mod new;
+mod set_commits;
 
 #[test]
 fn command_releases_help() {
@@ -24,6 +24,22 @@ fn creates_release() {
         .with_default_token();
 }
 
+#[test]
+fn expands_env_in_args() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "version": "v1.2.3-ci",
+                    "projects": ["wat-project"],
+                }))),
+        )
+        .register_trycmd_test("releases/releases-new-expand-env-in-args.trycmd")
+        .with_default_token();
+}
+
 #[test]
 fn allows_for_release_to_start_with_hyphen() {
     TestManager::new()
@@ -123,3 +139,114 @@ fn creates_release_which_is_instantly_finalized() {
         .register_trycmd_test("releases/releases-new-finalize.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn verifies_release_before_finalizing() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({ "version": "wat-release" }))),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/",
+            )
+            .with_response_file("releases/get-release-commits.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json")
+            .with_matcher(Matcher::Regex(format!(
+                r#""dateReleased":"{UTC_DATE_FORMAT}""#
+            ))),
+        )
+        .register_trycmd_test("releases/releases-new-finalize-verify.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn reports_unfinalized_release_if_verification_fails() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({ "version": "wat-release" }))),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/commits/",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "PUT",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/",
+            )
+            .with_response_file("releases/get-release.json")
+            .expect(0),
+        )
+        .register_trycmd_test("releases/releases-new-finalize-verify-empty.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn creates_release_with_date_created() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .with_matcher(Matcher::PartialJson(json!({
+                    "version": "wat-release",
+                    "dateCreated": "2019-03-14T10:00:00Z",
+                    "dateStarted": "2019-03-14T10:00:00Z",
+                }))),
+        )
+        .register_trycmd_test("releases/releases-new-date-created.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn warns_about_date_created_in_the_future() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json"),
+        )
+        .register_trycmd_test("releases/releases-new-date-created-future.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn rejects_invalid_date_created() {
+    TestManager::new()
+        .register_trycmd_test("releases/releases-new-date-created-invalid.trycmd")
+        .with_default_token();
+}
@@ -0,0 +1,80 @@
+use crate::integration::{MockEndpointBuilder, TestManager};
+
+#[test]
+fn command_releases_set_commits_dry_run() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+                .with_response_file("releases/get-repos.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .expect(0),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .expect(0),
+        )
+        .register_trycmd_test("releases/releases-set-commits-dry-run.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_releases_set_commits_fail_if_no_commits() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+                .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/releases/wat-release/previous-with-commits/",
+            )
+            .with_status(404)
+            .with_response_body("{}"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .expect(0),
+        )
+        .register_trycmd_test("releases/releases-set-commits-fail-if-no-commits.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_releases_set_commits_on_missing_git() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/repos/?cursor=")
+                .with_response_body("[]")
+                .expect(3),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(201)
+                .with_response_file("releases/get-release.json")
+                .expect(3),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/organizations/wat-org/releases/wat-release/previous-with-commits/",
+            )
+            .with_status(404)
+            .with_response_body("{}")
+            .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("PUT", "/api/0/organizations/wat-org/releases/wat-release/")
+                .expect(0),
+        )
+        .register_trycmd_test("releases/releases-set-commits-on-missing-git-*.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
@@ -17,3 +17,22 @@ fn command_send_event() {
         .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/"))
         .register_trycmd_test("send_event/*.trycmd");
 }
+
+#[cfg(not(windows))]
+#[test]
+fn command_send_event_tag_from_env() {
+    TestManager::new()
+        .mock_endpoint(MockEndpointBuilder::new("POST", "/api/1337/envelope/"))
+        .register_trycmd_test("send_event/tag_from_env/*.trycmd")
+        .env("CI_BRANCH", "main");
+}
+
+#[test]
+fn command_send_event_accepted_id() {
+    TestManager::new()
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/1337/envelope/")
+                .with_response_body(r#"{"id":"d0e1f2a3b4c54d6e8f708192a3b4c5d6"}"#),
+        )
+        .register_trycmd_test("send_event/accepted_id/*.trycmd");
+}
@@ -18,6 +18,59 @@ fn command_sourcemaps_inject_output() {
     TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject.trycmd");
 }
 
+#[test]
+fn command_sourcemaps_inject_dry_run_json() {
+    let testcase_cwd_path = "tests/integration/_cases/sourcemaps/sourcemaps-inject-dry-run.in/";
+    if std::path::Path::new(testcase_cwd_path).exists() {
+        remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    fs::create_dir_all(testcase_cwd_path).unwrap();
+    let files = [
+        "dummy_hosted.js",
+        "dummy_hosted.js.map",
+        "edge-runtime-webpack.js",
+        "edge-runtime-webpack.js.map",
+    ];
+    for file in files {
+        fs::copy(
+            format!("tests/integration/_fixtures/inject/server/{file}"),
+            format!("{testcase_cwd_path}{file}"),
+        )
+        .unwrap();
+    }
+
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject-dry-run.trycmd");
+
+    for file in files {
+        assert_eq!(
+            fs::read(format!("{testcase_cwd_path}{file}")).unwrap(),
+            fs::read(format!("tests/integration/_fixtures/inject/server/{file}")).unwrap(),
+            "{file} should not be modified in a dry run"
+        );
+    }
+}
+
+#[test]
+fn command_sourcemaps_inject_ignore_errors() {
+    let testcase_cwd_path =
+        "tests/integration/_cases/sourcemaps/sourcemaps-inject-ignore-errors.in/";
+    if std::path::Path::new(testcase_cwd_path).exists() {
+        remove_dir_all(testcase_cwd_path).unwrap();
+    }
+    copy_recursively(
+        "tests/integration/_fixtures/inject_ignore_errors/",
+        testcase_cwd_path,
+    )
+    .unwrap();
+
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-inject-ignore-errors.trycmd");
+
+    let good = fs::read_to_string(format!("{testcase_cwd_path}good.js")).unwrap();
+    assert!(good.contains("//# debugId="));
+    let broken = fs::read_to_string(format!("{testcase_cwd_path}broken.js")).unwrap();
+    assert!(!broken.contains("//# debugId="));
+}
+
 #[test]
 fn command_sourcemaps_inject_output_nomappings() {
     let testcase_cwd_path = "tests/integration/_cases/sourcemaps/sourcemaps-inject-nomappings.in/";
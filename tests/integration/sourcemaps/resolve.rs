@@ -9,3 +9,13 @@ fn command_sourcemaps_resolve_help() {
 fn command_sourcemaps_resolve() {
     TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-resolve.trycmd");
 }
+
+#[test]
+fn command_sourcemaps_resolve_context_lines() {
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-resolve-context-lines.trycmd");
+}
+
+#[test]
+fn command_sourcemaps_resolve_json() {
+    TestManager::new().register_trycmd_test("sourcemaps/sourcemaps-resolve-json.trycmd");
+}
@@ -26,6 +26,71 @@ fn command_sourcemaps_upload_successfully_upload_file() {
         .assert_mock_endpoints();
 }
 
+#[test]
+fn command_sourcemaps_upload_plain_progress() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Legacy, Default::default())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=&checksum=38ed853073df85147960ea3a5bced6170ec389b0",
+            )
+            .with_response_body("[]"),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-plain-progress.trycmd")
+        .env("SENTRY_NO_PROGRESS_BAR", "0")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_paths_from_allow_missing() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Legacy, Default::default())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=&checksum=38ed853073df85147960ea3a5bced6170ec389b0",
+            )
+            .with_response_body("[]"),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-paths-from-allow-missing.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_paths_from_missing() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Legacy, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-paths-from-missing.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_fail_on_empty_sourcemap() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Legacy, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-fail-on-empty-sourcemap.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_externalize_inline_maps() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-externalize-inline-maps.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_keep_inline_maps() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-keep-inline-maps.trycmd")
+        .with_default_token();
+}
+
 #[test]
 fn command_sourcemaps_upload_skip_already_uploaded() {
     TestManager::new()
@@ -51,6 +116,59 @@ fn command_sourcemaps_upload_skip_already_uploaded() {
         .assert_mock_endpoints();
 }
 
+#[test]
+fn command_sourcemaps_upload_since_release() {
+    // Only the sourcemap with a debug id is reused from the prior release,
+    // the other one has to be uploaded again.
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/old-release/files/?cursor=&checksum=a6758cdde03d5ab0e09f3663d7b1b755e760786a",
+            )
+            .with_response_body(
+                r#"[{
+                    "id": "1337",
+                    "name": "~/1.js.map",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "a6758cdde03d5ab0e09f3663d7b1b755e760786a",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }]"#,
+            ),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-since-release.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_json() {
+    // The files of both paths are reported in one document.
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/old-release/files/?cursor=&checksum=a6758cdde03d5ab0e09f3663d7b1b755e760786a",
+            )
+            .with_response_body(
+                r#"[{
+                    "id": "1337",
+                    "name": "~/1.js.map",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "a6758cdde03d5ab0e09f3663d7b1b755e760786a",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }]"#,
+            ),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-json.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
 #[test]
 fn command_sourcemaps_upload_no_dedupe() {
     TestManager::new()
@@ -85,6 +203,23 @@ fn command_sourcemaps_upload_modern_v2() {
         .assert_mock_endpoints();
 }
 
+#[test]
+fn command_sourcemaps_upload_prefetch_missing_chunks() {
+    // Both chunks fit into the prefetched first batch, so the missing chunk
+    // reported by the server must not be uploaded a second time.
+    TestManager::new()
+        .mock_common_upload_endpoints(
+            ServerBehavior::ModernV2,
+            ChunkOptions {
+                missing_chunks: vec!["ec8450a9db19805703a27a2545c18b7b27ba0d7d".to_string()],
+                chunk_size: 512,
+            },
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-prefetch-missing-chunks.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
 #[test]
 fn command_sourcemaps_upload_empty() {
     TestManager::new()
@@ -176,6 +311,38 @@ fn command_sourcemaps_upload_cjs_mjs() {
         .with_default_token();
 }
 
+#[test]
+fn command_sourcemaps_upload_webpack_manifest() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-webpack-manifest.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_dist_from_filename() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-dist-from-filename.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_rewrite_sources() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-rewrite-sources.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_artifact_types() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-artifact-types.trycmd")
+        .with_default_token();
+}
+
 #[test]
 fn command_sourcemaps_upload_complex_extension() {
     TestManager::new()
@@ -183,3 +350,75 @@ fn command_sourcemaps_upload_complex_extension() {
         .register_trycmd_test("sourcemaps/sourcemaps-upload-complex-extension.trycmd")
         .with_default_token();
 }
+
+#[test]
+fn command_sourcemaps_upload_below_chunked_threshold() {
+    let manager = TestManager::new();
+    let chunk_upload_response = format!(
+        r#"{{
+            "url": "{}/api/0/organizations/wat-org/chunk-upload/",
+            "chunkSize": 8388608,
+            "chunksPerRequest": 64,
+            "maxRequestSize": 33554432,
+            "concurrency": 8,
+            "hashAlgorithm": "sha1",
+            "accept": ["release_files", "artifact_bundles_v2"]
+        }}"#,
+        manager.server_url(),
+    );
+
+    manager
+        .mock_endpoint(
+            MockEndpointBuilder::new("GET", "/api/0/organizations/wat-org/chunk-upload/")
+                .with_response_body(chunk_upload_response),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new("POST", "/api/0/projects/wat-org/wat-project/releases/")
+                .with_status(208)
+                .with_response_file("releases/get-release.json")
+                .expect(1),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "GET",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/?cursor=",
+            )
+            .with_response_body("[]"),
+        )
+        .mock_endpoint(
+            MockEndpointBuilder::new(
+                "POST",
+                "/api/0/projects/wat-org/wat-project/releases/wat-release/files/",
+            )
+            .with_status(201)
+            .with_response_body(
+                r#"{
+                    "id": "1337",
+                    "name": "~/bundle.min.js.map",
+                    "headers": {},
+                    "size": 1522,
+                    "sha1": "38ed853073df85147960ea3a5bced6170ec389b0",
+                    "dateCreated": "2022-05-12T11:08:01.496220Z"
+                }"#,
+            )
+            .expect(1),
+        )
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-below-chunked-threshold.trycmd")
+        .with_default_token()
+        .assert_mock_endpoints();
+}
+
+#[test]
+fn command_sourcemaps_upload_unreachable() {
+    TestManager::new()
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-unreachable.trycmd")
+        .with_default_token();
+}
+
+#[test]
+fn command_sourcemaps_upload_url_prefix_validation() {
+    TestManager::new()
+        .mock_common_upload_endpoints(ServerBehavior::Modern, Default::default())
+        .register_trycmd_test("sourcemaps/sourcemaps-upload-url-prefix-*.trycmd")
+        .with_default_token();
+}
@@ -33,6 +33,8 @@ pub fn set(server_info: MockServerInfo, mut setter: impl FnMut(&str, Cow<str>))
     setter("SENTRY_URL", server_info.url.into());
     setter("SENTRY_DSN", dsn);
     setter("RUST_BACKTRACE", "0".into());
+    // Progress output depends on timing, so it is disabled unless a test opts in.
+    setter("SENTRY_NO_PROGRESS_BAR", "1".into());
 }
 
 /// Set the auth token environment variable using the provided setter function.
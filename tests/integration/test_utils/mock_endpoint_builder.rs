@@ -32,6 +32,13 @@ impl MockEndpointBuilder {
         self
     }
 
+    /// Add a header to the response of the mock endpoint.
+    pub fn with_response_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        self.builder = Box::new(move |server| (self.builder)(server).with_header(name, &value));
+        self
+    }
+
     /// Set the response body of the mock endpoint.
     pub fn with_response_body<T>(mut self, body: T) -> Self
     where
@@ -1,4 +1,6 @@
-use crate::integration::{MockEndpointBuilder, TestManager};
+use std::fs;
+
+use crate::integration::{AssertCommand, MockEndpointBuilder, TestManager};
 
 #[test]
 fn command_upload_proguard() {
@@ -15,3 +17,60 @@ fn command_upload_proguard() {
 fn command_upload_proguard_no_upload_no_auth_token() {
     TestManager::new().register_trycmd_test("upload_proguard/upload_proguard-no-upload.trycmd");
 }
+
+#[test]
+fn command_upload_proguard_input_encoding() {
+    let utf8_uuid = proguard_uuids(&["mapping-utf8.txt"]);
+    // The UTF-16 mapping is detected from its byte order mark.
+    let transcoded_uuids = proguard_uuids(&[
+        "--input-encoding",
+        "latin1",
+        "mapping-latin1.txt",
+        "mapping-utf16.txt",
+    ]);
+
+    // Once transcoded, the mappings are identical and have the same UUID.
+    assert_eq!(transcoded_uuids, [utf8_uuid.as_str(); 2].join("|"));
+}
+
+#[test]
+fn command_upload_proguard_normalize_line_endings() {
+    let utf8_uuid = proguard_uuids(&["mapping-utf8.txt"]);
+    let crlf_uuid = proguard_uuids(&["mapping-crlf.txt"]);
+    let normalized_uuid = proguard_uuids(&["--normalize-line-endings", "mapping-crlf.txt"]);
+
+    // Line endings and trailing whitespace change the UUID unless normalized.
+    assert_ne!(crlf_uuid, utf8_uuid);
+    assert_eq!(normalized_uuid, utf8_uuid);
+}
+
+/// Runs `upload-proguard --no-upload` on the given fixtures of the
+/// `proguard_encoding` directory and returns the UUIDs it writes.
+fn proguard_uuids(args: &[&str]) -> String {
+    let dir = tempfile::tempdir().expect("temp dir should be created");
+    let properties = dir.path().join("sentry-debug-meta.properties");
+
+    let args = args.iter().map(|arg| {
+        if arg.starts_with("mapping-") {
+            format!("tests/integration/_fixtures/proguard_encoding/{arg}")
+        } else {
+            arg.to_string()
+        }
+    });
+    TestManager::new()
+        .assert_cmd(
+            ["upload-proguard", "--no-upload", "--write-properties"]
+                .into_iter()
+                .map(String::from)
+                .chain([properties.display().to_string()])
+                .chain(args),
+        )
+        .run_and_assert(AssertCommand::Success);
+
+    let properties = fs::read_to_string(properties).expect("properties should be written");
+    properties
+        .lines()
+        .find_map(|line| line.strip_prefix("io.sentry.ProguardUuids="))
+        .expect("properties should contain the UUIDs")
+        .to_owned()
+}